@@ -0,0 +1,257 @@
+use anyhow::Result;
+use console::style;
+
+use crate::utils;
+use crate::utils::errors::ErrorKind;
+use crate::{Config, Repository};
+
+/// Print a setting, or the whole config when no key is given
+pub fn get(key: Option<String>, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let config = repo.config()?;
+
+    let Some(key) = key else {
+        if json {
+            return print_json(&config);
+        }
+        println!("{}", serde_json::to_string_pretty(&config)?);
+        return Ok(());
+    };
+
+    let value = config.get_value(&key)?;
+
+    if json {
+        return utils::json::print(&serde_json::json!({ "key": key, "value": value }));
+    }
+
+    match value {
+        serde_json::Value::String(s) => println!("{}", s),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                println!("{}", item.as_str().unwrap_or_default());
+            }
+        }
+        serde_json::Value::Null => {}
+        other => println!("{}", other),
+    }
+
+    Ok(())
+}
+
+/// Change a setting
+pub fn set(key: String, value: String, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let mut config = repo.config()?;
+    config.set_value(&key, &value)?;
+    repo.write_config(&config)?;
+
+    if json {
+        return print_json(&config);
+    }
+
+    utils::success(&format!(
+        "Set {} to {}",
+        style(&key).bold(),
+        config.get_value(&key)?
+    ));
+
+    Ok(())
+}
+
+/// Add a status, optionally at a 1-based position in the workflow
+pub fn add_status(name: String, position: Option<usize>, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let mut config = repo.config()?;
+    config.add_status(&name, position_index(position)?)?;
+
+    repo.store().create_status_dir(&name)?;
+    repo.write_config(&config)?;
+
+    if json {
+        return print_json(&config);
+    }
+
+    utils::success(&format!("Added status: {}", utils::status_badge(&name)));
+    print_list("Statuses", &config.statuses);
+
+    Ok(())
+}
+
+/// Remove a status, moving its tasks elsewhere if asked to
+pub fn remove_status(name: String, move_to: Option<String>, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let mut config = repo.config()?;
+    let store = repo.store();
+
+    if let Some(target) = &move_to {
+        if target == &name || !config.statuses.contains(target) {
+            let others: Vec<String> = config
+                .statuses
+                .iter()
+                .filter(|s| *s != &name)
+                .cloned()
+                .collect();
+            return Err(utils::errors::invalid_status(target, &others));
+        }
+    }
+
+    // Validate before touching any files
+    let mut updated = config.clone();
+    updated.remove_status(&name)?;
+
+    let files = store.status_files(&name);
+    let mut moved = 0;
+    if !files.is_empty() {
+        let Some(target) = &move_to else {
+            return Err(utils::errors::error(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Status '{}' still has {} task(s). Move them first, or pass --move-to <STATUS>",
+                    name,
+                    files.len()
+                ),
+            ));
+        };
+        moved = store.migrate_status(&name, target)?;
+    }
+
+    config = updated;
+    repo.write_config(&config)?;
+
+    let removed_dir = store.remove_status_dir(&name)?;
+
+    if json {
+        return print_json(&config);
+    }
+
+    utils::success(&format!("Removed status: {}", style(&name).bold()));
+    if let Some(target) = &move_to {
+        if moved > 0 {
+            println!(
+                "  Moved {} task(s) to {}",
+                moved,
+                utils::status_badge(target)
+            );
+        }
+    }
+    if !removed_dir {
+        utils::warning(&format!(
+            "Left {} in place because it still contains other files",
+            store.status_dir(&name).display()
+        ));
+    }
+    print_list("Statuses", &config.statuses);
+
+    Ok(())
+}
+
+/// Rename a status, moving its directory along with it
+pub fn rename_status(old: String, new: String, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let mut config = repo.config()?;
+    config.rename_status(&old, &new)?;
+
+    repo.store().rename_status_dir(&old, &new)?;
+    repo.write_config(&config)?;
+
+    if json {
+        return print_json(&config);
+    }
+
+    utils::success(&format!(
+        "Renamed status: {} {} {}",
+        style(&old).bold(),
+        style("→").dim(),
+        utils::status_badge(&new)
+    ));
+    print_list("Statuses", &config.statuses);
+
+    Ok(())
+}
+
+/// Add a priority, optionally at a 1-based position (lowest first)
+pub fn add_priority(name: String, position: Option<usize>, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let mut config = repo.config()?;
+    config.add_priority(&name, position_index(position)?)?;
+    repo.write_config(&config)?;
+
+    if json {
+        return print_json(&config);
+    }
+
+    utils::success(&format!("Added priority: {}", utils::priority_badge(&name)));
+    print_list("Priorities", &config.priorities);
+
+    Ok(())
+}
+
+/// Remove a priority
+///
+/// Tasks keep their priority in frontmatter, so this only warns about them.
+pub fn remove_priority(name: String, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let mut config = repo.config()?;
+    config.remove_priority(&name)?;
+    repo.write_config(&config)?;
+
+    if json {
+        return print_json(&config);
+    }
+
+    utils::success(&format!("Removed priority: {}", style(&name).bold()));
+
+    let in_use = repo
+        .store()
+        .all()?
+        .iter()
+        .filter(|task| task.priority.as_deref() == Some(name.as_str()))
+        .count();
+    if in_use > 0 {
+        utils::warning(&format!(
+            "{} task(s) still have priority '{}'",
+            in_use, name
+        ));
+    }
+    print_list("Priorities", &config.priorities);
+
+    Ok(())
+}
+
+/// Convert a 1-based position from the command line to an index
+fn position_index(position: Option<usize>) -> Result<Option<usize>> {
+    match position {
+        Some(0) => Err(utils::errors::error(
+            ErrorKind::InvalidInput,
+            "Position starts at 1",
+        )),
+        Some(position) => Ok(Some(position - 1)),
+        None => Ok(None),
+    }
+}
+
+fn print_list(label: &str, items: &[String]) {
+    println!(
+        "  {} {}",
+        style(format!("{}:", label)).dim(),
+        items.join(", ")
+    );
+}
+
+fn print_json(config: &Config) -> Result<()> {
+    utils::json::print(&serde_json::json!({ "config": config }))
+}
@@ -0,0 +1,24 @@
+use console::style;
+
+use crate::utils;
+use crate::Task;
+
+pub use crate::models::dependencies::ensure_dependencies_met;
+
+/// Tell the user which tasks became actionable
+pub fn report_unblocked(unblocked: &[Task]) {
+    if unblocked.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", style("Now unblocked:").bold());
+    for t in unblocked {
+        println!(
+            "  {} {} {}",
+            style("•").green(),
+            utils::task_id(&t.id),
+            t.title
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use console::style;
+
+use crate::models::doctor::{diagnose, repair, Problem};
+use crate::utils;
+use crate::utils::errors::ErrorKind;
+use crate::Repository;
+
+/// Check the task repository for problems, optionally repairing the safe ones
+pub fn doctor(fix: bool, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let config = repo.config()?;
+    let store = repo.store();
+
+    let mut problems = diagnose(&store, &config);
+    let mut fixed = Vec::new();
+
+    if fix {
+        for problem in &problems {
+            if repair(problem)? {
+                fixed.push(problem.clone());
+            }
+        }
+        if !fixed.is_empty() {
+            problems = diagnose(&store, &config);
+        }
+    }
+
+    if json {
+        utils::json::print(&serde_json::json!({
+            "problems": problems,
+            "fixed": fixed,
+        }))?;
+    } else {
+        print_report(&problems, &fixed, fix);
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    Err(utils::errors::error(
+        ErrorKind::Unhealthy,
+        format!("Found {} problem(s)", problems.len()),
+    ))
+}
+
+fn print_report(problems: &[Problem], fixed: &[Problem], fix: bool) {
+    for problem in fixed {
+        if let Some(repair) = &problem.fix {
+            utils::success(&format!(
+                "Fixed {}: {}",
+                style(problem.path.display()).dim(),
+                repair
+            ));
+        }
+    }
+    if !fixed.is_empty() {
+        println!();
+    }
+
+    if problems.is_empty() {
+        utils::success("No problems found");
+        return;
+    }
+
+    println!("Found {} problem(s):", style(problems.len()).bold());
+    println!();
+
+    for problem in problems {
+        println!(
+            "  {} {}",
+            style(problem.kind.label()).yellow().bold(),
+            problem.path.display()
+        );
+        for line in problem.message.lines() {
+            println!("    {}", line);
+        }
+        if let Some(repair) = &problem.fix {
+            println!("    {}", style(format!("Fix: {}", repair)).dim());
+        }
+    }
+
+    let fixable = problems.iter().filter(|p| p.fix.is_some()).count();
+    if fixable > 0 && !fix {
+        println!();
+        println!(
+            "Run {} to fix {} of them",
+            style("tasks doctor --fix").bold(),
+            fixable
+        );
+    }
+}
@@ -0,0 +1,507 @@
+use anyhow::Result;
+use console::style;
+use std::collections::BTreeSet;
+
+use super::resolve_task;
+use crate::utils;
+use crate::utils::errors::ErrorKind;
+use crate::{DependencyGraph, Repository, Task};
+
+/// Output formats supported by `tasks graph`
+const FORMATS: [&str; 3] = ["tree", "dot", "mermaid"];
+
+/// Render the task dependency graph
+pub fn graph(
+    format: Option<String>,
+    status: Option<String>,
+    tag: Option<String>,
+    root: Option<String>,
+) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let config = repo.config()?;
+    let format = format.unwrap_or_else(|| "tree".to_string());
+
+    // Validate format
+    if !FORMATS.contains(&format.as_str()) {
+        return Err(utils::errors::error(
+            ErrorKind::InvalidInput,
+            format!(
+                "Invalid format '{}'. Valid formats: {}",
+                format,
+                FORMATS.join(", ")
+            ),
+        ));
+    }
+
+    // Validate status if provided
+    if let Some(ref s) = status {
+        if !config.statuses.contains(s) {
+            return Err(utils::errors::invalid_status(s, &config.statuses));
+        }
+    }
+
+    let store = repo.store();
+    let graph = store.dependency_graph()?;
+
+    let root = match root {
+        Some(reference) => Some(resolve_task(&store, &reference)?),
+        None => None,
+    };
+
+    let subgraph = Subgraph::select(&graph, status.as_deref(), tag.as_deref(), root.as_ref());
+
+    // Problems go to stderr so DOT/Mermaid output stays pipeable
+    for cycle in graph.cycles() {
+        eprintln!(
+            "{} Dependency cycle: {}",
+            style("⚠").yellow().bold(),
+            cycle.join(" → ")
+        );
+    }
+    for dangling in graph.dangling_references() {
+        eprintln!(
+            "{} {} lists unknown task '{}' in {}",
+            style("⚠").yellow().bold(),
+            dangling.task_id,
+            dangling.reference,
+            dangling.field.key()
+        );
+    }
+    for (id, tasks) in graph.shared_ids() {
+        let slugs: Vec<&str> = tasks.iter().map(|t| t.slug.as_str()).collect();
+        eprintln!(
+            "{} ID {} is shared by {} tasks ({}); references can't tell them apart",
+            style("⚠").yellow().bold(),
+            id,
+            tasks.len(),
+            slugs.join(", ")
+        );
+    }
+
+    let output = match format.as_str() {
+        "dot" => render_dot(&subgraph),
+        "mermaid" => render_mermaid(&subgraph),
+        _ => {
+            if subgraph.nodes.is_empty() {
+                println!("No task dependencies found");
+                return Ok(());
+            }
+            render_tree(&subgraph)
+        }
+    };
+
+    print!("{}", output);
+
+    Ok(())
+}
+
+/// The part of the dependency graph selected for rendering
+struct Subgraph<'a> {
+    nodes: Vec<&'a Task>,
+    /// Edges as `(dependent, dependency)` pairs
+    edges: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Subgraph<'a> {
+    /// Select tasks by status, tag and connection to a root task
+    ///
+    /// Without a root, tasks that have no dependencies in either direction are
+    /// left out so the output only shows actual relationships.
+    fn select(
+        graph: &'a DependencyGraph,
+        status: Option<&str>,
+        tag: Option<&str>,
+        root: Option<&Task>,
+    ) -> Self {
+        let connected: Option<BTreeSet<&str>> = root.map(|root| {
+            let mut ids: BTreeSet<&str> = graph
+                .transitive_dependencies_of(&root.id)
+                .into_iter()
+                .collect();
+            ids.extend(graph.transitive_dependents_of(&root.id));
+            ids
+        });
+
+        let ids: BTreeSet<&str> = graph
+            .tasks()
+            .filter(|t| {
+                let is_root = root.is_some_and(|r| r.id == t.id);
+                let matches_filters = status.is_none_or(|s| t.status == s)
+                    && tag.is_none_or(|tag| t.tags.iter().flatten().any(|x| x == tag));
+                let is_connected = connected.as_ref().is_none_or(|c| c.contains(t.id.as_str()));
+                is_root || (matches_filters && is_connected)
+            })
+            .map(|t| t.id.as_str())
+            .collect();
+
+        let edges: Vec<(&str, &str)> = graph
+            .edges()
+            .filter(|(dependent, dependency)| ids.contains(dependent) && ids.contains(dependency))
+            .collect();
+
+        let linked: BTreeSet<&str> = edges.iter().flat_map(|(a, b)| [*a, *b]).collect();
+
+        let nodes = ids
+            .into_iter()
+            .filter(|id| root.is_some() || linked.contains(id))
+            .filter_map(|id| graph.task(id))
+            .collect();
+
+        Subgraph { nodes, edges }
+    }
+
+    fn task(&self, id: &str) -> Option<&'a Task> {
+        self.nodes.iter().find(|t| t.id == id).copied()
+    }
+
+    fn dependencies_of(&self, id: &str) -> Vec<&'a str> {
+        self.edges
+            .iter()
+            .filter(|(dependent, _)| *dependent == id)
+            .map(|(_, dependency)| *dependency)
+            .collect()
+    }
+
+    fn has_dependents(&self, id: &str) -> bool {
+        self.edges.iter().any(|(_, dependency)| *dependency == id)
+    }
+}
+
+/// Fill colour for a status node (DOT and Mermaid)
+fn status_color(status: &str) -> &'static str {
+    match status {
+        "todo" => "#d0ebff",
+        "in-progress" => "#fff3bf",
+        "testing" => "#f3d9fa",
+        "done" => "#d3f9d8",
+        _ => "#f1f3f5",
+    }
+}
+
+/// Border colour and width for a priority (DOT and Mermaid)
+fn priority_stroke(priority: Option<&str>) -> (&'static str, u8) {
+    match priority {
+        Some("Critical") => ("#e03131", 3),
+        Some("High") => ("#f08c00", 2),
+        Some("Medium") => ("#1098ad", 1),
+        Some("Low") => ("#2f9e44", 1),
+        _ => ("#868e96", 1),
+    }
+}
+
+/// Render the subgraph as Graphviz DOT
+///
+/// Edges point from a dependency to the task it blocks, so the graph reads in
+/// the order work has to happen.
+fn render_dot(subgraph: &Subgraph) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+
+    let mut out = String::from("digraph tasks {\n");
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n\n");
+
+    for task in &subgraph.nodes {
+        let (stroke, width) = priority_stroke(task.priority.as_deref());
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\\n{} · {}\", fillcolor=\"{}\", color=\"{}\", penwidth={}];\n",
+            escape(&task.id),
+            escape(&task.title),
+            escape(&task.id),
+            escape(&task.status),
+            status_color(&task.status),
+            stroke,
+            width
+        ));
+    }
+
+    if !subgraph.edges.is_empty() {
+        out.push('\n');
+    }
+    for (dependent, dependency) in &subgraph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\";\n",
+            escape(dependency),
+            escape(dependent)
+        ));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Render the subgraph as a Mermaid flowchart
+fn render_mermaid(subgraph: &Subgraph) -> String {
+    // Mermaid node and class IDs must be plain identifiers
+    let ident = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    };
+    let node_id = |id: &str| format!("t{}", ident(id));
+    let class_name = |status: &str| format!("status_{}", ident(status));
+    let escape = |s: &str| s.replace('"', "#quot;");
+
+    let mut out = String::from("flowchart LR\n");
+
+    for task in &subgraph.nodes {
+        out.push_str(&format!(
+            "  {}[\"{}<br/><small>{} · {}</small>\"]\n",
+            node_id(&task.id),
+            escape(&task.title),
+            escape(&task.id),
+            escape(&task.status)
+        ));
+    }
+
+    for (dependent, dependency) in &subgraph.edges {
+        out.push_str(&format!(
+            "  {} --> {}\n",
+            node_id(dependency),
+            node_id(dependent)
+        ));
+    }
+
+    let statuses: BTreeSet<&str> = subgraph.nodes.iter().map(|t| t.status.as_str()).collect();
+    for status in statuses {
+        out.push_str(&format!(
+            "  classDef {} fill:{},color:#212529\n",
+            class_name(status),
+            status_color(status)
+        ));
+    }
+
+    for task in &subgraph.nodes {
+        let (stroke, width) = priority_stroke(task.priority.as_deref());
+        out.push_str(&format!(
+            "  class {} {}\n",
+            node_id(&task.id),
+            class_name(&task.status)
+        ));
+        out.push_str(&format!(
+            "  style {} stroke:{},stroke-width:{}px\n",
+            node_id(&task.id),
+            stroke,
+            width
+        ));
+    }
+
+    out
+}
+
+/// Render the subgraph as an ASCII tree of dependencies
+///
+/// Top-level entries are tasks nothing else depends on; each task lists the
+/// tasks it depends on beneath it. Tasks reachable from several parents are
+/// expanded once and referenced afterwards.
+fn render_tree(subgraph: &Subgraph) -> String {
+    let mut out = String::new();
+    let mut expanded = BTreeSet::new();
+
+    let mut roots: Vec<&str> = subgraph
+        .nodes
+        .iter()
+        .map(|t| t.id.as_str())
+        .filter(|id| !subgraph.has_dependents(id))
+        .collect();
+
+    // Tasks that only appear inside cycles have no natural root
+    for task in &subgraph.nodes {
+        if roots.is_empty() || !reachable_from(subgraph, &roots, &task.id) {
+            roots.push(&task.id);
+        }
+    }
+
+    for id in roots {
+        tree_node(
+            subgraph,
+            id,
+            "",
+            None,
+            &mut expanded,
+            &mut Vec::new(),
+            &mut out,
+        );
+    }
+
+    out
+}
+
+/// Check whether `target` appears under any of the given roots
+fn reachable_from(subgraph: &Subgraph, roots: &[&str], target: &str) -> bool {
+    let mut stack: Vec<&str> = roots.to_vec();
+    let mut seen = BTreeSet::new();
+
+    while let Some(id) = stack.pop() {
+        if id == target {
+            return true;
+        }
+        if seen.insert(id) {
+            stack.extend(subgraph.dependencies_of(id));
+        }
+    }
+
+    false
+}
+
+fn tree_node<'a>(
+    subgraph: &Subgraph<'a>,
+    id: &'a str,
+    prefix: &str,
+    is_last: Option<bool>,
+    expanded: &mut BTreeSet<&'a str>,
+    path: &mut Vec<&'a str>,
+    out: &mut String,
+) {
+    let (branch, child_prefix) = match is_last {
+        None => (String::new(), String::new()),
+        Some(true) => (format!("{}└── ", prefix), format!("{}    ", prefix)),
+        Some(false) => (format!("{}├── ", prefix), format!("{}│   ", prefix)),
+    };
+
+    let label = match subgraph.task(id) {
+        Some(task) => format!(
+            "{} {} {} [{}]",
+            utils::priority_badge(task.priority.as_deref().unwrap_or("Medium")),
+            utils::task_id(&task.id),
+            task.title,
+            utils::status_badge(&task.status)
+        ),
+        None => id.to_string(),
+    };
+
+    if path.contains(&id) {
+        out.push_str(&format!("{}{} {}\n", branch, label, style("(cycle)").red()));
+        return;
+    }
+
+    let dependencies = subgraph.dependencies_of(id);
+
+    if !expanded.insert(id) && !dependencies.is_empty() {
+        out.push_str(&format!(
+            "{}{} {}\n",
+            branch,
+            label,
+            style("(see above)").dim()
+        ));
+        return;
+    }
+
+    out.push_str(&format!("{}{}\n", branch, label));
+
+    path.push(id);
+    for (i, dependency) in dependencies.iter().enumerate() {
+        let last = i + 1 == dependencies.len();
+        tree_node(
+            subgraph,
+            dependency,
+            &child_prefix,
+            Some(last),
+            expanded,
+            path,
+            out,
+        );
+    }
+    path.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, status: &str, priority: &str, depends_on: &[&str]) -> Task {
+        let mut task = Task::new(format!("Task {}", id), priority.to_string());
+        task.id = id.to_string();
+        task.slug = format!("task-{}", id);
+        task.status = status.to_string();
+        if !depends_on.is_empty() {
+            task.depends_on = Some(depends_on.iter().map(|s| s.to_string()).collect());
+        }
+        task
+    }
+
+    fn sample_graph() -> DependencyGraph {
+        DependencyGraph::build(vec![
+            task("a", "todo", "High", &["b", "c"]),
+            task("b", "done", "Low", &["c"]),
+            task("c", "in-progress", "Critical", &[]),
+            task("d", "todo", "Medium", &[]),
+        ])
+    }
+
+    #[test]
+    fn test_select_skips_unlinked_tasks() {
+        let graph = sample_graph();
+        let subgraph = Subgraph::select(&graph, None, None, None);
+
+        let ids: Vec<&str> = subgraph.nodes.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(subgraph.edges.len(), 3);
+    }
+
+    #[test]
+    fn test_select_by_status_and_root() {
+        let graph = sample_graph();
+
+        let subgraph = Subgraph::select(&graph, Some("done"), None, None);
+        assert!(subgraph.nodes.is_empty());
+
+        let root = graph.task("b").unwrap().clone();
+        let subgraph = Subgraph::select(&graph, None, None, Some(&root));
+        let ids: Vec<&str> = subgraph.nodes.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+
+        let root = graph.task("d").unwrap().clone();
+        let subgraph = Subgraph::select(&graph, None, None, Some(&root));
+        assert_eq!(subgraph.nodes.len(), 1);
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let graph = sample_graph();
+        let dot = render_dot(&Subgraph::select(&graph, None, None, None));
+
+        assert!(dot.starts_with("digraph tasks {"));
+        assert!(dot.contains("\"c\" -> \"a\";"));
+        assert!(dot.contains("\"c\" -> \"b\";"));
+        assert!(dot.contains("fillcolor=\"#d3f9d8\""));
+        assert!(dot.contains("color=\"#e03131\", penwidth=3"));
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        let graph = sample_graph();
+        let mermaid = render_mermaid(&Subgraph::select(&graph, None, None, None));
+
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("  tb --> ta\n"));
+        assert!(mermaid.contains("classDef status_in_progress fill:#fff3bf"));
+        assert!(mermaid.contains("class tc status_in_progress"));
+        assert!(mermaid.contains("style tc stroke:#e03131,stroke-width:3px"));
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let graph = sample_graph();
+        let tree = render_tree(&Subgraph::select(&graph, None, None, None));
+        let lines: Vec<&str> = tree.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains("Task a"));
+        assert!(lines[1].starts_with("├── ") && lines[1].contains("Task b"));
+        assert!(lines[2].starts_with("│   └── ") && lines[2].contains("Task c"));
+        assert!(lines[3].starts_with("└── ") && lines[3].contains("Task c"));
+    }
+
+    #[test]
+    fn test_render_tree_with_cycle() {
+        let graph = DependencyGraph::build(vec![
+            task("a", "todo", "High", &["b"]),
+            task("b", "todo", "High", &["a"]),
+        ]);
+        let tree = render_tree(&Subgraph::select(&graph, None, None, None));
+
+        assert!(tree.contains("(cycle)"));
+    }
+}
@@ -0,0 +1,318 @@
+use anyhow::{Context, Result};
+use console::style;
+use git2::{Oid, Sort};
+use serde::Serialize;
+
+use super::dependencies::ensure_dependencies_met;
+use crate::hooks::history::{self, PlannedLink, PlannedMove, ScanPlan, ScanState, ScannedCommit};
+use crate::utils;
+use crate::utils::errors::ErrorKind;
+use crate::{Config, Repository, TaskStore};
+
+/// Commits picked out for a scan
+struct Range {
+    commits: Vec<ScannedCommit>,
+    /// Newest commit in the range
+    tip: Option<Oid>,
+    /// What was scanned, for the report
+    description: String,
+}
+
+/// A planned move and what became of it
+#[derive(Serialize)]
+struct MoveResult<'a> {
+    #[serde(flatten)]
+    planned: &'a PlannedMove,
+    moved: bool,
+    error: Option<String>,
+}
+
+/// A planned link and what became of it
+#[derive(Serialize)]
+struct LinkResult<'a> {
+    #[serde(flatten)]
+    planned: &'a PlannedLink,
+    linked: bool,
+    error: Option<String>,
+}
+
+/// Apply the keywords in past commit messages to the tasks
+///
+/// Without a range, scans from the commit the last `--apply` run stopped at
+/// (or all of history) up to `HEAD`, and `--apply` remembers where it stopped.
+/// If any change can't be applied the marker stays put, so the next run
+/// retries those commits, and the scan fails.
+pub fn scan(range: Option<String>, apply: bool, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+
+    let tasks = Repository::current()?;
+    let config = tasks.config()?;
+    let store = tasks.store();
+    let repo = tasks.git()?;
+    let mut state = ScanState::load(&tasks)?;
+
+    let scanned = match &range {
+        Some(spec) => explicit_range(&repo, spec)?,
+        None => incremental_range(&repo, state.last_scanned.as_deref(), json)?,
+    };
+
+    let plan = history::plan(&scanned.commits, &config, &store.all()?)?;
+
+    let mut results: Vec<MoveResult> = plan
+        .moves
+        .iter()
+        .map(|planned| MoveResult {
+            planned,
+            moved: false,
+            error: None,
+        })
+        .collect();
+
+    let mut link_results: Vec<LinkResult> = plan
+        .links
+        .iter()
+        .map(|planned| LinkResult {
+            planned,
+            linked: false,
+            error: None,
+        })
+        .collect();
+
+    if apply {
+        for result in &mut results {
+            match apply_move(&store, &config, result.planned) {
+                Ok(()) => result.moved = true,
+                Err(e) => result.error = Some(utils::errors::summary(&e)),
+            }
+        }
+        for result in &mut link_results {
+            match apply_link(&store, result.planned) {
+                Ok(()) => result.linked = true,
+                Err(e) => result.error = Some(utils::errors::summary(&e)),
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count()
+        + link_results.iter().filter(|r| r.error.is_some()).count();
+
+    // Explicit ranges are one-off scans and don't move the marker
+    if apply && failed == 0 {
+        if let (None, Some(tip)) = (&range, scanned.tip) {
+            state.last_scanned = Some(tip.to_string());
+            state.save(&tasks)?;
+        }
+    }
+
+    if json {
+        utils::json::print(&serde_json::json!({
+            "range": scanned.description,
+            "commits": scanned.commits.len(),
+            "applied": apply,
+            "moves": results,
+            "links": link_results,
+            "last_scanned": state.last_scanned,
+        }))?;
+    } else {
+        print_report(&scanned, &plan, &results, &link_results, apply);
+    }
+
+    if failed == 0 {
+        return Ok(());
+    }
+
+    Err(utils::errors::error(
+        ErrorKind::General,
+        format!(
+            "Couldn't apply {} change(s); the next scan will try those commits again",
+            failed
+        ),
+    ))
+}
+
+/// Commits in a range like `v1.0..HEAD`, or reachable from a single revision
+fn explicit_range(repo: &git2::Repository, spec: &str) -> Result<Range> {
+    let mut walk = repo.revwalk()?;
+
+    let tip = if spec.contains("..") {
+        walk.push_range(spec)
+            .context(format!("Invalid revision range '{}'", spec))?;
+        repo.revparse(spec)?.to().map(|to| to.id())
+    } else {
+        let commit = repo
+            .revparse_single(spec)
+            .and_then(|object| object.peel_to_commit())
+            .context(format!("Invalid revision '{}'", spec))?;
+        walk.push(commit.id())?;
+        Some(commit.id())
+    };
+
+    Ok(Range {
+        commits: collect(repo, walk)?,
+        tip,
+        description: spec.to_string(),
+    })
+}
+
+/// Commits since the last applied scan, up to `HEAD`
+fn incremental_range(
+    repo: &git2::Repository,
+    last_scanned: Option<&str>,
+    quiet: bool,
+) -> Result<Range> {
+    // Nothing has been committed yet
+    let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) else {
+        return Ok(Range {
+            commits: Vec::new(),
+            tip: None,
+            description: "HEAD".to_string(),
+        });
+    };
+
+    let mut walk = repo.revwalk()?;
+    walk.push(head.id())?;
+
+    let mut description = "HEAD".to_string();
+    if let Some(last) = last_scanned {
+        let known = Oid::from_str(last)
+            .ok()
+            .filter(|oid| repo.find_commit(*oid).is_ok());
+
+        match known {
+            Some(oid) => {
+                walk.hide(oid)?;
+                description = format!("{}..HEAD", short_id(last));
+            }
+            // Rescanning is harmless: moves only go forward and links aren't repeated
+            None if !quiet => utils::warning(&format!(
+                "Last scanned commit {} is no longer in history, scanning everything",
+                last
+            )),
+            None => {}
+        }
+    }
+
+    Ok(Range {
+        commits: collect(repo, walk)?,
+        tip: Some(head.id()),
+        description,
+    })
+}
+
+/// Read the commits of a walk, oldest first
+fn collect(repo: &git2::Repository, mut walk: git2::Revwalk) -> Result<Vec<ScannedCommit>> {
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+    walk.map(|oid| {
+        let commit = repo.find_commit(oid?)?;
+        Ok(ScannedCommit {
+            id: commit.id().to_string(),
+            message: commit.message().unwrap_or_default().to_string(),
+        })
+    })
+    .collect()
+}
+
+/// Move a task as `tasks move` would, without overriding dependencies
+fn apply_move(store: &TaskStore, config: &Config, planned: &PlannedMove) -> Result<()> {
+    let mut task = store.find(&planned.task_id)?;
+    ensure_dependencies_met(store, config, &task, &planned.to, false)?;
+    store.move_task(&mut task, &planned.to)?;
+    Ok(())
+}
+
+/// Record the scanned commits on the task they reference
+fn apply_link(store: &TaskStore, link: &PlannedLink) -> Result<()> {
+    let mut task = store.find(&link.task_id)?;
+    task.commits
+        .get_or_insert_with(Vec::new)
+        .extend(link.commits.iter().cloned());
+    store.save(&mut task)?;
+    Ok(())
+}
+
+fn print_report(
+    scanned: &Range,
+    plan: &ScanPlan,
+    results: &[MoveResult],
+    link_results: &[LinkResult],
+    apply: bool,
+) {
+    println!(
+        "Scanned {} commit(s) in {}",
+        style(scanned.commits.len()).bold(),
+        scanned.description
+    );
+
+    if plan.is_empty() {
+        utils::success("No task changes found");
+        return;
+    }
+
+    if !results.is_empty() {
+        println!();
+        println!(
+            "{}",
+            style(if apply { "Moved:" } else { "Would move:" }).bold()
+        );
+        for result in results {
+            let planned = result.planned;
+            println!(
+                "  {} {} {} {} {} {}",
+                utils::task_id(&planned.task_id),
+                planned.title,
+                utils::status_badge(&planned.from),
+                style("→").dim(),
+                utils::status_badge(&planned.to),
+                style(format!(
+                    "({} {})",
+                    short_id(&planned.commit),
+                    planned.keyword
+                ))
+                .dim()
+            );
+            if let Some(error) = &result.error {
+                println!("    {}", style(format!("Not moved: {}", error)).red());
+            }
+        }
+    }
+
+    if !plan.links.is_empty() {
+        println!();
+        println!(
+            "{}",
+            style(if apply {
+                "Linked commits:"
+            } else {
+                "Would link commits:"
+            })
+            .bold()
+        );
+        for result in link_results {
+            let link = result.planned;
+            println!(
+                "  {} {} {}",
+                utils::task_id(&link.task_id),
+                link.title,
+                style(format!("+{} commit(s)", link.commits.len())).dim()
+            );
+            if let Some(error) = &result.error {
+                println!("    {}", style(format!("Not linked: {}", error)).red());
+            }
+        }
+    }
+
+    if !apply {
+        println!();
+        println!(
+            "Run again with {} to make these changes",
+            style("--apply").bold()
+        );
+    }
+}
+
+/// Abbreviate a commit ID the way git does
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(7)]
+}
@@ -1,11 +1,19 @@
 use anyhow::{bail, Context, Result};
+use chrono::Local;
 use console::style;
+use git2::{Commit, ConfigLevel, ErrorCode, Oid};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io::Read;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 
+use super::dependencies::ensure_dependencies_met;
+use super::save::open_repository;
+use crate::hooks::log::parse_since;
+use crate::hooks::{runner, HookEvent, HookLog, LogFilter, TaskAction};
 use crate::utils;
+use crate::{Config, Repository, Task, TaskStore};
 
 /// Hook types supported by repo-tasks
 #[derive(Debug, Clone)]
@@ -14,6 +22,8 @@ pub enum HookType {
     PostCommit,
     PrepareCommitMsg,
     PostCheckout,
+    CommitMsg,
+    PrePush,
 }
 
 impl HookType {
@@ -23,6 +33,20 @@ impl HookType {
             HookType::PostCommit => "post-commit",
             HookType::PrepareCommitMsg => "prepare-commit-msg",
             HookType::PostCheckout => "post-checkout",
+            HookType::CommitMsg => "commit-msg",
+            HookType::PrePush => "pre-push",
+        }
+    }
+
+    /// What the hook does, as shown after installing it
+    fn description(&self) -> &str {
+        match self {
+            HookType::PreCommit => "Keep task files out of regular commits",
+            HookType::PostCommit => "Auto-update task status",
+            HookType::PrepareCommitMsg => "Add task context to commits",
+            HookType::PostCheckout => "Show task info on branch switch",
+            HookType::CommitMsg => "Enforce hook_policy on new commits",
+            HookType::PrePush => "Enforce hook_policy on pushed commits and branches",
         }
     }
 
@@ -32,19 +56,30 @@ impl HookType {
             HookType::PostCommit,
             HookType::PrepareCommitMsg,
             HookType::PostCheckout,
+            HookType::CommitMsg,
+            HookType::PrePush,
         ]
     }
+
+    /// Whether git passes the hook input on stdin
+    fn reads_stdin(&self) -> bool {
+        matches!(self, HookType::PrePush)
+    }
 }
 
+/// Directory for hooks committed with the repository, relative to its root
+const SHARED_HOOKS_DIR: &str = ".repo-tasks/hooks";
+
 /// Install git hooks for repo-tasks
-pub fn install(hook_name: Option<String>) -> Result<()> {
+///
+/// With `shared`, the hooks go in `.repo-tasks/hooks` to be committed, and
+/// `core.hooksPath` is pointed at them.
+pub fn install(hook_name: Option<String>, shared: bool) -> Result<()> {
     // Verify we're in a git repository
-    let git_hooks_dir = find_git_hooks_dir()?;
+    let repo = open_repository()?;
 
     // Verify repo-tasks is initialized
-    if !Path::new(".repo-tasks/config.json").exists() {
-        bail!("repo-tasks not initialized. Run 'tasks init' first.");
-    }
+    utils::errors::ensure_initialized()?;
 
     // Determine which hooks to install
     let hooks_to_install = if let Some(name) = hook_name {
@@ -56,39 +91,123 @@ pub fn install(hook_name: Option<String>) -> Result<()> {
         HookType::all()
     };
 
-    let mut installed_count = 0;
+    let git_hooks_dir = if shared {
+        enable_shared_hooks(&repo)?
+    } else {
+        find_git_hooks_dir(&repo)?
+    };
+
+    // Hooks call back into the binary doing the installing, unless they are
+    // shared, where each clone finds its own on the PATH
+    let binary = if is_shared_hooks_dir(&repo, &git_hooks_dir) {
+        None
+    } else {
+        Some(std::env::current_exe().context("Failed to locate the tasks binary")?)
+    };
 
-    for hook_type in hooks_to_install {
-        install_hook(&git_hooks_dir, &hook_type)?;
-        installed_count += 1;
+    for hook_type in &hooks_to_install {
+        install_hook(&git_hooks_dir, hook_type, binary.as_deref())?;
     }
+    let installed_count = hooks_to_install.len();
 
     utils::success(&format!(
-        "Installed {} git hook{}",
+        "Installed {} git hook{} in {}",
         installed_count,
-        if installed_count == 1 { "" } else { "s" }
+        if installed_count == 1 { "" } else { "s" },
+        git_hooks_dir.display()
     ));
 
     println!("\n{}", style("Installed hooks:").bold());
-    println!("  {} {}", style("✓").green(), "pre-commit - Validate task references");
-    println!("  {} {}", style("✓").green(), "post-commit - Auto-update task status");
-    println!("  {} {}", style("✓").green(), "prepare-commit-msg - Add task context to commits");
-    println!("  {} {}", style("✓").green(), "post-checkout - Show task info on branch switch");
+    for hook_type in &hooks_to_install {
+        println!(
+            "  {} {} - {}",
+            style("✓").green(),
+            hook_type.filename(),
+            hook_type.description()
+        );
+    }
 
     println!("\n{}", style("Tip:").dim());
-    println!("  {}", style("Use 'git commit --no-verify' to bypass hooks if needed").dim());
+    println!(
+        "  {}",
+        style("Use 'git commit --no-verify' to bypass hooks if needed").dim()
+    );
+    if binary.is_none() {
+        println!(
+            "  {}",
+            style(format!(
+                "Commit {} with 'tasks save'; teammates enable it with 'tasks hooks install --shared'",
+                SHARED_HOOKS_DIR
+            ))
+            .dim()
+        );
+    }
 
     Ok(())
 }
 
 /// Uninstall repo-tasks git hooks
-pub fn uninstall() -> Result<()> {
-    let git_hooks_dir = find_git_hooks_dir()?;
+///
+/// When this clone uses the shared hooks, it stops using them; `shared` also
+/// removes them from `.repo-tasks/hooks`.
+pub fn uninstall(shared: bool) -> Result<()> {
+    let repo = open_repository()?;
+    let git_hooks_dir = find_git_hooks_dir(&repo)?;
+
+    if is_shared_hooks_dir(&repo, &git_hooks_dir) {
+        let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+        config
+            .remove("core.hooksPath")
+            .context("Failed to unset core.hooksPath")?;
+        utils::success(&format!(
+            "Stopped using the shared hooks in {}",
+            git_hooks_dir.display()
+        ));
+
+        if !shared {
+            println!(
+                "  {}",
+                style("Run 'tasks hooks uninstall --shared' to remove them from the repository")
+                    .dim()
+            );
+            return Ok(());
+        }
+    }
+
+    let hooks_dir = if shared {
+        shared_hooks_dir(&repo)?
+    } else {
+        git_hooks_dir
+    };
+    let removed_count = remove_hooks(&hooks_dir)?;
+
+    if shared {
+        // Only removed if nothing else was put there
+        fs::remove_dir(&hooks_dir).ok();
+    }
 
+    if removed_count == 0 {
+        println!(
+            "{}",
+            style("No repo-tasks hooks found to uninstall").yellow()
+        );
+    } else {
+        utils::success(&format!(
+            "Uninstalled {} git hook{}",
+            removed_count,
+            if removed_count == 1 { "" } else { "s" }
+        ));
+    }
+
+    Ok(())
+}
+
+/// Remove our hooks from a directory, restoring the ones they replaced
+fn remove_hooks(hooks_dir: &Path) -> Result<usize> {
     let mut removed_count = 0;
 
     for hook_type in HookType::all() {
-        let hook_path = git_hooks_dir.join(hook_type.filename());
+        let hook_path = hooks_dir.join(hook_type.filename());
 
         if hook_path.exists() {
             // Check if it's our hook
@@ -100,43 +219,49 @@ pub fn uninstall() -> Result<()> {
             }
         }
 
-        // Also remove backup if it exists
-        let backup_path = git_hooks_dir.join(format!("{}.backup", hook_type.filename()));
+        // Put back the hook we were chaining to
+        let backup_path = backup_path(hooks_dir, &hook_type);
         if backup_path.exists() {
-            fs::remove_file(&backup_path).ok(); // Ignore errors on backup removal
+            if hook_path.exists() {
+                utils::warning(&format!(
+                    "Left {} in place: {} was replaced by another hook",
+                    backup_path.display(),
+                    hook_type.filename()
+                ));
+            } else {
+                fs::rename(&backup_path, &hook_path).context(format!(
+                    "Failed to restore original {} hook",
+                    hook_type.filename()
+                ))?;
+                println!(
+                    "  {} Restored original {} hook",
+                    style("→").dim(),
+                    hook_type.filename()
+                );
+            }
         }
     }
 
-    if removed_count == 0 {
-        println!("{}", style("No repo-tasks hooks found to uninstall").yellow());
-    } else {
-        utils::success(&format!(
-            "Uninstalled {} git hook{}",
-            removed_count,
-            if removed_count == 1 { "" } else { "s" }
-        ));
-    }
-
-    Ok(())
+    Ok(removed_count)
 }
 
 /// List installed repo-tasks git hooks
 pub fn list() -> Result<()> {
-    let git_hooks_dir = find_git_hooks_dir()?;
+    let repo = open_repository()?;
+    let git_hooks_dir = find_git_hooks_dir(&repo)?;
 
     println!("{}", style("Git Hooks Status:").bold());
+    println!(
+        "{}",
+        style(format!("Hooks directory: {}", git_hooks_dir.display())).dim()
+    );
     println!();
 
     let mut installed_count = 0;
 
     for hook_type in HookType::all() {
         let hook_path = git_hooks_dir.join(hook_type.filename());
-        let is_installed = if hook_path.exists() {
-            let content = fs::read_to_string(&hook_path)?;
-            content.contains("repo-tasks")
-        } else {
-            false
-        };
+        let is_installed = is_our_hook(&hook_path)?;
 
         let status_icon = if is_installed {
             style("✓").green()
@@ -150,7 +275,19 @@ pub fn list() -> Result<()> {
             style("not installed").dim()
         };
 
-        println!("  {} {:<20} {}", status_icon, hook_type.filename(), status_text);
+        let chained = if is_installed && backup_path(&git_hooks_dir, &hook_type).exists() {
+            format!(" {}", style("(runs the original hook first)").dim())
+        } else {
+            String::new()
+        };
+
+        println!(
+            "  {} {:<20} {}{}",
+            status_icon,
+            hook_type.filename(),
+            status_text,
+            chained
+        );
 
         if is_installed {
             installed_count += 1;
@@ -159,32 +296,175 @@ pub fn list() -> Result<()> {
 
     println!();
     if installed_count == 0 {
-        println!("{}", style("No hooks installed. Run 'tasks hooks install' to install them.").dim());
+        println!(
+            "{}",
+            style("No hooks installed. Run 'tasks hooks install' to install them.").dim()
+        );
     } else {
-        println!("{}", style(format!("{} of {} hooks installed", installed_count, HookType::all().len())).dim());
+        println!(
+            "{}",
+            style(format!(
+                "{} of {} hooks installed",
+                installed_count,
+                HookType::all().len()
+            ))
+            .dim()
+        );
+    }
+
+    // Shared hooks committed by a teammate, but not enabled here
+    let shared_dir = shared_hooks_dir(&repo)?;
+    if !is_shared_hooks_dir(&repo, &git_hooks_dir)
+        && HookType::all()
+            .iter()
+            .any(|h| is_our_hook(&shared_dir.join(h.filename())).unwrap_or(false))
+    {
+        println!(
+            "{}",
+            style(format!(
+                "This repository has shared hooks in {}. Run 'tasks hooks install --shared' to use them.",
+                shared_dir.display()
+            ))
+            .dim()
+        );
     }
 
     Ok(())
 }
 
-/// Find the .git/hooks directory
-fn find_git_hooks_dir() -> Result<PathBuf> {
-    // Check current directory for .git
-    let git_dir = Path::new(".git");
-
-    if !git_dir.exists() {
-        bail!("Not in a git repository. Initialize git first with 'git init'.");
+/// Check whether a hook file was installed by repo-tasks
+fn is_our_hook(hook_path: &Path) -> Result<bool> {
+    if !hook_path.exists() {
+        return Ok(false);
     }
 
-    let hooks_dir = git_dir.join("hooks");
+    let content = fs::read_to_string(hook_path)?;
+    Ok(content.contains("repo-tasks"))
+}
+
+/// Find the directory git runs hooks from
+///
+/// That's `core.hooksPath` if set (relative to the working tree), otherwise
+/// `hooks` in the git directory shared by all worktrees. Paths inside the
+/// working tree are returned relative to it.
+fn find_git_hooks_dir(repo: &git2::Repository) -> Result<PathBuf> {
+    let hooks_dir = match configured_hooks_path(repo)? {
+        Some(path) => path,
+        None => common_dir(repo).join("hooks"),
+    };
 
     // Create hooks directory if it doesn't exist
     if !hooks_dir.exists() {
-        fs::create_dir_all(&hooks_dir)
-            .context("Failed to create .git/hooks directory")?;
+        fs::create_dir_all(&hooks_dir).context(format!(
+            "Failed to create hooks directory: {}",
+            hooks_dir.display()
+        ))?;
+    }
+
+    Ok(relative_to_workdir(repo, hooks_dir))
+}
+
+/// The value of `core.hooksPath`, made absolute
+fn configured_hooks_path(repo: &git2::Repository) -> Result<Option<PathBuf>> {
+    let path = match repo.config()?.get_path("core.hooksPath") {
+        Ok(path) => path,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e).context("Failed to read core.hooksPath"),
+    };
+
+    let base = repo.workdir().unwrap_or_else(|| repo.path());
+    Ok(Some(base.join(path)))
+}
+
+/// The git directory shared by all worktrees
+///
+/// A linked worktree's git directory names it in a `commondir` file; for
+/// everything else (including submodules) it is the git directory itself.
+fn common_dir(repo: &git2::Repository) -> PathBuf {
+    let git_dir = repo.path();
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => {
+            let common = git_dir.join(common.trim());
+            fs::canonicalize(&common).unwrap_or(common)
+        }
+        Err(_) => git_dir.to_path_buf(),
     }
+}
 
-    Ok(hooks_dir)
+fn shared_hooks_dir(repo: &git2::Repository) -> Result<PathBuf> {
+    let workdir = repo
+        .workdir()
+        .context("Shared hooks need a repository with a working tree")?;
+    Ok(relative_to_workdir(repo, workdir.join(SHARED_HOOKS_DIR)))
+}
+
+/// Check whether git is running the hooks committed in `.repo-tasks/hooks`
+fn is_shared_hooks_dir(repo: &git2::Repository, hooks_dir: &Path) -> bool {
+    let Ok(shared) = shared_hooks_dir(repo) else {
+        return false;
+    };
+
+    match (fs::canonicalize(hooks_dir), fs::canonicalize(&shared)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => hooks_dir == shared,
+    }
+}
+
+/// Point `core.hooksPath` at `.repo-tasks/hooks`, creating it if needed
+///
+/// Refuses to take over a `core.hooksPath` set by another tool.
+fn enable_shared_hooks(repo: &git2::Repository) -> Result<PathBuf> {
+    let shared = shared_hooks_dir(repo)?;
+
+    if let Some(current) = configured_hooks_path(repo)? {
+        if !is_shared_hooks_dir(repo, &current) {
+            bail!(
+                "core.hooksPath is already set to {}. Unset it with 'git config --unset core.hooksPath' to use shared hooks.",
+                relative_to_workdir(repo, current).display()
+            );
+        }
+    }
+
+    // Hooks in the git directory stop running once core.hooksPath is set
+    let default_dir = relative_to_workdir(repo, common_dir(repo).join("hooks"));
+    let stranded: Vec<String> = HookType::all()
+        .iter()
+        .map(|h| h.filename().to_string())
+        .filter(|name| {
+            let path = default_dir.join(name);
+            path.exists() && !is_our_hook(&path).unwrap_or(true)
+        })
+        .collect();
+    if !stranded.is_empty() {
+        utils::warning(&format!(
+            "git will no longer run these hooks from {}: {}. Move them to {}/<hook>.backup to keep them running.",
+            default_dir.display(),
+            stranded.join(", "),
+            shared.display()
+        ));
+    }
+
+    fs::create_dir_all(&shared).context(format!("Failed to create {}", shared.display()))?;
+
+    let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+    config
+        .set_str("core.hooksPath", SHARED_HOOKS_DIR)
+        .context("Failed to set core.hooksPath")?;
+    println!(
+        "  {} Set core.hooksPath to {}",
+        style("→").dim(),
+        SHARED_HOOKS_DIR
+    );
+
+    Ok(shared)
+}
+
+/// Shorten paths inside the working tree, for messages
+fn relative_to_workdir(repo: &git2::Repository, path: PathBuf) -> PathBuf {
+    repo.workdir()
+        .and_then(|workdir| path.strip_prefix(workdir).ok())
+        .map(Path::to_path_buf)
+        .unwrap_or(path)
 }
 
 /// Parse hook name string to HookType
@@ -194,27 +474,42 @@ fn parse_hook_name(name: &str) -> Result<HookType> {
         "post-commit" => Ok(HookType::PostCommit),
         "prepare-commit-msg" => Ok(HookType::PrepareCommitMsg),
         "post-checkout" => Ok(HookType::PostCheckout),
-        _ => bail!("Unknown hook type: {}. Valid types: pre-commit, post-commit, prepare-commit-msg, post-checkout", name),
+        "commit-msg" => Ok(HookType::CommitMsg),
+        "pre-push" => Ok(HookType::PrePush),
+        _ => bail!("Unknown hook type: {}. Valid types: pre-commit, post-commit, prepare-commit-msg, post-checkout, commit-msg, pre-push", name),
     }
 }
 
 /// Install a specific hook
-fn install_hook(hooks_dir: &Path, hook_type: &HookType) -> Result<()> {
+fn install_hook(hooks_dir: &Path, hook_type: &HookType, binary: Option<&Path>) -> Result<()> {
     let hook_path = hooks_dir.join(hook_type.filename());
 
-    // Backup existing hook if it exists and isn't ours
-    if hook_path.exists() {
-        let content = fs::read_to_string(&hook_path)?;
-        if !content.contains("repo-tasks") {
-            let backup_path = hooks_dir.join(format!("{}.backup", hook_type.filename()));
-            fs::copy(&hook_path, &backup_path)
-                .context(format!("Failed to backup existing {} hook", hook_type.filename()))?;
-            println!("  {} Backed up existing {} hook", style("→").dim(), hook_type.filename());
+    // Keep an existing hook that isn't ours, so ours can run it first
+    if hook_path.exists() && !is_our_hook(&hook_path)? {
+        let backup_path = backup_path(hooks_dir, hook_type);
+        if backup_path.exists() {
+            bail!(
+                "Both {} and {} exist, and repo-tasks can only run one of them. \
+                Combine or remove one, then install again.",
+                hook_path.display(),
+                backup_path.display()
+            );
         }
+
+        fs::rename(&hook_path, &backup_path).context(format!(
+            "Failed to preserve existing {} hook",
+            hook_type.filename()
+        ))?;
+        println!(
+            "  {} Existing {} hook moved to {} and will run first",
+            style("→").dim(),
+            hook_type.filename(),
+            backup_path.display()
+        );
     }
 
     // Get hook template content
-    let hook_content = get_hook_template(hook_type);
+    let hook_content = get_hook_template(hook_type, binary);
 
     // Write hook file
     fs::write(&hook_path, hook_content)
@@ -231,295 +526,542 @@ fn install_hook(hooks_dir: &Path, hook_type: &HookType) -> Result<()> {
     Ok(())
 }
 
-/// Get the template content for a hook
-fn get_hook_template(hook_type: &HookType) -> String {
-    let common_header = r#"#!/bin/sh
+/// Where a hook that was installed before ours is kept
+fn backup_path(hooks_dir: &Path, hook_type: &HookType) -> PathBuf {
+    hooks_dir.join(format!("{}.backup", hook_type.filename()))
+}
+
+/// Get the script installed for a hook
+///
+/// Scripts are thin wrappers around `tasks hook run`, which holds all of the
+/// behaviour. They call the binary that installed them, falling back to the
+/// one on the PATH if it has since moved; shared hooks (no `binary`) always
+/// use the PATH. A hook that was there before ours runs first, and if it
+/// fails its exit status is passed on to git.
+fn get_hook_template(hook_type: &HookType, binary: Option<&Path>) -> String {
+    let lookup = "$(command -v repo-tasks || command -v tasks) || exit 0";
+    let locate = match binary {
+        Some(binary) => format!(
+            "TASKS_BIN={}\nif [ ! -x \"$TASKS_BIN\" ]; then\n    TASKS_BIN={}\nfi",
+            shell_quote(&binary.to_string_lossy()),
+            lookup
+        ),
+        None => format!("TASKS_BIN={}", lookup),
+    };
+
+    // Both hooks need the input, so it is read once and replayed
+    let (capture, replay, run) = if hook_type.reads_stdin() {
+        (
+            "HOOK_INPUT=$(cat)\n",
+            "printf '%s\\n' \"$HOOK_INPUT\" | ",
+            "printf '%s\\n' \"$HOOK_INPUT\" | ",
+        )
+    } else {
+        ("", "", "exec ")
+    };
+
+    format!(
+        r#"#!/bin/sh
 # Generated by repo-tasks
 # This hook is managed by repo-tasks. To uninstall: tasks hooks uninstall
-"#;
-
-    match hook_type {
-        HookType::PreCommit => format!(
-            r#"{}
-# Pre-commit hook: Validate task references and prevent .repo-tasks commits
-
-# Check for staged .repo-tasks files
-STAGED_TASKS=$(git diff --cached --name-only | grep "^\.repo-tasks/")
-
-if [ -n "$STAGED_TASKS" ]; then
-  echo "Error: Cannot commit task files with regular git commit"
-  echo ""
-  echo "The following task files are staged:"
-  echo "$STAGED_TASKS" | sed 's/^/  - /'
-  echo ""
-  echo "Task files should only be committed with 'tasks save'"
-  echo ""
-  echo "To fix:"
-  echo "  1. Unstage task files: git restore --staged .repo-tasks/"
-  echo "  2. Commit project files: git commit -m \"Your message\""
-  echo "  3. Then save task files: tasks save"
-  echo ""
-  echo "Or use --no-verify to bypass this check (not recommended)"
-  exit 1
+
+{capture}ORIGINAL_HOOK="$(dirname "$0")/{name}.backup"
+if [ -x "$ORIGINAL_HOOK" ]; then
+    {replay}"$ORIGINAL_HOOK" "$@" || exit $?
 fi
 
-exit 0
+{locate}
+
+{run}"$TASKS_BIN" hook run {name} "$@"
 "#,
-            common_header
-        ),
-        HookType::PostCommit => format!(
-            r#"{}
-# Post-commit hook: Auto-update task status based on commit message
-
-# Get the commit message
-COMMIT_MSG=$(git log -1 --pretty=%B)
-
-# Log file
-LOG_FILE=".repo-tasks/hooks.log"
-mkdir -p "$(dirname "$LOG_FILE")"
-
-# Log timestamp
-echo "$(date '+%Y-%m-%d %H:%M:%S') - post-commit hook triggered" >> "$LOG_FILE"
-
-# Find repo-tasks binary
-REPO_TASKS_BIN="repo-tasks"
-if ! command -v "$REPO_TASKS_BIN" > /dev/null 2>&1; then
-    # Try to find it in common locations
-    if [ -f "./target/release/repo-tasks" ]; then
-        REPO_TASKS_BIN="./target/release/repo-tasks"
-    elif [ -f "./target/debug/repo-tasks" ]; then
-        REPO_TASKS_BIN="./target/debug/repo-tasks"
-    else
-        echo "$(date '+%Y-%m-%d %H:%M:%S') - WARNING: repo-tasks binary not found" >> "$LOG_FILE"
-        exit 0
-    fi
-fi
+        name = hook_type.filename()
+    )
+}
 
-# Extract task IDs using grep (14-digit numbers in various formats)
-# Formats: [TASKID], #TASKID, task/TASKID, closes #TASKID, fixes #TASKID
-TASK_IDS=$(echo "$COMMIT_MSG" | grep -oE '(\[|#|task/|closes #|fixes #)([0-9]{{14}})' | grep -oE '[0-9]{{14}}' | sort -u)
+/// Quote a string for use as a single word in a POSIX shell script
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
 
-if [ -z "$TASK_IDS" ]; then
-    echo "$(date '+%Y-%m-%d %H:%M:%S') - No task IDs found in commit message" >> "$LOG_FILE"
-    exit 0
-fi
+/// Run the behaviour behind an installed hook
+///
+/// Only the checking hooks can fail: `pre-commit`, to stop a commit that
+/// includes task files, and `commit-msg` and `pre-push`, to enforce the
+/// `hook_policy` in the config. The other hooks record problems in
+/// `.repo-tasks/hooks.log` and always succeed, so task bookkeeping never
+/// gets in the way of git.
+pub fn run(name: String, args: Vec<String>) -> Result<()> {
+    let hook_type = parse_hook_name(&name)?;
+
+    // Nothing to do in repositories without tasks
+    let Ok(tasks) = Repository::current() else {
+        return Ok(());
+    };
 
-# Convert commit message to lowercase for case-insensitive matching
-COMMIT_MSG_LOWER=$(echo "$COMMIT_MSG" | tr '[:upper:]' '[:lower:]')
-
-# Determine target status from keywords
-TARGET_STATUS=""
-
-# Check for "done" keywords
-if echo "$COMMIT_MSG_LOWER" | grep -qE '\[done\]|\[complete\]|\[completed\]|\[finished\]|closes #[0-9]{{14}}|fixes #[0-9]{{14}}'; then
-    TARGET_STATUS="done"
-# Check for "testing" keywords
-elif echo "$COMMIT_MSG_LOWER" | grep -qE '\[testing\]|\[review\]|\[ready\]'; then
-    TARGET_STATUS="testing"
-# Check for "in-progress" keywords
-elif echo "$COMMIT_MSG_LOWER" | grep -qE '\[wip\]|\[in-progress\]|\[started\]'; then
-    TARGET_STATUS="in-progress"
-fi
+    let result = match hook_type {
+        HookType::PreCommit => return run_pre_commit(&tasks),
+        HookType::CommitMsg => return run_commit_msg(&tasks, &args),
+        HookType::PrePush => return run_pre_push(&tasks, &args),
+        HookType::PostCommit => run_post_commit(&tasks),
+        HookType::PrepareCommitMsg => run_prepare_commit_msg(&tasks, &args),
+        HookType::PostCheckout => run_post_checkout(&tasks, &args),
+    };
 
-if [ -z "$TARGET_STATUS" ]; then
-    echo "$(date '+%Y-%m-%d %H:%M:%S') - No status keywords found in commit message" >> "$LOG_FILE"
-    exit 0
-fi
+    if let Err(e) = result {
+        let event =
+            HookEvent::new(hook_type.filename(), "failed").error(utils::errors::summary(&e));
+        record(&tasks, &event);
+        warn_failure(&event);
+    }
 
-# Update each task
-echo "$TASK_IDS" | while read -r TASK_ID; do
-    if [ -n "$TASK_ID" ]; then
-        echo "$(date '+%Y-%m-%d %H:%M:%S') - Moving task $TASK_ID to $TARGET_STATUS" >> "$LOG_FILE"
-
-        # Try to move the task, suppress output but capture errors
-        if OUTPUT=$("$REPO_TASKS_BIN" move "$TASK_ID" "$TARGET_STATUS" 2>&1); then
-            echo "$(date '+%Y-%m-%d %H:%M:%S') - Successfully moved task $TASK_ID to $TARGET_STATUS" >> "$LOG_FILE"
-            # Show user feedback
-            echo "repo-tasks: Moved task $TASK_ID to $TARGET_STATUS"
-        else
-            # Log error but don't fail the commit
-            echo "$(date '+%Y-%m-%d %H:%M:%S') - ERROR moving task $TASK_ID: $OUTPUT" >> "$LOG_FILE"
-        fi
-    fi
-done
-
-exit 0
-"#,
-            common_header
-        ),
-        HookType::PrepareCommitMsg => format!(
-            r###"{}
-# Prepare-commit-msg hook: Add task context to commit template
+    Ok(())
+}
 
-COMMIT_MSG_FILE=$1
-COMMIT_SOURCE=$2
+/// Refuse commits that include task files, which belong in `tasks save`
+fn run_pre_commit(tasks: &Repository) -> Result<()> {
+    let repo = tasks.git()?;
+    let staged = staged_paths(&repo)?;
+    let task_files = runner::staged_task_files(&staged);
 
-# Only run for new commits (not merge, squash, etc.)
-if [ -n "$COMMIT_SOURCE" ] && [ "$COMMIT_SOURCE" != "template" ]; then
-    exit 0
-fi
+    if task_files.is_empty() {
+        return Ok(());
+    }
 
-# Get current branch name
-BRANCH=$(git symbolic-ref --short HEAD 2>/dev/null)
+    let list: Vec<String> = task_files.iter().map(|f| format!("  - {}", f)).collect();
+    bail!(
+        "Cannot commit task files with regular git commit\n\
+        \n\
+        The following task files are staged:\n\
+        {}\n\
+        \n\
+        Task files should only be committed with 'tasks save'\n\
+        \n\
+        To fix:\n  \
+        1. Unstage task files: git restore --staged .repo-tasks/\n  \
+        2. Commit project files: git commit -m \"Your message\"\n  \
+        3. Then save task files: tasks save\n\
+        \n\
+        Or use --no-verify to bypass this check (not recommended)",
+        list.join("\n")
+    );
+}
 
-if [ -z "$BRANCH" ]; then
-    exit 0
-fi
+/// Enforce the commit rules of the hook policy on the message being committed
+fn run_commit_msg(tasks: &Repository, args: &[String]) -> Result<()> {
+    let config = tasks.config()?;
+    if !config.hook_policy.checks_commits() {
+        return Ok(());
+    }
 
-# Extract task ID from branch name (formats: task/TASKID or TASKID-slug)
-TASK_ID=""
+    let Some(message_file) = args.first() else {
+        return Ok(());
+    };
+    let message = fs::read_to_string(message_file)
+        .context(format!("Failed to read commit message: {}", message_file))?;
 
-# Try task/TASKID format
-if echo "$BRANCH" | grep -qE '^task/[0-9]{{14}}'; then
-    TASK_ID=$(echo "$BRANCH" | grep -oE '[0-9]{{14}}' | head -1)
-# Try TASKID-slug format
-elif echo "$BRANCH" | grep -qE '^[0-9]{{14}}-'; then
-    TASK_ID=$(echo "$BRANCH" | grep -oE '^[0-9]{{14}}')
-fi
+    // git aborts empty commits by itself
+    let message = runner::strip_comments(&message);
+    if message.is_empty() {
+        return Ok(());
+    }
 
-# If no task ID found, exit
-if [ -z "$TASK_ID" ]; then
-    exit 0
-fi
+    let all = tasks.all()?;
+    let problems = runner::commit_policy_problems(&message, &config, &all, true)?;
+    if problems.is_empty() {
+        return Ok(());
+    }
 
-# Find repo-tasks binary
-REPO_TASKS_BIN="repo-tasks"
-if ! command -v "$REPO_TASKS_BIN" > /dev/null 2>&1; then
-    if [ -f "./target/release/repo-tasks" ]; then
-        REPO_TASKS_BIN="./target/release/repo-tasks"
-    elif [ -f "./target/debug/repo-tasks" ]; then
-        REPO_TASKS_BIN="./target/debug/repo-tasks"
-    else
-        exit 0
-    fi
-fi
+    let summary = message.lines().next().unwrap_or_default();
+    bail!(
+        "Commit blocked by hook_policy:\n\
+        \n\
+        {}\n\
+        \n\
+        Reference an open task, e.g. \"[ID] {}\", or change hook_policy in .repo-tasks/config.json\n\
+        Or use --no-verify to bypass this check (not recommended)",
+        format_violation(None, summary, &problems),
+        summary
+    );
+}
 
-# Get task info (capturing stdout and stderr separately)
-TASK_INFO=$("$REPO_TASKS_BIN" show "$TASK_ID" 2>/dev/null)
+/// Enforce the push rules of the hook policy on the commits being pushed
+///
+/// git passes the remote as arguments, and a line per pushed ref on stdin:
+/// `<local ref> <local sha> <remote ref> <remote sha>`.
+fn run_pre_push(tasks: &Repository, args: &[String]) -> Result<()> {
+    let config = tasks.config()?;
+    if !config.hook_policy.checks_pushes() {
+        return Ok(());
+    }
 
-if [ $? -ne 0 ] || [ -z "$TASK_INFO" ]; then
-    # Task not found or error, just exit
-    exit 0
-fi
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("Failed to read the refs being pushed")?;
 
-# Extract task title and status from output
-TASK_TITLE=$(echo "$TASK_INFO" | grep "^Task:" | sed 's/^Task: //')
-TASK_STATUS=$(echo "$TASK_INFO" | grep "^Status:" | sed 's/^Status: //')
+    let repo = tasks.git()?;
+    let all = tasks.all()?;
+    let remote = args.first().map(String::as_str).unwrap_or("origin");
+    let mut violations = Vec::new();
 
-if [ -z "$TASK_TITLE" ]; then
-    exit 0
-fi
+    for line in input.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [local_ref, local_sha, _, remote_sha] = fields[..] else {
+            continue;
+        };
+        let local = Oid::from_str(local_sha)?;
 
-# Prepend task context to commit message
-TEMP_FILE="$COMMIT_MSG_FILE.tmp"
+        // Deleting a remote branch
+        if local.is_zero() {
+            continue;
+        }
 
-# Add task reference
-echo "# Task: $TASK_TITLE" > "$TEMP_FILE"
-echo "# ID: $TASK_ID" >> "$TEMP_FILE"
-if [ -n "$TASK_STATUS" ]; then
-    echo "# Status: $TASK_STATUS" >> "$TEMP_FILE"
-fi
-echo "#" >> "$TEMP_FILE"
-echo "# Closes #$TASK_ID" >> "$TEMP_FILE"
-echo "" >> "$TEMP_FILE"
+        if let Some(branch) = local_ref.strip_prefix("refs/heads/") {
+            if let Some(problem) = runner::push_policy_problem(branch, &config, &all) {
+                violations.push(format!("  {}\n    - {}", local_ref, problem));
+            }
+        }
 
-# Append original commit message
-cat "$COMMIT_MSG_FILE" >> "$TEMP_FILE"
+        if config.hook_policy.require_task_reference {
+            for commit in pushed_commits(&repo, remote, local, Oid::from_str(remote_sha)?)? {
+                // Merge commits describe the merge, not a task
+                if commit.parent_count() > 1 {
+                    continue;
+                }
+
+                let message = commit.message().unwrap_or_default();
+                let problems = runner::commit_policy_problems(message, &config, &all, false)?;
+                if !problems.is_empty() {
+                    violations.push(format_violation(
+                        Some(&commit.id().to_string()[..7]),
+                        commit.summary().unwrap_or_default(),
+                        &problems,
+                    ));
+                }
+            }
+        }
+    }
 
-# Replace original file
-mv "$TEMP_FILE" "$COMMIT_MSG_FILE"
+    if violations.is_empty() {
+        return Ok(());
+    }
 
-exit 0
-"###,
-            common_header
-        ),
-        HookType::PostCheckout => format!(
-            r#"{}
-# Post-checkout hook: Show task info when switching branches
+    bail!(
+        "Push blocked by hook_policy:\n\
+        \n\
+        {}\n\
+        \n\
+        Reword the commits (git rebase -i), start the task (tasks start), or change hook_policy in .repo-tasks/config.json\n\
+        Or use --no-verify to bypass this check (not recommended)",
+        violations.join("\n")
+    );
+}
 
-PREV_HEAD=$1
-NEW_HEAD=$2
-BRANCH_SWITCH=$3
+/// Commits that a push would add to the remote, newest first
+fn pushed_commits<'r>(
+    repo: &'r git2::Repository,
+    remote: &str,
+    local: Oid,
+    remote_oid: Oid,
+) -> Result<Vec<Commit<'r>>> {
+    let mut walk = repo.revwalk()?;
+    walk.push(local)?;
+
+    if !remote_oid.is_zero() && repo.find_commit(remote_oid).is_ok() {
+        walk.hide(remote_oid)?;
+    } else {
+        // A new branch: skip whatever the remote already has elsewhere
+        walk.hide_glob(&format!("refs/remotes/{}/*", remote))?;
+    }
 
-# Only run on branch switches (not file checkouts)
-if [ "$BRANCH_SWITCH" != "1" ]; then
-    exit 0
-fi
+    walk.map(|oid| Ok(repo.find_commit(oid?)?)).collect()
+}
 
-# Get current branch name
-BRANCH=$(git symbolic-ref --short HEAD 2>/dev/null)
+/// Describe a commit that breaks the policy, and how
+fn format_violation(commit: Option<&str>, summary: &str, problems: &[String]) -> String {
+    let mut lines = vec![match commit {
+        Some(id) => format!("  {} {}", id, summary),
+        None => format!("  {}", summary),
+    }];
+    lines.extend(problems.iter().map(|p| format!("    - {}", p)));
+    lines.join("\n")
+}
 
-if [ -z "$BRANCH" ]; then
-    exit 0
-fi
+/// Move the tasks referenced by the new commit according to its keywords
+fn run_post_commit(tasks: &Repository) -> Result<()> {
+    let repo = tasks.git()?;
+    let commit = repo.head()?.peel_to_commit()?;
+    let message = commit.message().unwrap_or_default();
+    let sha = commit.id().to_string();
+
+    let config = tasks.config()?;
+    let updates = runner::status_updates(message, &config)?;
+
+    if updates.is_empty() {
+        record(
+            tasks,
+            &HookEvent::new("post-commit", "no status keywords").commit(&sha),
+        );
+        return Ok(());
+    }
 
-# Extract task ID from branch name (formats: task/TASKID or TASKID-slug)
-TASK_ID=""
+    let store = tasks.store();
+    for update in &updates {
+        let event = HookEvent::new("post-commit", format!("move to {}", update.target_status))
+            .commit(&sha)
+            .task(&update.task_id);
+
+        let event = match apply_status_update(&store, &config, update) {
+            Ok(true) => {
+                println!(
+                    "repo-tasks: Moved task {} to {}",
+                    update.task_id, update.target_status
+                );
+                HookEvent {
+                    action: format!("moved to {} ({})", update.target_status, update.keyword),
+                    ..event
+                }
+            }
+            Ok(false) => HookEvent {
+                action: format!("already in {}", update.target_status),
+                ..event
+            },
+            // Don't fail the commit, it has already happened
+            Err(e) => {
+                let event = event.error(utils::errors::summary(&e));
+                warn_failure(&event);
+                event
+            }
+        };
 
-# Try task/TASKID format
-if echo "$BRANCH" | grep -qE '^task/[0-9]{{14}}'; then
-    TASK_ID=$(echo "$BRANCH" | grep -oE '[0-9]{{14}}' | head -1)
-# Try TASKID-slug format
-elif echo "$BRANCH" | grep -qE '^[0-9]{{14}}-'; then
-    TASK_ID=$(echo "$BRANCH" | grep -oE '^[0-9]{{14}}')
-fi
+        record(tasks, &event);
+    }
 
-# If no task ID found, exit silently
-if [ -z "$TASK_ID" ]; then
-    exit 0
-fi
+    Ok(())
+}
 
-# Find repo-tasks binary
-REPO_TASKS_BIN="repo-tasks"
-if ! command -v "$REPO_TASKS_BIN" > /dev/null 2>&1; then
-    if [ -f "./target/release/repo-tasks" ]; then
-        REPO_TASKS_BIN="./target/release/repo-tasks"
-    elif [ -f "./target/debug/repo-tasks" ]; then
-        REPO_TASKS_BIN="./target/debug/repo-tasks"
-    else
-        exit 0
-    fi
-fi
+/// Move a task as `tasks move` would, without overriding dependencies
+///
+/// Returns false if the task was already in the target status.
+fn apply_status_update(store: &TaskStore, config: &Config, update: &TaskAction) -> Result<bool> {
+    let mut task = store.find(&update.task_id)?;
+    if task.status == update.target_status {
+        return Ok(false);
+    }
 
-# Get task info
-TASK_INFO=$("$REPO_TASKS_BIN" show "$TASK_ID" 2>/dev/null)
+    ensure_dependencies_met(store, config, &task, &update.target_status, false)?;
+    store.move_task(&mut task, &update.target_status)?;
 
-if [ $? -ne 0 ] || [ -z "$TASK_INFO" ]; then
-    # Task not found, exit silently
-    exit 0
-fi
+    Ok(true)
+}
 
-# Extract task details from output
-TASK_TITLE=$(echo "$TASK_INFO" | grep "^Task:" | sed 's/^Task: //')
-TASK_STATUS=$(echo "$TASK_INFO" | grep "^Status:" | sed 's/^Status: //')
-TASK_PRIORITY=$(echo "$TASK_INFO" | grep "^Priority:" | sed 's/^Priority: //')
-
-# Display task information
-echo ""
-echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
-echo "📋 Working on task: $TASK_TITLE"
-echo "   ID: $TASK_ID"
-if [ -n "$TASK_STATUS" ]; then
-    echo "   Status: $TASK_STATUS"
-fi
-if [ -n "$TASK_PRIORITY" ]; then
-    echo "   Priority: $TASK_PRIORITY"
-fi
-echo "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
+/// Put the current branch's task above a new commit message
+fn run_prepare_commit_msg(tasks: &Repository, args: &[String]) -> Result<()> {
+    let Some(message_file) = args.first() else {
+        return Ok(());
+    };
+    if !runner::prepares_message(args.get(1).map(String::as_str)) {
+        return Ok(());
+    }
 
-# Suggest moving to in-progress if status is todo
-if [ "$TASK_STATUS" = "todo" ]; then
-    echo ""
-    echo "💡 Tip: Move this task to in-progress?"
-    echo "   Run: tasks move $TASK_ID in-progress"
-fi
+    let Some(task) = branch_task(tasks)? else {
+        return Ok(());
+    };
 
-echo ""
+    let message = fs::read_to_string(message_file)
+        .context(format!("Failed to read commit message: {}", message_file))?;
+    fs::write(
+        message_file,
+        format!("{}{}", runner::commit_message_context(&task), message),
+    )
+    .context(format!("Failed to write commit message: {}", message_file))?;
 
-exit 0
-"#,
-            common_header
-        ),
+    Ok(())
+}
+
+/// Show the task for the branch that was just checked out
+fn run_post_checkout(tasks: &Repository, args: &[String]) -> Result<()> {
+    // Only branch switches, not file checkouts
+    if args.get(2).map(String::as_str) != Some("1") {
+        return Ok(());
+    }
+
+    let Some(task) = branch_task(tasks)? else {
+        return Ok(());
+    };
+
+    let rule = "━".repeat(60);
+    println!();
+    println!("{}", rule);
+    println!("📋 Working on task: {}", task.title);
+    println!("   ID: {}", task.id);
+    println!("   Status: {}", task.status);
+    if let Some(priority) = &task.priority {
+        println!("   Priority: {}", priority);
+    }
+    println!("{}", rule);
+
+    let config = tasks.config()?;
+    if config.statuses.first() == Some(&task.status) {
+        println!();
+        println!("💡 Tip: Move this task to {}?", config.started_status);
+        println!("   Run: tasks move {} {}", task.id, config.started_status);
+    }
+
+    println!();
+
+    Ok(())
+}
+
+/// Find the task the checked-out branch is named after
+fn branch_task(tasks: &Repository) -> Result<Option<Task>> {
+    let repo = tasks.git()?;
+
+    // An unborn or detached HEAD has no branch name
+    let Ok(head) = repo.head() else {
+        return Ok(None);
+    };
+    let Some(branch) = head.shorthand().filter(|_| head.is_branch()) else {
+        return Ok(None);
+    };
+
+    let config = tasks.config()?;
+    let Some(task_id) = runner::branch_task_id(branch, &config) else {
+        return Ok(None);
+    };
+
+    Ok(tasks.find(&task_id).ok())
+}
+
+/// Paths staged for the next commit
+fn staged_paths(repo: &git2::Repository) -> Result<Vec<String>> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .collect())
+}
+
+/// Add an event to `.repo-tasks/hooks.log`
+///
+/// Logging is best effort: a hook never fails because its log can't be written.
+fn record(tasks: &Repository, event: &HookEvent) {
+    HookLog::for_repository(tasks).append(event).ok();
+}
+
+/// Tell the person committing about a failure, which git would otherwise hide
+fn warn_failure(event: &HookEvent) {
+    eprintln!(
+        "repo-tasks: {} hook failed: {}",
+        event.hook,
+        event.error.as_deref().unwrap_or_default()
+    );
+    eprintln!("repo-tasks: Run 'tasks hooks log --failures' for details");
+}
+
+/// Show what the hooks have done, most recent last
+pub fn show_log(
+    task: Option<String>,
+    since: Option<String>,
+    failures: bool,
+    limit: Option<usize>,
+    clear: bool,
+    json: bool,
+) -> Result<()> {
+    utils::errors::ensure_initialized()?;
+    let tasks = Repository::current()?;
+
+    let log = HookLog::for_repository(&tasks);
+
+    if clear {
+        log.clear()?;
+        if json {
+            utils::json::print(&serde_json::json!({ "cleared": true }))?;
+        } else {
+            utils::success("Cleared the hook log");
+        }
+        return Ok(());
+    }
+
+    let filter = LogFilter {
+        // Events keep the IDs of tasks that may since have been deleted
+        task_id: task.map(|reference| tasks.find(&reference).map(|t| t.id).unwrap_or(reference)),
+        since: since
+            .map(|value| parse_since(&value, Local::now()))
+            .transpose()?,
+        failures,
+    };
+
+    let mut events: Vec<HookEvent> = log
+        .read()?
+        .into_iter()
+        .filter(|e| filter.matches(e))
+        .collect();
+    if let Some(limit) = limit {
+        events.drain(..events.len().saturating_sub(limit));
+    }
+
+    if json {
+        return utils::json::print(&serde_json::json!({ "events": events }));
+    }
+
+    if events.is_empty() {
+        println!("No hook activity recorded");
+        return Ok(());
+    }
+
+    for event in &events {
+        print_event(event);
+    }
+
+    let failed = events.iter().filter(|e| e.failed()).count();
+    if failed > 0 {
+        println!();
+        println!("{}", style(format!("{} failure(s)", failed)).red().bold());
+    }
+
+    Ok(())
+}
+
+/// Print one log entry, with failures marked and their error below
+fn print_event(event: &HookEvent) {
+    let time = event
+        .timestamp()
+        .map(|t| {
+            t.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| event.time.clone());
+
+    // Short commit ID and task IDs, each followed by a space
+    let mut subject = String::new();
+    if let Some(commit) = &event.commit {
+        subject.extend(commit.chars().take(7));
+        subject.push(' ');
+    }
+    for id in &event.task_ids {
+        subject.push_str(id);
+        subject.push(' ');
+    }
+
+    let line = format!(
+        "{}  {:<18} {}{}",
+        style(time).dim(),
+        event.hook,
+        subject,
+        event.action
+    );
+
+    match &event.error {
+        Some(error) => {
+            println!("{} {}", style("✗").red().bold(), line);
+            println!("    {}", style(error).red());
+        }
+        None => println!("  {}", line),
     }
 }
 
@@ -533,18 +1075,90 @@ mod tests {
         assert_eq!(HookType::PostCommit.filename(), "post-commit");
         assert_eq!(HookType::PrepareCommitMsg.filename(), "prepare-commit-msg");
         assert_eq!(HookType::PostCheckout.filename(), "post-checkout");
+        assert_eq!(HookType::CommitMsg.filename(), "commit-msg");
+        assert_eq!(HookType::PrePush.filename(), "pre-push");
     }
 
     #[test]
     fn test_parse_hook_name() {
-        assert!(matches!(parse_hook_name("pre-commit").unwrap(), HookType::PreCommit));
-        assert!(matches!(parse_hook_name("post-commit").unwrap(), HookType::PostCommit));
+        assert!(matches!(
+            parse_hook_name("pre-commit").unwrap(),
+            HookType::PreCommit
+        ));
+        assert!(matches!(
+            parse_hook_name("post-commit").unwrap(),
+            HookType::PostCommit
+        ));
         assert!(parse_hook_name("invalid").is_err());
     }
 
     #[test]
     fn test_all_hooks() {
         let hooks = HookType::all();
-        assert_eq!(hooks.len(), 4);
+        assert_eq!(hooks.len(), 6);
+    }
+
+    #[test]
+    fn test_hook_template_calls_installing_binary() {
+        let template = get_hook_template(&HookType::PostCommit, Some(Path::new("/opt/bin/tasks")));
+
+        assert!(template.starts_with("#!/bin/sh\n"));
+        assert!(template.contains("TASKS_BIN='/opt/bin/tasks'\n"));
+        assert!(template.contains("exec \"$TASKS_BIN\" hook run post-commit \"$@\"\n"));
+    }
+
+    #[test]
+    fn test_shared_hook_template_uses_path() {
+        let template = get_hook_template(&HookType::PostCommit, None);
+
+        assert!(!template.contains("/opt/bin"));
+        assert!(template
+            .contains("TASKS_BIN=$(command -v repo-tasks || command -v tasks) || exit 0\n\nexec"));
+    }
+
+    #[test]
+    fn test_hook_template_runs_original_first() {
+        let template = get_hook_template(&HookType::PreCommit, Some(Path::new("/opt/bin/tasks")));
+
+        let original = template
+            .find("\"$ORIGINAL_HOOK\" \"$@\" || exit $?")
+            .unwrap();
+        let ours = template.find("hook run pre-commit").unwrap();
+        assert!(original < ours);
+        assert!(template.contains("ORIGINAL_HOOK=\"$(dirname \"$0\")/pre-commit.backup\"\n"));
+    }
+
+    #[test]
+    fn test_pre_push_template_replays_stdin() {
+        let template = get_hook_template(&HookType::PrePush, Some(Path::new("/opt/bin/tasks")));
+
+        assert!(template.contains("HOOK_INPUT=$(cat)\n"));
+        assert!(template.contains(
+            "printf '%s\\n' \"$HOOK_INPUT\" | \"$TASKS_BIN\" hook run pre-push \"$@\"\n"
+        ));
+        assert!(!template.contains("exec"));
+    }
+
+    #[test]
+    fn test_format_violation() {
+        let problems = vec!["doesn't reference a task".to_string()];
+
+        assert_eq!(
+            format_violation(Some("abc1234"), "Tidy up", &problems),
+            "  abc1234 Tidy up\n    - doesn't reference a task"
+        );
+        assert_eq!(
+            format_violation(None, "Tidy up", &problems),
+            "  Tidy up\n    - doesn't reference a task"
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/usr/bin/tasks"), "'/usr/bin/tasks'");
+        assert_eq!(
+            shell_quote("/home/o'neil/tasks"),
+            r"'/home/o'\''neil/tasks'"
+        );
     }
 }
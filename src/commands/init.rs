@@ -1,40 +1,32 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use console::style;
-use std::fs;
 use std::path::Path;
 
 use crate::utils;
-use crate::Config;
+use crate::Repository;
 
 /// Initialize a new repo-tasks repository
 pub fn init(project_name: Option<String>) -> Result<()> {
-    let base = Path::new(".repo-tasks");
-
-    // Check if already initialized
-    if base.exists() {
-        bail!("Repository already initialized");
-    }
-
-    // Create directory structure
-    let statuses = ["todo", "in-progress", "testing", "done"];
-    for status in &statuses {
-        let path = base.join("tasks").join(status);
-        fs::create_dir_all(&path)?;
-    }
+    init_in(Path::new(""), project_name)
+}
 
-    // Write default config
-    let config = Config::default(project_name.clone());
-    config.write()?;
+/// Initialize a new repo-tasks repository in `root`
+fn init_in(root: &Path, project_name: Option<String>) -> Result<()> {
+    let repo = Repository::init(root, project_name)?;
+    let config = repo.config()?;
 
-    let project_display = project_name.unwrap_or_else(|| config.project_name.clone());
     utils::success(&format!(
         "Initialized repo-tasks for '{}'",
-        style(&project_display).bold()
+        style(&config.project_name).bold()
     ));
     println!("  {}", style("Created .repo-tasks/config.json").dim());
     println!(
         "  {}",
-        style("Created task directories: todo, in-progress, testing, done").dim()
+        style(format!(
+            "Created task directories: {}",
+            config.statuses.join(", ")
+        ))
+        .dim()
     );
 
     Ok(())
@@ -43,51 +35,34 @@ pub fn init(project_name: Option<String>) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::env;
     use tempfile::TempDir;
 
     #[test]
     fn test_init_creates_structure() {
         let temp_dir = TempDir::new().unwrap();
-        let original_dir = env::current_dir().unwrap();
 
-        // Change to temp directory
-        env::set_current_dir(&temp_dir).unwrap();
-
-        let result = init(Some("test-project".to_string()));
+        let result = init_in(temp_dir.path(), Some("test-project".to_string()));
         assert!(result.is_ok(), "init failed: {:?}", result.err());
 
-        // Verify directories exist (while still in temp dir)
-        assert!(Path::new(".repo-tasks/tasks/todo").exists());
-        assert!(Path::new(".repo-tasks/tasks/in-progress").exists());
-        assert!(Path::new(".repo-tasks/tasks/testing").exists());
-        assert!(Path::new(".repo-tasks/tasks/done").exists());
-        assert!(Path::new(".repo-tasks/config.json").exists());
-
-        // Restore original directory
-        env::set_current_dir(&original_dir).unwrap();
+        // Verify directories exist
+        let base = temp_dir.path().join(".repo-tasks");
+        assert!(base.join("tasks/todo").exists());
+        assert!(base.join("tasks/in-progress").exists());
+        assert!(base.join("tasks/testing").exists());
+        assert!(base.join("tasks/done").exists());
+        assert!(base.join("config.json").exists());
     }
 
-    // TODO: This test has issues with directory isolation in test environment
-    // The functionality works correctly in practice (manually tested)
-    // Will improve test isolation in Phase 3
     #[test]
-    #[ignore]
     fn test_init_fails_if_already_initialized() {
         let temp_dir = TempDir::new().unwrap();
-        let original_dir = env::current_dir().unwrap();
-
-        env::set_current_dir(&temp_dir).unwrap();
 
         // First init should succeed
-        let first_result = init(Some("test-project".to_string()));
+        let first_result = init_in(temp_dir.path(), Some("test-project".to_string()));
         assert!(first_result.is_ok());
 
         // Second init should fail
-        let second_result = init(Some("test-project".to_string()));
-
-        // Restore directory
-        env::set_current_dir(&original_dir).unwrap();
+        let second_result = init_in(temp_dir.path(), Some("test-project".to_string()));
 
         assert!(second_result.is_err());
         assert!(second_result
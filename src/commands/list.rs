@@ -1,10 +1,9 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use console::style;
-use std::path::PathBuf;
-use walkdir::WalkDir;
 
 use crate::utils;
-use crate::{Config, Task};
+use crate::utils::errors::ErrorKind;
+use crate::Repository;
 
 /// List all tasks in a given status
 pub fn list(
@@ -12,75 +11,37 @@ pub fn list(
     priority: Option<String>,
     tag: Option<String>,
     format: Option<String>,
+    json: bool,
 ) -> Result<()> {
     // Check if initialized
-    if !Config::is_initialized() {
-        bail!("Not in a repo-tasks repository. Run 'tasks init' first.");
-    }
+    utils::errors::ensure_initialized()?;
 
-    let config = Config::load()?;
-    let status = status.unwrap_or_else(|| "todo".to_string());
+    let repo = Repository::current()?;
+    let config = repo.config()?;
+    let status = status.unwrap_or_else(|| config.initial_status().to_string());
 
     // Determine output format (CLI flag overrides config)
     let output_format = format.unwrap_or_else(|| config.output_format.clone());
 
     // Validate format
     if output_format != "table" && output_format != "list" {
-        bail!(
-            "Invalid format '{}'. Valid formats: table, list",
-            output_format
-        );
+        return Err(utils::errors::error(
+            ErrorKind::InvalidInput,
+            format!(
+                "Invalid format '{}'. Valid formats: table, list",
+                output_format
+            ),
+        ));
     }
 
-    // Validate status
-    if !config.statuses.contains(&status) {
-        bail!(
-            "Invalid status '{}'. Valid statuses: {}",
-            status,
-            config.statuses.join(", ")
-        );
-    }
+    // Collect tasks, most important first
+    let tasks = repo.list(&status, priority.as_deref(), tag.as_deref())?;
 
-    // Validate priority if provided
-    if let Some(ref p) = priority {
-        if !config.priorities.contains(p) {
-            bail!(
-                "Invalid priority '{}'. Valid priorities: {}",
-                p,
-                config.priorities.join(", ")
-            );
-        }
-    }
-
-    // Build path to status directory
-    let tasks_dir = PathBuf::from(".repo-tasks/tasks").join(&status);
-
-    if !tasks_dir.exists() {
-        println!("No tasks in status '{}'", status);
-        return Ok(());
-    }
-
-    // Collect tasks
-    let mut tasks: Vec<Task> = WalkDir::new(&tasks_dir)
-        .max_depth(1)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("md"))
-        .filter_map(|e| Task::from_file(e.path()).ok())
-        .collect();
-
-    // Apply filters
-    if let Some(ref filter_priority) = priority {
-        tasks.retain(|t| t.priority.as_ref() == Some(filter_priority));
-    }
-
-    if let Some(ref filter_tag) = tag {
-        tasks.retain(|t| {
-            t.tags
-                .as_ref()
-                .map(|tags| tags.iter().any(|t| t == filter_tag))
-                .unwrap_or(false)
-        });
+    if json {
+        return utils::json::print(&serde_json::json!({
+            "status": status,
+            "tasks": utils::json::tasks(&tasks),
+        }));
     }
 
     if tasks.is_empty() {
@@ -92,18 +53,6 @@ pub fn list(
         return Ok(());
     }
 
-    // Sort by priority (Critical > High > Medium > Low)
-    tasks.sort_by(|a, b| {
-        let priority_rank = |p: &Option<String>| match p.as_deref() {
-            Some("Critical") => 0,
-            Some("High") => 1,
-            Some("Medium") => 2,
-            Some("Low") => 3,
-            _ => 4,
-        };
-        priority_rank(&a.priority).cmp(&priority_rank(&b.priority))
-    });
-
     // Print header
     let mut header = format!("Tasks in {}", style(&status).bold().cyan());
     header.push_str(&format!(" ({} total)", style(tasks.len()).bold()));
@@ -0,0 +1,679 @@
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use super::save::push_to_remote;
+use crate::utils;
+use crate::utils::errors::ErrorKind;
+use crate::utils::json::TaskJson;
+use crate::{NewTask, Repository, TaskUpdate};
+
+/// MCP protocol revisions we can speak, newest first
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// URI scheme for task resources (`task://{id}`)
+const TASK_URI_PREFIX: &str = "task://";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// A JSON-RPC error response
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn internal(err: anyhow::Error) -> Self {
+        let message = console::strip_ansi_codes(&format!("{:#}", err)).into_owned();
+        RpcError::new(INTERNAL_ERROR, message)
+    }
+}
+
+/// Serve the Model Context Protocol over stdio until stdin closes
+///
+/// Messages are newline-delimited JSON-RPC 2.0. Nothing but responses is
+/// written to stdout; warnings from the commands underneath go to stderr.
+pub fn mcp() -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_message(&line) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle one line of input, returning the response to send (if any)
+fn handle_message(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)),
+            ))
+        }
+    };
+
+    match message {
+        Value::Array(batch) => {
+            let responses: Vec<Value> = batch.into_iter().filter_map(handle_request).collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        message => handle_request(message),
+    }
+}
+
+/// Handle a single request or notification
+fn handle_request(message: Value) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let method = message.get("method").and_then(Value::as_str);
+
+    let Some(method) = method else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            RpcError::new(INVALID_REQUEST, "Invalid request: missing method"),
+        ));
+    };
+
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = dispatch(method, &params);
+
+    // Notifications never get a response, even when they fail
+    let id = id?;
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => error_response(id, err),
+    })
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+fn dispatch(method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(initialize(params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(params),
+        "resources/list" => list_resources(),
+        "resources/templates/list" => Ok(json!({
+            "resourceTemplates": [{
+                "uriTemplate": format!("{}{{id}}", TASK_URI_PREFIX),
+                "name": "task",
+                "title": "Task",
+                "description": "A task file, by ID or slug",
+                "mimeType": "text/markdown",
+            }]
+        })),
+        "resources/read" => read_resource(params),
+        method if method.starts_with("notifications/") => Ok(Value::Null),
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    // Agree on the client's version if we know it, otherwise offer our newest
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+            "resources": { "listChanged": false, "subscribe": false },
+        },
+        "serverInfo": {
+            "name": "repo-tasks",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": "Manage the tasks stored in this repository's .repo-tasks/ directory. \
+            Tasks are referenced by ID or slug; results use the same JSON as `tasks --json`.",
+    })
+}
+
+/// Tool definitions, with statuses and priorities taken from the config when
+/// there is one
+fn tool_definitions() -> Value {
+    let config = Repository::current().and_then(|repo| repo.config()).ok();
+    let statuses = config.as_ref().map(|c| c.statuses.clone());
+    let priorities = config.as_ref().map(|c| c.priorities.clone());
+    let default_priority = match &config {
+        Some(config) => format!("Priority (defaults to {})", config.default_priority()),
+        None => "Priority (defaults to the middle of the configured priorities)".to_string(),
+    };
+
+    let with_enum = |description: &str, values: &Option<Vec<String>>| {
+        let mut schema = json!({ "type": "string", "description": description });
+        if let Some(values) = values {
+            schema["enum"] = json!(values);
+        }
+        schema
+    };
+
+    let task_id = json!({ "type": "string", "description": "Task ID or slug" });
+    let string_list = |description: &str| json!({ "type": "array", "items": { "type": "string" }, "description": description });
+    let force = json!({
+        "type": "boolean",
+        "description": "Ignore unfinished dependencies",
+        "default": false,
+    });
+
+    json!([
+        {
+            "name": "list_tasks",
+            "description": "List tasks in a status (the first status, e.g. todo, by default), optionally filtered by priority and tag.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": with_enum("Status to list", &statuses),
+                    "priority": with_enum("Only tasks with this priority", &priorities),
+                    "tag": { "type": "string", "description": "Only tasks with this tag" },
+                },
+            },
+        },
+        {
+            "name": "show_task",
+            "description": "Show a task's details, including its description and dependencies.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "task_id": task_id },
+                "required": ["task_id"],
+            },
+        },
+        {
+            "name": "create_task",
+            "description": "Create a new task.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string", "description": "Task title" },
+                    "priority": with_enum(&default_priority, &priorities),
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Tags",
+                    },
+                    "notes": { "type": "string", "description": "Task description" },
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "update_task",
+            "description": "Change a task's fields. Only the fields given are changed.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "task_id": task_id,
+                    "title": { "type": "string", "description": "New title (renames the file)" },
+                    "priority": with_enum("New priority", &priorities),
+                    "add_tags": string_list("Tags to add"),
+                    "remove_tags": string_list("Tags to remove"),
+                    "body": { "type": "string", "description": "New description, replacing the old one" },
+                    "append_body": { "type": "string", "description": "Text to add to the end of the description" },
+                    "depends_on": string_list("IDs of tasks that must be done first"),
+                    "blocks": string_list("IDs of tasks that can't start until this one is done"),
+                    "set": {
+                        "type": "object",
+                        "description": "Other frontmatter fields to set, like Assignee; null removes a field",
+                    },
+                },
+                "required": ["task_id"],
+            },
+        },
+        {
+            "name": "move_task",
+            "description": "Move a task to another status. Refused while the task has unfinished dependencies unless force is set.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "task_id": task_id,
+                    "new_status": with_enum("Status to move to", &statuses),
+                    "force": force,
+                },
+                "required": ["task_id", "new_status"],
+            },
+        },
+        {
+            "name": "start_task",
+            "description": "Start working on a task: move it to the started status (in-progress by default) and check out a git branch named {id}-{slug}.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "task_id": task_id, "force": force },
+                "required": ["task_id"],
+            },
+        },
+        {
+            "name": "search_tasks",
+            "description": "Search all task files for lines matching a regex.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Regex to search for" },
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "save_tasks",
+            "description": "Commit changes under .repo-tasks/ to git, optionally pushing them.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "message": {
+                        "type": "string",
+                        "description": "Commit message (generated if omitted)",
+                    },
+                    "push": { "type": "boolean", "description": "Push after committing", "default": false },
+                },
+            },
+        },
+    ])
+}
+
+fn call_tool(params: &Value) -> Result<Value, RpcError> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing tool name"))?;
+
+    let empty = Map::new();
+    let args = match params.get("arguments") {
+        None | Some(Value::Null) => &empty,
+        Some(Value::Object(args)) => args,
+        Some(_) => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                "Tool arguments must be an object",
+            ))
+        }
+    };
+
+    let result = match name {
+        "list_tasks" => list_tasks(args),
+        "show_task" => show_task(args),
+        "create_task" => create_task(args),
+        "update_task" => update_task(args),
+        "move_task" => move_task(args),
+        "start_task" => start_task(args),
+        "search_tasks" => search(args),
+        "save_tasks" => save_tasks(args),
+        name => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown tool: {}", name),
+            ))
+        }
+    };
+
+    // Tool failures are results the model can read, not protocol errors
+    Ok(match result {
+        Ok(value) => json!({
+            "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap_or_default() }],
+            "structuredContent": value,
+            "isError": false,
+        }),
+        Err(err) => {
+            let error = utils::errors::to_json(&err);
+            json!({
+                "content": [{ "type": "text", "text": error["error"]["message"] }],
+                "structuredContent": error,
+                "isError": true,
+            })
+        }
+    })
+}
+
+fn list_tasks(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+
+    let status = match string_arg(args, "status")? {
+        Some(status) => status,
+        None => repo.config()?.initial_status().to_string(),
+    };
+    let priority = string_arg(args, "priority")?;
+    let tag = string_arg(args, "tag")?;
+
+    let tasks = repo.list(&status, priority.as_deref(), tag.as_deref())?;
+
+    Ok(json!({ "status": status, "tasks": utils::json::tasks(&tasks) }))
+}
+
+fn show_task(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+    let task = Repository::current()?.find(&required_arg(args, "task_id")?)?;
+
+    Ok(json!({ "task": TaskJson::from(&task) }))
+}
+
+fn create_task(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+
+    // Accept a list of tags, or a comma-separated string like the CLI
+    let tags: Vec<String> = match args.get("tags") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(tags)) => tags.split(',').map(str::to_string).collect(),
+        Some(Value::Array(tags)) => tags
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(_) => return Err(invalid_argument("tags", "a list of strings")),
+    };
+
+    let task = Repository::current()?.create(NewTask {
+        title: required_arg(args, "title")?,
+        priority: string_arg(args, "priority")?,
+        tags,
+        body: string_arg(args, "notes")?.unwrap_or_default(),
+    })?;
+
+    Ok(json!({ "task": TaskJson::from(&task) }))
+}
+
+fn update_task(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+
+    let set = match args.get("set") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Object(fields)) => fields
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), serde_yaml::to_value(value)?)))
+            .collect::<Result<_>>()?,
+        Some(_) => return Err(invalid_argument("set", "an object")),
+    };
+
+    let task = repo.find(&required_arg(args, "task_id")?)?;
+    let outcome = repo.update(
+        task,
+        TaskUpdate {
+            title: string_arg(args, "title")?,
+            priority: string_arg(args, "priority")?,
+            add_tags: list_arg(args, "add_tags")?,
+            remove_tags: list_arg(args, "remove_tags")?,
+            body: string_arg(args, "body")?,
+            append_body: string_arg(args, "append_body")?,
+            depends_on: list_arg(args, "depends_on")?,
+            blocks: list_arg(args, "blocks")?,
+            set,
+        },
+    )?;
+
+    Ok(json!({
+        "task": TaskJson::from(&outcome.task),
+        "updated": true,
+        "renamed_from": outcome.renamed_from.map(|p| p.display().to_string()),
+        "linked": utils::json::tasks(&outcome.linked),
+    }))
+}
+
+fn move_task(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+
+    let new_status = required_arg(args, "new_status")?;
+    let task = repo.find(&required_arg(args, "task_id")?)?;
+    let outcome = repo.move_task(task, &new_status, bool_arg(args, "force")?)?;
+
+    Ok(json!({
+        "task": TaskJson::from(&outcome.task),
+        "from": outcome.from,
+        "to": outcome.task.status,
+        "moved": outcome.moved,
+        "unblocked": utils::json::tasks(&outcome.unblocked),
+    }))
+}
+
+fn start_task(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+
+    let task = repo.find(&required_arg(args, "task_id")?)?;
+    let outcome = repo.start(task, bool_arg(args, "force")?)?;
+
+    Ok(json!({
+        "task": TaskJson::from(&outcome.task),
+        "from": outcome.from,
+        "moved": outcome.moved,
+        "branch": outcome.branch,
+        "branch_created": outcome.branch_created,
+    }))
+}
+
+fn search(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+    let query = required_arg(args, "query")?;
+    let matches = Repository::current()?.search(&query)?;
+
+    Ok(json!({ "query": query, "matches": matches }))
+}
+
+fn save_tasks(args: &Map<String, Value>) -> Result<Value> {
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+
+    let mut outcome = repo.save(string_arg(args, "message")?)?;
+    outcome.pushed =
+        outcome.committed && bool_arg(args, "push")? && push_to_remote(&repo.git()?, true)?;
+
+    Ok(serde_json::to_value(outcome)?)
+}
+
+fn list_resources() -> Result<Value, RpcError> {
+    utils::errors::ensure_initialized().map_err(RpcError::internal)?;
+    let tasks = Repository::current()
+        .and_then(|repo| repo.all())
+        .map_err(RpcError::internal)?;
+
+    let resources: Vec<Value> = tasks
+        .iter()
+        .map(|task| {
+            json!({
+                "uri": format!("{}{}", TASK_URI_PREFIX, task.id),
+                "name": format!("{}-{}", task.id, task.slug),
+                "title": task.title,
+                "description": format!(
+                    "{} · {}",
+                    task.status,
+                    task.priority.as_deref().unwrap_or("Medium")
+                ),
+                "mimeType": "text/markdown",
+            })
+        })
+        .collect();
+
+    Ok(json!({ "resources": resources }))
+}
+
+fn read_resource(params: &Value) -> Result<Value, RpcError> {
+    let uri = params
+        .get("uri")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing resource uri"))?;
+
+    let reference = uri
+        .strip_prefix(TASK_URI_PREFIX)
+        .ok_or_else(|| RpcError::new(RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri)))?;
+
+    utils::errors::ensure_initialized().map_err(RpcError::internal)?;
+    let store = Repository::current().map_err(RpcError::internal)?.store();
+    let task = store
+        .find(reference)
+        .map_err(|err| match utils::errors::classify(&err) {
+            ErrorKind::NotFound => {
+                RpcError::new(RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri))
+            }
+            _ => RpcError::internal(err),
+        })?;
+
+    let path = task.path.clone().unwrap_or_else(|| store.task_path(&task));
+    let text = fs::read_to_string(&path).map_err(|e| RpcError::internal(e.into()))?;
+
+    Ok(json!({
+        "contents": [{ "uri": uri, "mimeType": "text/markdown", "text": text }]
+    }))
+}
+
+fn string_arg(args: &Map<String, Value>, name: &str) -> Result<Option<String>> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(invalid_argument(name, "a string")),
+    }
+}
+
+fn required_arg(args: &Map<String, Value>, name: &str) -> Result<String> {
+    string_arg(args, name)?.ok_or_else(|| {
+        utils::errors::error(
+            ErrorKind::InvalidInput,
+            format!("Missing required argument '{}'", name),
+        )
+    })
+}
+
+fn list_arg(args: &Map<String, Value>, name: &str) -> Result<Vec<String>> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| invalid_argument(name, "a list of strings"))
+            })
+            .collect(),
+        Some(_) => Err(invalid_argument(name, "a list of strings")),
+    }
+}
+
+fn bool_arg(args: &Map<String, Value>, name: &str) -> Result<bool> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(_) => Err(invalid_argument(name, "a boolean")),
+    }
+}
+
+fn invalid_argument(name: &str, expected: &str) -> anyhow::Error {
+    utils::errors::error(
+        ErrorKind::InvalidInput,
+        format!("Argument '{}' must be {}", name, expected),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, params: Value) -> Value {
+        let line = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        handle_message(&line.to_string()).expect("requests get a response")
+    }
+
+    #[test]
+    fn test_initialize_negotiates_version() {
+        let response = request("initialize", json!({ "protocolVersion": "2024-11-05" }));
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(response["result"]["serverInfo"]["name"], "repo-tasks");
+
+        let response = request("initialize", json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+    }
+
+    #[test]
+    fn test_tools_list_names() {
+        let response = request("tools/list", Value::Null);
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "list_tasks",
+                "show_task",
+                "create_task",
+                "update_task",
+                "move_task",
+                "start_task",
+                "search_tasks",
+                "save_tasks"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let line = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_message(&line.to_string()).is_none());
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let response = handle_message("{not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert!(response["id"].is_null());
+
+        let response = request("tasks/explode", Value::Null);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = request("tools/call", json!({ "name": "explode" }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_tool_argument_errors_are_results() {
+        let mut args = Map::new();
+        args.insert("task_id".to_string(), json!(42));
+
+        let err = required_arg(&args, "task_id").unwrap_err();
+        assert_eq!(utils::errors::classify(&err), ErrorKind::InvalidInput);
+        assert!(required_arg(&Map::new(), "task_id").is_err());
+        assert!(!bool_arg(&Map::new(), "force").unwrap());
+
+        args.insert("tags".to_string(), json!(["cli", 3]));
+        assert!(list_arg(&args, "tags").is_err());
+        assert!(list_arg(&Map::new(), "tags").unwrap().is_empty());
+    }
+}
@@ -1,23 +1,47 @@
+pub mod config;
+pub mod dependencies;
+pub mod doctor;
+pub mod graph;
+pub mod history;
 pub mod hooks;
 pub mod init;
 pub mod list;
+pub mod mcp;
 pub mod move_task;
 pub mod new;
+pub mod next;
 pub mod open;
+pub mod resolve;
 pub mod save;
+pub mod schema;
 pub mod search;
 pub mod show;
 pub mod start;
 pub mod update;
 
-pub use hooks::{install as hooks_install, list as hooks_list, uninstall as hooks_uninstall};
+pub use config::{
+    add_priority as config_add_priority, add_status as config_add_status, get as config_get,
+    remove_priority as config_remove_priority, remove_status as config_remove_status,
+    rename_status as config_rename_status, set as config_set,
+};
+pub use doctor::doctor;
+pub use graph::graph;
+pub use history::scan as history_scan;
+pub use hooks::{
+    install as hooks_install, list as hooks_list, run as hooks_run, show_log as hooks_log,
+    uninstall as hooks_uninstall,
+};
 pub use init::init;
 pub use list::list;
+pub use mcp::mcp;
 pub use move_task::move_task;
 pub use new::new;
+pub use next::next;
 pub use open::open;
+pub use resolve::resolve_task;
 pub use save::save;
+pub use schema::schema;
 pub use search::search;
 pub use show::show;
 pub use start::start;
-pub use update::update;
+pub use update::{update, UpdateFlags};
@@ -1,84 +1,64 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use console::style;
-use std::path::PathBuf;
-use walkdir::WalkDir;
 
+use super::dependencies::report_unblocked;
+use super::resolve_task;
 use crate::utils;
-use crate::{Config, Task};
+use crate::{Repository, Task};
 
 /// Move a task to a different status
-pub fn move_task(slug_or_id: String, new_status: String) -> Result<()> {
+pub fn move_task(slug_or_id: String, new_status: String, force: bool, json: bool) -> Result<()> {
     // Check if initialized
-    if !Config::is_initialized() {
-        bail!("Not in a repo-tasks repository. Run 'tasks init' first.");
-    }
+    utils::errors::ensure_initialized()?;
 
-    let config = Config::load()?;
+    let repo = Repository::current()?;
+    let config = repo.config()?;
 
     // Validate new status
     if !config.statuses.contains(&new_status) {
-        bail!(
-            "Invalid status '{}'. Valid statuses: {}",
-            new_status,
-            config.statuses.join(", ")
-        );
+        return Err(utils::errors::invalid_status(&new_status, &config.statuses));
     }
 
     // Find the task
-    let tasks_base = PathBuf::from(".repo-tasks/tasks");
-    let mut found_task: Option<(Task, PathBuf)> = None;
+    let task = resolve_task(&repo.store(), &slug_or_id)?;
 
-    for entry in WalkDir::new(&tasks_base)
-        .min_depth(2)
-        .max_depth(2)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("md"))
-    {
-        let filename = entry.file_name().to_string_lossy();
+    // Refuse to move ahead of unfinished dependencies
+    let outcome = repo.move_task(task, &new_status, force)?;
+    let task = &outcome.task;
 
-        if filename.contains(&slug_or_id) {
-            if let Ok(task) = Task::from_file(entry.path()) {
-                found_task = Some((task, entry.path().to_path_buf()));
-                break;
-            }
-        }
+    if json {
+        return print_json(task, &outcome.from, outcome.moved, &outcome.unblocked);
     }
 
-    let (mut task, old_path) = match found_task {
-        Some(t) => t,
-        None => bail!("Task not found: {}", slug_or_id),
-    };
-
     // Check if already in target status
-    if task.status == new_status {
+    if !outcome.moved {
         println!("Task is already in status '{}'", new_status);
         return Ok(());
     }
 
-    let old_status = task.status.clone();
-
-    // Update status
-    task.status = new_status.clone();
-
-    // Determine new path
-    let new_path = task.file_path(&new_status);
-
-    // Save to new location
-    task.to_file(&new_path)?;
-
-    // Remove from old location
-    std::fs::remove_file(&old_path)?;
+    let new_path = repo.store().task_path(task);
 
     utils::success(&format!("Moved task: {}", style(&task.title).bold()));
     println!(
         "  {} {} {} {}",
         style("From:").dim(),
-        utils::status_badge(&old_status),
+        utils::status_badge(&outcome.from),
         style("→").dim(),
         utils::status_badge(&new_status)
     );
     println!("  {}", style(new_path.display()).dim());
 
+    report_unblocked(&outcome.unblocked);
+
     Ok(())
 }
+
+fn print_json(task: &Task, from: &str, moved: bool, unblocked: &[Task]) -> Result<()> {
+    utils::json::print(&serde_json::json!({
+        "task": utils::json::TaskJson::from(task),
+        "from": from,
+        "to": task.status,
+        "moved": moved,
+        "unblocked": utils::json::tasks(unblocked),
+    }))
+}
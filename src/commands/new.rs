@@ -1,9 +1,10 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use console::style;
 use dialoguer::{Input, Select};
 
 use crate::utils;
-use crate::{Config, Task};
+use crate::utils::errors::ErrorKind;
+use crate::{NewTask, Repository};
 
 /// Create a new task, either interactively or with command-line arguments
 pub fn new(
@@ -11,13 +12,13 @@ pub fn new(
     priority_arg: Option<String>,
     tags_arg: Option<String>,
     notes_arg: Option<String>,
+    json: bool,
 ) -> Result<()> {
     // Check if initialized
-    if !Config::is_initialized() {
-        bail!("Not in a repo-tasks repository. Run 'tasks init' first.");
-    }
+    utils::errors::ensure_initialized()?;
 
-    let config = Config::load()?;
+    let repo = Repository::current()?;
+    let config = repo.config()?;
 
     // Determine if we're in non-interactive mode
     let non_interactive = title_arg.is_some();
@@ -25,83 +26,72 @@ pub fn new(
     let title = if let Some(t) = title_arg {
         // Non-interactive mode
         if t.trim().is_empty() {
-            bail!("Task title cannot be empty");
+            return Err(empty_title());
         }
         t.trim().to_string()
     } else {
         // Interactive mode - check if we have a TTY
         if !atty::is(atty::Stream::Stdin) {
-            bail!(
+            return Err(utils::errors::error(
+                ErrorKind::Usage,
                 "Not running in a terminal and no title provided.\n\
                 \n\
                 Use non-interactive mode:\n\
                 \n\
                 \x1b[1m  tasks new --title \"Your task title\" --priority High\x1b[0m\n\
                 \n\
-                Or run in an interactive terminal."
-            );
+                Or run in an interactive terminal.",
+            ));
         }
 
         // Prompt for title
         let input: String = Input::new().with_prompt("Task title").interact_text()?;
 
         if input.trim().is_empty() {
-            bail!("Task title cannot be empty");
+            return Err(empty_title());
         }
         input.trim().to_string()
     };
 
-    let priority = if let Some(p) = priority_arg {
-        // Validate priority
-        if !config.priorities.contains(&p) {
-            bail!(
-                "Invalid priority: '{}'\n\
-                \n\
-                Valid priorities are: {}",
-                p,
-                config.priorities.join(", ")
-            );
-        }
-        p
-    } else if non_interactive {
-        // Default to Medium in non-interactive mode
-        "Medium".to_string()
+    let priority = if priority_arg.is_some() || non_interactive {
+        // Validated on creation, and the default priority if not given
+        priority_arg
     } else {
         // Interactive mode - prompt for priority
         let priority_index = Select::new()
             .with_prompt("Priority")
             .items(&config.priorities)
-            .default(1) // Default to "Medium" (index 1)
+            .default(
+                config
+                    .priorities
+                    .iter()
+                    .position(|p| p == config.default_priority())
+                    .unwrap_or_default(),
+            )
             .interact()?;
 
-        config.priorities[priority_index].clone()
+        Some(config.priorities[priority_index].clone())
     };
 
-    // Create task
-    let mut task = Task::new(title, priority);
-
-    // Add tags if provided
-    if let Some(tags_str) = tags_arg {
-        let tags: Vec<String> = tags_str
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-        if !tags.is_empty() {
-            task.tags = Some(tags);
-        }
+    let tags = tags_arg
+        .map(|tags| tags.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+
+    // Save task with an ID that's unique in this repository
+    let task = repo.create(NewTask {
+        title,
+        priority,
+        tags,
+        body: notes_arg.unwrap_or_default(),
+    })?;
+    let path = repo.store().task_path(&task);
+
+    if json {
+        return utils::json::print(&serde_json::json!({
+            "task": utils::json::TaskJson::from(&task),
+        }));
     }
 
-    // Add notes/body if provided
-    if let Some(notes) = notes_arg {
-        task.body = notes;
-    }
-
-    let path = task.file_path("todo");
-
-    // Save task
-    task.to_file(&path)?;
-
     utils::success(&format!("Created task: {}", style(&task.slug).bold()));
     println!("  ID: {}", style(&task.id).dim());
     println!(
@@ -115,3 +105,7 @@ pub fn new(
 
     Ok(())
 }
+
+fn empty_title() -> anyhow::Error {
+    utils::errors::error(ErrorKind::InvalidInput, "Task title cannot be empty")
+}
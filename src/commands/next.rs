@@ -0,0 +1,88 @@
+use anyhow::Result;
+use console::style;
+use serde::Serialize;
+
+use super::start::start_task;
+use crate::utils;
+use crate::Repository;
+
+/// A ready task as printed by `tasks next --json`
+#[derive(Serialize)]
+struct ReadyJson<'a> {
+    #[serde(flatten)]
+    task: utils::json::TaskJson<'a>,
+    unblocks: usize,
+}
+
+/// Show the tasks that are ready to be worked on, best candidate first
+pub fn next(limit: Option<usize>, start_top: bool, json: bool) -> Result<()> {
+    // Check if initialized
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
+    let config = repo.config()?;
+    let graph = repo.store().dependency_graph()?;
+
+    let mut ready = graph.ready_tasks(&config);
+    if let Some(limit) = limit {
+        ready.truncate(limit);
+    }
+
+    if json && !start_top {
+        let tasks: Vec<_> = ready
+            .iter()
+            .map(|entry| ReadyJson {
+                task: utils::json::TaskJson::from(entry.task),
+                unblocks: entry.unblocks,
+            })
+            .collect();
+
+        return utils::json::print(&serde_json::json!({ "tasks": tasks }));
+    }
+
+    if ready.is_empty() {
+        println!("No tasks are ready to work on");
+        return Ok(());
+    }
+
+    if start_top {
+        let top = ready[0].task;
+        if !json {
+            println!(
+                "{} {} {}",
+                style("Next up:").cyan(),
+                utils::task_id(&top.id),
+                style(&top.title).bold()
+            );
+            println!();
+        }
+        return start_task(&repo, top.clone(), false, json);
+    }
+
+    println!("Ready to work on ({} total)", style(ready.len()).bold());
+    println!();
+
+    for (index, entry) in ready.iter().enumerate() {
+        let task = entry.task;
+        let task_priority = task.priority.as_deref().unwrap_or("Medium");
+
+        print!(
+            "{:>3}. {} [{}] {} - {}",
+            index + 1,
+            utils::priority_badge(task_priority),
+            utils::task_id(&task.id),
+            utils::task_slug(&task.slug),
+            task.title
+        );
+
+        if entry.unblocks > 0 {
+            print!(
+                " {}",
+                style(format!("(unblocks {})", entry.unblocks)).green()
+            );
+        }
+
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use dialoguer::Select;
+
+use crate::utils::errors;
+use crate::{Task, TaskStore};
+
+/// Resolve a task reference, prompting the user to pick one if it is ambiguous
+///
+/// When several tasks match and we're attached to a terminal, a picker is
+/// shown. Otherwise (scripts, hooks, CI) the command fails with the list of
+/// candidates so nothing is ever acted on by accident.
+pub fn resolve_task(store: &TaskStore, reference: &str) -> Result<Task> {
+    if !(atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stdout)) {
+        return store.find(reference);
+    }
+
+    let mut matches = store.find_matches(reference)?;
+
+    if matches.len() < 2 {
+        return matches
+            .pop()
+            .ok_or_else(|| errors::task_not_found(reference.trim()));
+    }
+
+    let items: Vec<String> = matches
+        .iter()
+        .map(|t| format!("{}  {} ({})", t.id, t.title, t.status))
+        .collect();
+
+    let index = Select::new()
+        .with_prompt(format!(
+            "'{}' matches several tasks, pick one",
+            reference.trim()
+        ))
+        .items(&items)
+        .default(0)
+        .interact()?;
+
+    Ok(matches.remove(index))
+}
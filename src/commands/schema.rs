@@ -0,0 +1,26 @@
+use anyhow::Result;
+
+use crate::models::schema::{config_schema, task_schema};
+use crate::utils;
+use crate::utils::errors::ErrorKind;
+use crate::Repository;
+
+/// Print the JSON Schema for config.json or task frontmatter
+pub fn schema(target: String) -> Result<()> {
+    // Fill in the project's statuses and priorities when the config loads
+    // (not required, since the schema helps fix a broken config)
+    let config = Repository::current().and_then(|repo| repo.config()).ok();
+
+    let schema = match target.as_str() {
+        "config" => config_schema(config.as_ref()),
+        "task" => task_schema(config.as_ref()),
+        _ => {
+            return Err(utils::errors::error(
+                ErrorKind::InvalidInput,
+                format!("Unknown schema '{}'. Valid options: config, task", target),
+            ))
+        }
+    };
+
+    utils::json::print(&schema)
+}
@@ -1,91 +1,75 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use console::style;
-use std::path::PathBuf;
-use walkdir::WalkDir;
 
+use super::resolve_task;
 use crate::utils;
-use crate::{Config, Task};
+use crate::Repository;
 
 /// Show details of a specific task
-pub fn show(slug_or_id: String) -> Result<()> {
+pub fn show(slug_or_id: String, json: bool) -> Result<()> {
     // Check if initialized
-    if !Config::is_initialized() {
-        bail!("Not in a repo-tasks repository. Run 'tasks init' first.");
-    }
+    utils::errors::ensure_initialized()?;
+    let repo = Repository::current()?;
 
     // Search for task across all status directories
-    let tasks_base = PathBuf::from(".repo-tasks/tasks");
-
-    let mut found_task: Option<Task> = None;
+    let task = resolve_task(&repo.store(), &slug_or_id)?;
 
-    for entry in WalkDir::new(&tasks_base)
-        .min_depth(2)
-        .max_depth(2)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("md"))
-    {
-        let filename = entry.file_name().to_string_lossy();
-
-        // Check if filename contains the slug or ID
-        if filename.contains(&slug_or_id) {
-            if let Ok(task) = Task::from_file(entry.path()) {
-                found_task = Some(task);
-                break;
-            }
-        }
+    if json {
+        return utils::json::print(&serde_json::json!({
+            "task": utils::json::TaskJson::from(&task),
+        }));
     }
 
-    match found_task {
-        Some(task) => {
-            println!(
-                "{}: {}",
-                style("Task").bold(),
-                style(&task.title).bold().cyan()
-            );
-            println!("{}: {}", style("ID").dim(), utils::task_id(&task.id));
-            println!("{}: {}", style("Slug").dim(), utils::task_slug(&task.slug));
-            println!(
-                "{}: {}",
-                style("Status").dim(),
-                utils::status_badge(&task.status)
-            );
+    println!(
+        "{}: {}",
+        style("Task").bold(),
+        style(&task.title).bold().cyan()
+    );
+    println!("{}: {}", style("ID").dim(), utils::task_id(&task.id));
+    println!("{}: {}", style("Slug").dim(), utils::task_slug(&task.slug));
+    println!(
+        "{}: {}",
+        style("Status").dim(),
+        utils::status_badge(&task.status)
+    );
 
-            if let Some(priority) = &task.priority {
-                println!(
-                    "{}: {}",
-                    style("Priority").dim(),
-                    utils::priority_badge(priority)
-                );
-            }
-
-            if let Some(tags) = &task.tags {
-                if !tags.is_empty() {
-                    println!("{}: {}", style("Tags").dim(), utils::tags(tags));
-                }
-            }
-
-            if let Some(blocks) = &task.blocks {
-                if !blocks.is_empty() {
-                    println!("{}: {}", style("Blocks").dim(), blocks.join(", "));
-                }
-            }
+    if let Some(priority) = &task.priority {
+        println!(
+            "{}: {}",
+            style("Priority").dim(),
+            utils::priority_badge(priority)
+        );
+    }
 
-            if let Some(depends_on) = &task.depends_on {
-                if !depends_on.is_empty() {
-                    println!("{}: {}", style("Depends on").dim(), depends_on.join(", "));
-                }
-            }
+    if let Some(tags) = &task.tags {
+        if !tags.is_empty() {
+            println!("{}: {}", style("Tags").dim(), utils::tags(tags));
+        }
+    }
 
-            if !task.body.is_empty() {
-                println!("\n{}:", style("Description").bold());
-                println!("{}", task.body);
-            }
+    if let Some(blocks) = &task.blocks {
+        if !blocks.is_empty() {
+            println!("{}: {}", style("Blocks").dim(), blocks.join(", "));
+        }
+    }
 
-            Ok(())
+    if let Some(depends_on) = &task.depends_on {
+        if !depends_on.is_empty() {
+            println!("{}: {}", style("Depends on").dim(), depends_on.join(", "));
         }
-        None => {
-            bail!("Task not found: {}", slug_or_id);
+    }
+
+    if let Some(commits) = &task.commits {
+        if !commits.is_empty() {
+            let short: Vec<&str> = commits.iter().map(|c| &c[..c.len().min(7)]).collect();
+            println!("{}: {}", style("Commits").dim(), short.join(", "));
         }
     }
+
+    if !task.body.is_empty() {
+        println!("\n{}:", style("Description").bold());
+        println!("{}", task.body);
+    }
+
+    Ok(())
 }
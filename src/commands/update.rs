@@ -1,49 +1,168 @@
-use anyhow::{bail, Result};
+use anyhow::{Context, Result};
 use console::style;
 use dialoguer::{Input, MultiSelect, Select};
-use std::path::PathBuf;
-use walkdir::WalkDir;
+use serde_yaml::Value;
+use std::io;
+use std::path::Path;
 
+use super::resolve_task;
 use crate::utils;
-use crate::{Config, Task};
+use crate::utils::errors::ErrorKind;
+use crate::{Config, Repository, Task, TaskUpdate};
 
-/// Update task properties
-pub fn update(slug_or_id: String) -> Result<()> {
+/// Changes given as command-line flags
+#[derive(Debug, Default)]
+pub struct UpdateFlags {
+    pub title: Option<String>,
+    pub priority: Option<String>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    pub set_body: Option<String>,
+    pub append_body: Option<String>,
+    pub depends_on: Vec<String>,
+    pub blocks: Vec<String>,
+    /// `KEY=VALUE` pairs
+    pub set: Vec<String>,
+}
+
+/// Update task properties, from flags or interactively
+pub fn update(slug_or_id: String, flags: UpdateFlags, json: bool) -> Result<()> {
     // Check if initialized
-    if !Config::is_initialized() {
-        bail!("Not in a repo-tasks repository. Run 'tasks init' first.");
-    }
+    utils::errors::ensure_initialized()?;
 
-    let config = Config::load()?;
+    let repo = Repository::current()?;
+    let config = repo.config()?;
 
     // Find the task
-    let tasks_base = PathBuf::from(".repo-tasks/tasks");
-    let mut found_task: Option<(Task, PathBuf)> = None;
-
-    for entry in WalkDir::new(&tasks_base)
-        .min_depth(2)
-        .max_depth(2)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("md"))
-    {
-        let filename = entry.file_name().to_string_lossy();
-
-        if filename.contains(&slug_or_id) {
-            if let Ok(task) = Task::from_file(entry.path()) {
-                found_task = Some((task, entry.path().to_path_buf()));
-                break;
-            }
+    let task = resolve_task(&repo.store(), &slug_or_id)?;
+
+    let changes = flags.into_changes()?;
+    let changes = if !changes.is_empty() {
+        changes
+    } else if !atty::is(atty::Stream::Stdin) {
+        return Err(utils::errors::error(
+            ErrorKind::Usage,
+            "Not running in a terminal and no changes given.\n\
+            \n\
+            Pass the changes as options, for example:\n\
+            \n\
+            \x1b[1m  tasks update <task> --priority High --add-tag cli\x1b[0m\n\
+            \n\
+            Or run in an interactive terminal.",
+        ));
+    } else {
+        prompt_changes(&config, &task, json)?
+    };
+
+    if changes.is_empty() {
+        if json {
+            return print_json(&task, false, None, &[]);
+        }
+        println!("No changes made.");
+        return Ok(());
+    }
+
+    let outcome = repo.update(task, changes)?;
+    let task = &outcome.task;
+
+    if json {
+        return print_json(task, true, outcome.renamed_from.as_deref(), &outcome.linked);
+    }
+
+    // If path changed (due to slug change), report the rename
+    if let Some(old_path) = &outcome.renamed_from {
+        utils::success("Updated task and renamed file");
+        println!("  Old: {}", style(old_path.display()).dim());
+        println!(
+            "  New: {}",
+            style(repo.store().task_path(task).display()).dim()
+        );
+    } else {
+        utils::success(&format!("Updated task: {}", style(&task.slug).bold()));
+    }
+
+    for other in &outcome.linked {
+        println!(
+            "  Also updated {} {}",
+            utils::task_id(&other.id),
+            style(&other.title).dim()
+        );
+    }
+
+    Ok(())
+}
+
+impl UpdateFlags {
+    /// The changes the flags ask for, with `-` bodies read from stdin
+    fn into_changes(self) -> Result<TaskUpdate> {
+        if self.set_body.as_deref() == Some("-") && self.append_body.as_deref() == Some("-") {
+            return Err(utils::errors::error(
+                ErrorKind::Usage,
+                "Only one of --set-body and --append-body can read from stdin",
+            ));
         }
+
+        let set = self
+            .set
+            .iter()
+            .map(|pair| parse_field(pair))
+            .collect::<Result<_>>()?;
+
+        Ok(TaskUpdate {
+            title: self.title,
+            priority: self.priority,
+            add_tags: self.add_tags,
+            remove_tags: self.remove_tags,
+            body: self.set_body.map(read_body).transpose()?,
+            append_body: self.append_body.map(read_body).transpose()?,
+            depends_on: self.depends_on,
+            blocks: self.blocks,
+            set,
+        })
+    }
+}
+
+/// Body text, or stdin for `-`
+fn read_body(text: String) -> Result<String> {
+    if text != "-" {
+        return Ok(text);
     }
 
-    let (mut task, old_path) = match found_task {
-        Some(t) => t,
-        None => bail!("Task not found: {}", slug_or_id),
+    io::read_to_string(io::stdin()).context("Failed to read the description from stdin")
+}
+
+/// Parse a `--set KEY=VALUE` pair
+///
+/// The value is read as YAML, so `3` is a number and `[a, b]` a list; an
+/// empty value removes the field.
+fn parse_field(pair: &str) -> Result<(String, Value)> {
+    let Some((key, value)) = pair.split_once('=') else {
+        return Err(utils::errors::error(
+            ErrorKind::InvalidInput,
+            format!("Invalid --set '{}': expected KEY=VALUE", pair),
+        ));
+    };
+
+    let value = if value.trim().is_empty() {
+        Value::Null
+    } else {
+        match serde_yaml::from_str(value) {
+            Ok(Value::Mapping(_)) | Err(_) => Value::String(value.to_string()),
+            Ok(value) => value,
+        }
     };
 
-    println!("Updating task: {}", task.title);
-    println!();
+    Ok((key.trim().to_string(), value))
+}
+
+/// Ask which fields to change and what to change them to
+fn prompt_changes(config: &Config, task: &Task, json: bool) -> Result<TaskUpdate> {
+    let mut changes = TaskUpdate::default();
+
+    if !json {
+        println!("Updating task: {}", task.title);
+        println!();
+    }
 
     // Choose what to update
     let options = vec!["Title", "Priority", "Tags", "Body", "Done"];
@@ -52,11 +171,6 @@ pub fn update(slug_or_id: String) -> Result<()> {
         .items(&options)
         .interact()?;
 
-    if selections.is_empty() {
-        println!("No changes made.");
-        return Ok(());
-    }
-
     // Update selected fields
     for &index in &selections {
         match options[index] {
@@ -66,9 +180,8 @@ pub fn update(slug_or_id: String) -> Result<()> {
                     .with_initial_text(&task.title)
                     .interact_text()?;
 
-                if !new_title.trim().is_empty() {
-                    task.title = new_title.trim().to_string();
-                    task.slug = Task::generate_slug(&task.title);
+                if !new_title.trim().is_empty() && new_title.trim() != task.title {
+                    changes.title = Some(new_title);
                 }
             }
             "Priority" => {
@@ -84,28 +197,28 @@ pub fn update(slug_or_id: String) -> Result<()> {
                     .default(current_idx)
                     .interact()?;
 
-                task.priority = Some(config.priorities[new_priority_idx].clone());
+                changes.priority = Some(config.priorities[new_priority_idx].clone());
             }
             "Tags" => {
-                let current_tags = task.tags.as_ref().map(|t| t.join(", ")).unwrap_or_default();
+                let current: Vec<String> = task.tags.clone().unwrap_or_default();
 
                 let new_tags: String = Input::new()
                     .with_prompt("Tags (comma-separated)")
-                    .with_initial_text(&current_tags)
+                    .with_initial_text(current.join(", "))
                     .allow_empty(true)
                     .interact_text()?;
 
-                if new_tags.trim().is_empty() {
-                    task.tags = None;
-                } else {
-                    task.tags = Some(
-                        new_tags
-                            .split(',')
-                            .map(|s| s.trim().to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect(),
-                    );
-                }
+                let new_tags: Vec<String> = new_tags
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                changes.remove_tags = current
+                    .iter()
+                    .filter(|t| !new_tags.contains(t))
+                    .cloned()
+                    .collect();
+                changes.add_tags = new_tags;
             }
             "Body" => {
                 let new_body: String = Input::new()
@@ -114,32 +227,30 @@ pub fn update(slug_or_id: String) -> Result<()> {
                     .allow_empty(true)
                     .interact_text()?;
 
-                task.body = new_body;
+                changes.body = Some(new_body);
             }
-            "Done" => {
-                // This is handled by selecting "Done" - we'll mark it for completion
-                // but the actual move will be suggested
+            // This is handled by selecting "Done" - we'll mark it for completion
+            // but the actual move will be suggested
+            "Done" if !json => {
                 println!("To mark as done, use 'tasks move {} done'", task.slug);
             }
             _ => {}
         }
     }
 
-    // Determine new path (slug might have changed)
-    let new_path = task.file_path(&task.status);
-
-    // Save the task
-    task.to_file(&new_path)?;
-
-    // If path changed (due to slug change), remove old file
-    if old_path != new_path {
-        std::fs::remove_file(&old_path)?;
-        utils::success("Updated task and renamed file");
-        println!("  Old: {}", style(old_path.display()).dim());
-        println!("  New: {}", style(new_path.display()).dim());
-    } else {
-        utils::success(&format!("Updated task: {}", style(&task.slug).bold()));
-    }
+    Ok(changes)
+}
 
-    Ok(())
+fn print_json(
+    task: &Task,
+    updated: bool,
+    renamed_from: Option<&Path>,
+    linked: &[Task],
+) -> Result<()> {
+    utils::json::print(&serde_json::json!({
+        "task": utils::json::TaskJson::from(task),
+        "updated": updated,
+        "renamed_from": renamed_from.map(|p| p.display().to_string()),
+        "linked": utils::json::tasks(linked),
+    }))
 }
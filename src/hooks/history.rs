@@ -0,0 +1,266 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::parser::parse_commit_message;
+use super::runner::status_updates;
+use crate::{Config, Repository, Task};
+
+/// A commit found by `tasks history scan`
+#[derive(Debug, Clone)]
+pub struct ScannedCommit {
+    pub id: String,
+    pub message: String,
+}
+
+/// A status change a scanned commit asks for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedMove {
+    pub task_id: String,
+    pub title: String,
+    pub from: String,
+    pub to: String,
+    /// The commit that asked for it
+    pub commit: String,
+    pub keyword: String,
+}
+
+/// Scanned commits that reference a task and aren't recorded on it yet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedLink {
+    pub task_id: String,
+    pub title: String,
+    pub commits: Vec<String>,
+}
+
+/// What a history scan would change
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScanPlan {
+    /// In the order of the commits that asked for them
+    pub moves: Vec<PlannedMove>,
+    pub links: Vec<PlannedLink>,
+}
+
+impl ScanPlan {
+    /// Whether the scan found nothing to change
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty() && self.links.is_empty()
+    }
+}
+
+/// Work out what a run of commits, oldest first, means for the tasks
+///
+/// Each task moves to the latest status (in `statuses` order) any commit asks
+/// for, and only forward: history never reopens a task that has since moved
+/// on. References to tasks that don't exist are ignored, and commits already
+/// recorded on a task aren't linked again, so scanning is safe to repeat.
+pub fn plan(commits: &[ScannedCommit], config: &Config, tasks: &[Task]) -> Result<ScanPlan> {
+    let rank = |status: &str| config.statuses.iter().position(|s| s == status);
+    let find = |id: &str| tasks.iter().find(|t| t.id == id);
+
+    // Task ID to (index of the commit, move)
+    let mut moves: HashMap<String, (usize, PlannedMove)> = HashMap::new();
+    let mut links: Vec<PlannedLink> = Vec::new();
+
+    for (index, commit) in commits.iter().enumerate() {
+        for update in status_updates(&commit.message, config)? {
+            let Some(task) = find(&update.task_id) else {
+                continue;
+            };
+
+            let target = rank(&update.target_status);
+            let best = match moves.get(&task.id) {
+                Some((_, planned)) => rank(&planned.to),
+                None => rank(&task.status),
+            };
+            if target <= best {
+                continue;
+            }
+
+            let planned = PlannedMove {
+                task_id: task.id.clone(),
+                title: task.title.clone(),
+                from: task.status.clone(),
+                to: update.target_status,
+                commit: commit.id.clone(),
+                keyword: update.keyword,
+            };
+            moves.insert(task.id.clone(), (index, planned));
+        }
+
+        for id in parse_commit_message(&commit.message, config)?.task_ids {
+            let Some(task) = find(&id) else {
+                continue;
+            };
+            if task.commits.iter().flatten().any(|c| *c == commit.id) {
+                continue;
+            }
+
+            match links.iter_mut().find(|l| l.task_id == id) {
+                Some(link) => link.commits.push(commit.id.clone()),
+                None => links.push(PlannedLink {
+                    task_id: id,
+                    title: task.title.clone(),
+                    commits: vec![commit.id.clone()],
+                }),
+            }
+        }
+    }
+
+    let mut moves: Vec<(usize, PlannedMove)> = moves.into_values().collect();
+    moves.sort_by_key(|(index, _)| *index);
+
+    Ok(ScanPlan {
+        moves: moves.into_iter().map(|(_, planned)| planned).collect(),
+        links,
+    })
+}
+
+/// Where the last scan stopped, kept in `.repo-tasks/history.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanState {
+    /// The newest commit the last applied scan covered
+    pub last_scanned: Option<String>,
+}
+
+impl ScanState {
+    fn path(repo: &Repository) -> PathBuf {
+        repo.tasks_dir().join("history.json")
+    }
+
+    /// Load a repository's state, or a blank one if nothing has been scanned yet
+    pub fn load(repo: &Repository) -> Result<Self> {
+        let path = Self::path(repo);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content =
+            fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).context(format!("Failed to parse {}", path.display()))
+    }
+
+    /// Save the state in the repository
+    pub fn save(&self, repo: &Repository) -> Result<()> {
+        let path = Self::path(repo);
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content + "\n").context(format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config::default(Some("repo-tasks".to_string()))
+    }
+
+    fn commit(id: &str, message: &str) -> ScannedCommit {
+        ScannedCommit {
+            id: id.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn task(id: &str, status: &str) -> Task {
+        let mut task = Task::new(format!("Task {}", id), "High".to_string());
+        task.id = id.to_string();
+        task.status = status.to_string();
+        task
+    }
+
+    #[test]
+    fn test_plan_moves_forward_only() {
+        let tasks = vec![
+            task("20260110142106", "todo"),
+            task("20260110142107", "done"),
+        ];
+        let commits = vec![
+            commit("a1", "[wip] [20260110142106] Start parser"),
+            commit("a2", "Finish parser\n\nCloses #20260110142106"),
+            commit("a3", "[wip] [20260110142106] Tweak parser"),
+            commit("a4", "[wip] [20260110142107] Revisit lexer"),
+        ];
+
+        let plan = plan(&commits, &config(), &tasks).unwrap();
+
+        assert_eq!(
+            plan.moves,
+            vec![PlannedMove {
+                task_id: "20260110142106".to_string(),
+                title: "Task 20260110142106".to_string(),
+                from: "todo".to_string(),
+                to: "done".to_string(),
+                commit: "a2".to_string(),
+                keyword: "closes".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_orders_moves_by_commit() {
+        let tasks = vec![
+            task("20260110142106", "todo"),
+            task("20260110142107", "todo"),
+        ];
+        let commits = vec![
+            commit("a1", "[wip] [20260110142107] Lexer"),
+            commit("a2", "[done] [20260110142106] Parser"),
+            commit("a3", "[done] [20260110142107] Lexer"),
+        ];
+
+        let plan = plan(&commits, &config(), &tasks).unwrap();
+        let moved: Vec<&str> = plan.moves.iter().map(|m| m.task_id.as_str()).collect();
+
+        assert_eq!(moved, vec!["20260110142106", "20260110142107"]);
+        assert_eq!(plan.moves[1].commit, "a3");
+    }
+
+    #[test]
+    fn test_plan_links_new_commits() {
+        let mut linked = task("20260110142106", "todo");
+        linked.commits = Some(vec!["a1".to_string()]);
+        let tasks = vec![linked];
+        let commits = vec![
+            commit("a1", "[20260110142106] Parser"),
+            commit("a2", "See #20260110142106"),
+            commit("a3", "Closes #20260110142199"),
+        ];
+
+        let plan = plan(&commits, &config(), &tasks).unwrap();
+
+        assert!(plan.moves.is_empty());
+        assert_eq!(
+            plan.links,
+            vec![PlannedLink {
+                task_id: "20260110142106".to_string(),
+                title: "Task 20260110142106".to_string(),
+                commits: vec!["a2".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_empty() {
+        let plan = plan(&[commit("a1", "Tidy up")], &config(), &[]).unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_scan_state_is_kept_in_the_repository() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path(), None).unwrap();
+        assert_eq!(ScanState::load(&repo).unwrap(), ScanState::default());
+
+        let state = ScanState {
+            last_scanned: Some("a1".to_string()),
+        };
+        state.save(&repo).unwrap();
+
+        assert!(temp_dir.path().join(".repo-tasks/history.json").exists());
+        assert_eq!(ScanState::load(&repo).unwrap(), state);
+    }
+}
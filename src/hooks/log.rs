@@ -0,0 +1,368 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, SecondsFormat, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::utils::errors::{self, ErrorKind};
+use crate::Repository;
+
+/// Size at which the log is moved aside to `hooks.log.1` and started afresh
+pub const MAX_LOG_BYTES: u64 = 256 * 1024;
+
+/// Something a hook did, stored as one JSON line in `.repo-tasks/hooks.log`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookEvent {
+    /// When it happened, in RFC 3339
+    pub time: String,
+    /// The hook that ran, e.g. `post-commit`
+    pub hook: String,
+    /// The commit the hook acted on, if any
+    pub commit: Option<String>,
+    /// The tasks the hook acted on
+    #[serde(default)]
+    pub task_ids: Vec<String>,
+    /// What the hook did, e.g. `moved to done`
+    pub action: String,
+    /// Why it failed, if it did
+    pub error: Option<String>,
+}
+
+impl HookEvent {
+    /// An event for `hook`, happening now
+    pub fn new(hook: &str, action: impl Into<String>) -> Self {
+        HookEvent {
+            time: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            hook: hook.to_string(),
+            commit: None,
+            task_ids: Vec::new(),
+            action: action.into(),
+            error: None,
+        }
+    }
+
+    /// Record the commit the event is about
+    pub fn commit(mut self, commit: impl Into<String>) -> Self {
+        self.commit = Some(commit.into());
+        self
+    }
+
+    /// Record a task the event is about
+    pub fn task(mut self, task_id: impl Into<String>) -> Self {
+        self.task_ids.push(task_id.into());
+        self
+    }
+
+    /// Record why the hook failed
+    pub fn error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+
+    /// Whether the hook failed
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// When it happened, if the time can be read
+    pub fn timestamp(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.time).ok()
+    }
+}
+
+/// The hook log, rotated once it grows past [`MAX_LOG_BYTES`]
+#[derive(Debug, Clone)]
+pub struct HookLog {
+    path: PathBuf,
+}
+
+impl HookLog {
+    /// The log for a repository, in `.repo-tasks/hooks.log`
+    pub fn for_repository(repo: &Repository) -> Self {
+        Self::new(repo.tasks_dir().join("hooks.log"))
+    }
+
+    /// Create a log stored in the given file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        HookLog { path: path.into() }
+    }
+
+    /// Get the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file older events are moved to
+    fn rotated_path(&self) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(".1");
+        PathBuf::from(name)
+    }
+
+    /// Add an event to the log
+    pub fn append(&self, event: &HookEvent) -> Result<()> {
+        let full = fs::metadata(&self.path)
+            .map(|m| m.len() >= MAX_LOG_BYTES)
+            .unwrap_or(false);
+        if full {
+            fs::rename(&self.path, self.rotated_path())
+                .context(format!("Failed to rotate {}", self.path.display()))?;
+        }
+
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .context(format!("Failed to write {}", self.path.display()))
+    }
+
+    /// All events, oldest first
+    ///
+    /// Lines that aren't events, like those written by older versions, are
+    /// skipped.
+    pub fn read(&self) -> Result<Vec<HookEvent>> {
+        let mut events = Vec::new();
+
+        for path in [self.rotated_path(), self.path.clone()] {
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e).context(format!("Failed to read {}", path.display())),
+            };
+
+            events.extend(
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<HookEvent>(line).ok()),
+            );
+        }
+
+        Ok(events)
+    }
+
+    /// Delete every event
+    pub fn clear(&self) -> Result<()> {
+        for path in [self.rotated_path(), self.path.clone()] {
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                    return Err(e).context(format!("Failed to remove {}", path.display()))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Which events to show
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// Only events about this task
+    pub task_id: Option<String>,
+    /// Only events from this time on
+    pub since: Option<DateTime<FixedOffset>>,
+    /// Only failures
+    pub failures: bool,
+}
+
+impl LogFilter {
+    /// Whether an event passes the filter
+    pub fn matches(&self, event: &HookEvent) -> bool {
+        if self.failures && !event.failed() {
+            return false;
+        }
+
+        if let Some(id) = &self.task_id {
+            if !event.task_ids.contains(id) {
+                return false;
+            }
+        }
+
+        match (self.since, event.timestamp()) {
+            (Some(since), Some(time)) => time >= since,
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Parse a `--since` value: an age like `30m`, `12h`, `7d` or `2w`, a date
+/// like `2026-01-10`, or an RFC 3339 time
+pub fn parse_since(value: &str, now: DateTime<Local>) -> Result<DateTime<FixedOffset>> {
+    let value = value.trim();
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(Local).earliest());
+        if let Some(midnight) = midnight {
+            return Ok(midnight.fixed_offset());
+        }
+    }
+
+    let invalid = || {
+        errors::error(
+            ErrorKind::InvalidInput,
+            format!(
+                "Invalid time '{}': use an age like 30m, 12h, 7d or 2w, or a date like 2026-01-10",
+                value
+            ),
+        )
+    };
+
+    let unit_start = value.char_indices().last().map_or(0, |(i, _)| i);
+    let (amount, unit) = value.split_at(unit_start);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+
+    let age = match unit {
+        "m" => TimeDelta::try_minutes(amount),
+        "h" => TimeDelta::try_hours(amount),
+        "d" => TimeDelta::try_days(amount),
+        "w" => TimeDelta::try_weeks(amount),
+        _ => return Err(invalid()),
+    };
+
+    age.and_then(|age| now.checked_sub_signed(age))
+        .map(|time| time.fixed_offset())
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn event(time: &str, task_id: &str, error: Option<&str>) -> HookEvent {
+        HookEvent {
+            time: time.to_string(),
+            hook: "post-commit".to_string(),
+            commit: Some("abc1234".to_string()),
+            task_ids: vec![task_id.to_string()],
+            action: "moved to done".to_string(),
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = HookLog::new(temp_dir.path().join("hooks.log"));
+
+        assert!(log.read().unwrap().is_empty());
+
+        fs::write(
+            log.path(),
+            "2026-01-10 14:21:06 - post-commit hook triggered\n",
+        )
+        .unwrap();
+        let first = HookEvent::new("post-commit", "moved to done")
+            .commit("abc1234")
+            .task("20260110142106");
+        let second = HookEvent::new("post-checkout", "failed").error("no HEAD");
+        log.append(&first).unwrap();
+        log.append(&second).unwrap();
+
+        assert_eq!(log.read().unwrap(), vec![first, second]);
+
+        log.clear().unwrap();
+        assert!(log.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_log_for_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path(), None).unwrap();
+
+        let log = HookLog::for_repository(&repo);
+        log.append(&HookEvent::new("post-commit", "no status keywords"))
+            .unwrap();
+
+        assert!(temp_dir.path().join(".repo-tasks/hooks.log").exists());
+    }
+
+    #[test]
+    fn test_append_rotates_full_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = HookLog::new(temp_dir.path().join("hooks.log"));
+
+        let old = event("2026-01-10T14:21:06+00:00", "1", None);
+        let line = serde_json::to_string(&old).unwrap() + "\n";
+        let count = MAX_LOG_BYTES as usize / line.len() + 1;
+        fs::write(log.path(), line.repeat(count)).unwrap();
+
+        let new = event("2026-01-11T14:21:06+00:00", "2", None);
+        log.append(&new).unwrap();
+
+        assert!(temp_dir.path().join("hooks.log.1").exists());
+        assert_eq!(fs::read_to_string(log.path()).unwrap().lines().count(), 1);
+
+        let events = log.read().unwrap();
+        assert_eq!(events.len(), count + 1);
+        assert_eq!(events.last(), Some(&new));
+    }
+
+    #[test]
+    fn test_filter() {
+        let ok = event("2026-01-10T14:21:06+00:00", "1", None);
+        let failed = event("2026-01-12T14:21:06+00:00", "2", Some("not found"));
+
+        let all = LogFilter::default();
+        assert!(all.matches(&ok) && all.matches(&failed));
+
+        let failures = LogFilter {
+            failures: true,
+            ..Default::default()
+        };
+        assert!(!failures.matches(&ok) && failures.matches(&failed));
+
+        let task = LogFilter {
+            task_id: Some("1".to_string()),
+            ..Default::default()
+        };
+        assert!(task.matches(&ok) && !task.matches(&failed));
+
+        let since = LogFilter {
+            since: DateTime::parse_from_rfc3339("2026-01-11T00:00:00+00:00").ok(),
+            ..Default::default()
+        };
+        assert!(!since.matches(&ok) && since.matches(&failed));
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Local::now();
+
+        assert_eq!(parse_since("2h", now).unwrap(), now - TimeDelta::hours(2));
+        assert_eq!(parse_since("7d", now).unwrap(), now - TimeDelta::days(7));
+        assert_eq!(
+            parse_since("2026-01-10T14:21:06+02:00", now)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-01-10T14:21:06+02:00"
+        );
+        assert_eq!(
+            parse_since("2026-01-10", now)
+                .unwrap()
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            "2026-01-10 00:00"
+        );
+
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("3y", now).is_err());
+        assert!(parse_since("", now).is_err());
+        assert!(parse_since("99999999999999w", now).is_err());
+        assert!(parse_since("-99999999999999m", now).is_err());
+    }
+}
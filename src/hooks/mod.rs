@@ -1,3 +1,7 @@
+pub mod history;
+pub mod log;
 pub mod parser;
+pub mod runner;
 
-pub use parser::{parse_commit_message, CommitInfo, StatusKeyword};
+pub use log::{HookEvent, HookLog, LogFilter};
+pub use parser::{parse_commit_message, CommitInfo, StatusKeyword, TaskAction};
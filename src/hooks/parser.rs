@@ -1,5 +1,11 @@
+use anyhow::Result;
 use regex::Regex;
-use std::sync::OnceLock;
+
+use crate::utils::errors::{self, ErrorKind};
+use crate::Config;
+
+/// Trailer naming the status for one or more tasks
+const STATUS_TRAILER: &str = "Task-Status";
 
 /// Parsed information from a commit message
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -8,6 +14,8 @@ pub struct CommitInfo {
     pub task_ids: Vec<String>,
     /// Status keywords found with their target status
     pub status_keywords: Vec<StatusKeyword>,
+    /// The status change for each task a keyword applies to
+    pub actions: Vec<TaskAction>,
 }
 
 /// A status keyword found in a commit message
@@ -19,12 +27,24 @@ pub struct StatusKeyword {
     pub target_status: String,
 }
 
+/// A status change for a single task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskAction {
+    /// The task to move
+    pub task_id: String,
+    /// The keyword or trailer that applies to the task
+    pub keyword: String,
+    /// The status to move the task to
+    pub target_status: String,
+}
+
 impl CommitInfo {
     /// Create a new empty CommitInfo
     pub fn new() -> Self {
         CommitInfo {
             task_ids: Vec::new(),
             status_keywords: Vec::new(),
+            actions: Vec::new(),
         }
     }
 
@@ -47,6 +67,11 @@ impl CommitInfo {
     pub fn first_status_keyword(&self) -> Option<&StatusKeyword> {
         self.status_keywords.first()
     }
+
+    /// Get the status change for a task, if a keyword applies to it
+    pub fn action_for(&self, task_id: &str) -> Option<&TaskAction> {
+        self.actions.iter().find(|a| a.task_id == task_id)
+    }
 }
 
 impl Default for CommitInfo {
@@ -57,146 +82,396 @@ impl Default for CommitInfo {
 
 /// Parse a commit message and extract task IDs and status keywords
 ///
-/// Supports multiple task ID formats:
-/// - `[TASKID]` - e.g., [20260110142106]
-/// - `#TASKID` - e.g., #20260110142106
-/// - `task/TASKID` - e.g., task/20260110142106
-/// - `closes #TASKID` - e.g., closes #20260110142106
-/// - `fixes #TASKID` - e.g., fixes #20260110142106
+/// Task references and status keywords come from the config:
+/// `reference_formats` (by default `[ID]`, `#ID` and `task/ID`, with IDs in
+/// the configured `id_scheme`) and `commit_keywords` (by default `[done]`,
+/// `closes`, `fixes`, ... → done, `[review]`, ... → testing, `[wip]`, ... →
+/// in-progress). `Task-Status: <status>` and `Task-Status: #ID <status>`
+/// trailers are always recognised.
+///
+/// Each keyword applies to particular references, collected in
+/// [`CommitInfo::actions`]:
+/// - A word keyword like `closes` applies to the references right after it
+///   (`closes #A, #B and #C`)
+/// - Any other keyword, like `[wip]`, applies to the references after it on
+///   the same line (`[wip] #B`), or else to those before it
+///   (`[A] Add parser [done]`). On a line without references it applies to
+///   every task no other keyword does
+/// - A trailer with references applies to those tasks and overrides any
+///   keyword; without references it applies to every task no keyword does
 ///
-/// Detects status keywords:
-/// - `[done]`, `[complete]`, `[finished]`, `closes`, `fixes` → done
-/// - `[testing]`, `[review]`, `[ready]` → testing
-/// - `[wip]`, `[in-progress]`, `[started]` → in-progress
-pub fn parse_commit_message(message: &str) -> CommitInfo {
+/// When several keywords apply to a task, the one moving it furthest along
+/// the configured statuses wins. Fails if the configured keywords and formats
+/// don't make a pattern the regex engine accepts.
+pub fn parse_commit_message(message: &str, config: &Config) -> Result<CommitInfo> {
+    let patterns = Patterns::new(config)?;
     let mut info = CommitInfo::new();
 
     // Extract task IDs
-    info.task_ids = extract_task_ids(message);
+    info.task_ids = references(message, &patterns.reference);
 
     // Extract status keywords
-    info.status_keywords = extract_status_keywords(message);
+    info.status_keywords = extract_status_keywords(message, &patterns);
 
-    info
+    // Tie keywords to the tasks they apply to
+    info.actions = extract_actions(message, &patterns, &mut info.task_ids);
+
+    Ok(info)
 }
 
-/// Extract all task IDs from a commit message
-fn extract_task_ids(message: &str) -> Vec<String> {
-    static TASK_ID_REGEX: OnceLock<Regex> = OnceLock::new();
+/// Regexes for finding task references and keywords, built from the config
+struct Patterns<'a> {
+    config: &'a Config,
+    /// Any task reference, capturing the ID
+    reference: Regex,
+    /// Word keywords followed by a list of references
+    words: Option<Regex>,
+    /// Keywords matched anywhere, like `[wip]`
+    markers: Option<Regex>,
+    trailer: Regex,
+    trailer_id: Regex,
+}
 
-    let regex = TASK_ID_REGEX.get_or_init(|| {
-        // Match task IDs in various formats:
-        // [20260110142106], #20260110142106, task/20260110142106
-        // closes #20260110142106, fixes #20260110142106
-        Regex::new(r"(?:(?:closes|fixes)\s+)?(?:\[|#|task/)(\d{14})(?:\])?").unwrap()
-    });
+impl<'a> Patterns<'a> {
+    fn new(config: &'a Config) -> Result<Self> {
+        let id_pattern = config.task_id_pattern();
+        let formats = |id: &str| -> String {
+            config
+                .reference_formats
+                .iter()
+                .map(|format| {
+                    let (before, after) = format.split_once("{id}").unwrap_or((format, ""));
+                    format!("{}{}{}", regex::escape(before), id, regex::escape(after))
+                })
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+        let reference = formats(&format!("({})", id_pattern));
+        let any_reference = format!("(?:{})", formats(&format!("(?:{})", id_pattern)));
+
+        let (words, markers): (Vec<&String>, Vec<&String>) =
+            config.commit_keywords.keys().partition(|k| is_word(k));
+        let alternation = |keywords: &[&String]| -> String {
+            keywords
+                .iter()
+                .map(|k| regex::escape(k))
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+
+        let words = (!words.is_empty())
+            .then(|| {
+                compile(&format!(
+                    r"\b(?i:{keywords})\s+{r}(?:(?:\s*,\s*|\s+(?i:and)\s+|\s+){r})*",
+                    keywords = alternation(&words),
+                    r = any_reference
+                ))
+            })
+            .transpose()?;
+        let markers = (!markers.is_empty())
+            .then(|| compile(&format!("(?i){}", alternation(&markers))))
+            .transpose()?;
+
+        Ok(Patterns {
+            config,
+            reference: compile(&reference)?,
+            words,
+            markers,
+            trailer: compile(&format!(r"(?i)^\s*{}\s*:(.*)$", STATUS_TRAILER))?,
+            trailer_id: compile(&format!(r"^(?:{}|({}))$", reference, id_pattern))?,
+        })
+    }
 
-    let mut task_ids = Vec::new();
-    let mut seen = std::collections::HashSet::new();
+    /// The configured keyword (in its configured form) for some matched text
+    fn keyword(&self, text: &str) -> Option<StatusKeyword> {
+        let (keyword, status) = self
+            .config
+            .commit_keywords
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(text))?;
+        Some(keyword_for(keyword, status))
+    }
 
-    for cap in regex.captures_iter(message) {
-        if let Some(id) = cap.get(1) {
-            let task_id = id.as_str().to_string();
-            // Only add unique task IDs
-            if seen.insert(task_id.clone()) {
-                task_ids.push(task_id);
-            }
+    /// How far along the statuses a keyword moves tasks
+    fn strength(&self, keyword: &StatusKeyword) -> usize {
+        self.config
+            .statuses
+            .iter()
+            .position(|s| *s == keyword.target_status)
+            .map_or(0, |i| i + 1)
+    }
+}
+
+/// Compile a pattern built from the commit keywords and reference formats
+fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| {
+        errors::error(
+            ErrorKind::InvalidInput,
+            format!(
+                "Invalid commit_keywords or reference_formats in config: {}",
+                e
+            ),
+        )
+    })
+}
+
+/// Whether a keyword is a word that comes before references, like `closes`
+fn is_word(keyword: &str) -> bool {
+    keyword
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Extract the strongest keyword for each status, in order of strength
+///
+/// Word keywords only count when a task reference follows them.
+fn extract_status_keywords(message: &str, patterns: &Patterns) -> Vec<StatusKeyword> {
+    let mut found: Vec<(usize, StatusKeyword)> = Vec::new();
+
+    if let Some(words) = &patterns.words {
+        for m in words.find_iter(message) {
+            let word = m.as_str().split_whitespace().next().unwrap_or_default();
+            found.extend(patterns.keyword(word).map(|k| (m.start(), k)));
+        }
+    }
+    if let Some(markers) = &patterns.markers {
+        for m in markers.find_iter(message) {
+            found.extend(patterns.keyword(m.as_str()).map(|k| (m.start(), k)));
         }
     }
 
-    task_ids
+    // Only the first keyword for each status
+    found.sort_by_key(|(pos, _)| *pos);
+    let mut keywords: Vec<StatusKeyword> = Vec::new();
+    for (_, keyword) in found {
+        if !keywords
+            .iter()
+            .any(|k| k.target_status == keyword.target_status)
+        {
+            keywords.push(keyword);
+        }
+    }
+
+    keywords.sort_by_key(|k| std::cmp::Reverse(patterns.strength(k)));
+    keywords
 }
 
-/// Extract status keywords from a commit message
-fn extract_status_keywords(message: &str) -> Vec<StatusKeyword> {
-    let mut keywords = Vec::new();
-    let message_lower = message.to_lowercase();
-
-    // Check for "done" keywords
-    let done_patterns = [
-        r"\[done\]",
-        r"\[complete\]",
-        r"\[completed\]",
-        r"\[finished\]",
-        r"\bcloses\s+#\d{14}",
-        r"\bfixes\s+#\d{14}",
-    ];
-
-    static DONE_REGEX: OnceLock<Vec<Regex>> = OnceLock::new();
-    let regexes = DONE_REGEX.get_or_init(|| {
-        done_patterns.iter()
-            .map(|p| Regex::new(p).unwrap())
-            .collect()
-    });
+/// Work out which status each referenced task moves to
+///
+/// IDs only mentioned in trailers are added to `task_ids`.
+fn extract_actions(
+    message: &str,
+    patterns: &Patterns,
+    task_ids: &mut Vec<String>,
+) -> Vec<TaskAction> {
+    // Keywords tied to particular tasks, and those applying to the rest
+    let mut bound: Vec<(String, StatusKeyword)> = Vec::new();
+    let mut defaults: Vec<StatusKeyword> = Vec::new();
+    let mut trailers: Vec<(String, StatusKeyword)> = Vec::new();
+    let mut trailer_default = None;
+
+    for line in message.lines() {
+        if let Some(caps) = patterns.trailer.captures(line) {
+            let (ids, keyword) = parse_trailer(&caps[1], patterns);
+            let Some(keyword) = keyword else {
+                continue;
+            };
+
+            if ids.is_empty() {
+                trailer_default = Some(keyword);
+                continue;
+            }
+            for id in ids {
+                if !task_ids.contains(&id) {
+                    task_ids.push(id.clone());
+                }
+                trailers.push((id, keyword.clone()));
+            }
+            continue;
+        }
 
-    for (i, regex) in regexes.iter().enumerate() {
-        if regex.is_match(&message_lower) {
-            let keyword_name = match i {
-                0 => "done",
-                1 | 2 => "complete",
-                3 => "finished",
-                4 => "closes",
-                5 => "fixes",
-                _ => "done",
+        // `closes #A, #B` claims the references it lists
+        let claimed: Vec<_> = patterns
+            .words
+            .iter()
+            .flat_map(|words| words.find_iter(line))
+            .collect();
+        for m in &claimed {
+            let word = m.as_str().split_whitespace().next().unwrap_or_default();
+            let Some(keyword) = patterns.keyword(word) else {
+                continue;
             };
-            keywords.push(StatusKeyword {
-                keyword: keyword_name.to_string(),
-                target_status: "done".to_string(),
-            });
-            break; // Only add one "done" keyword
+            for id in references(m.as_str(), &patterns.reference) {
+                bound.push((id, keyword.clone()));
+            }
+        }
+
+        let refs: Vec<(usize, String)> = patterns
+            .reference
+            .captures_iter(line)
+            .filter_map(|caps| captured_id(&caps))
+            .filter(|id| !claimed.iter().any(|m| m.range().contains(&id.start())))
+            .map(|id| (id.start(), id.as_str().to_string()))
+            .collect();
+
+        let keywords: Vec<(usize, StatusKeyword)> = patterns
+            .markers
+            .iter()
+            .flat_map(|markers| markers.find_iter(line))
+            .filter_map(|m| Some((m.start(), patterns.keyword(m.as_str())?)))
+            .collect();
+
+        for (i, (start, keyword)) in keywords.iter().enumerate() {
+            let end = keywords.get(i + 1).map_or(usize::MAX, |(next, _)| *next);
+            let mut targets: Vec<&String> = refs
+                .iter()
+                .filter(|(pos, _)| pos > start && *pos < end)
+                .map(|(_, id)| id)
+                .collect();
+
+            // A trailing keyword applies to the references before it
+            if targets.is_empty() && i == 0 {
+                targets = refs
+                    .iter()
+                    .filter(|(pos, _)| pos < start)
+                    .map(|(_, id)| id)
+                    .collect();
+            }
+
+            if targets.is_empty() {
+                if refs.is_empty() && claimed.is_empty() {
+                    defaults.push(keyword.clone());
+                }
+                continue;
+            }
+            for id in targets {
+                bound.push((id.clone(), keyword.clone()));
+            }
         }
     }
 
-    // Check for "testing" keywords
-    let testing_patterns = [r"\[testing\]", r"\[review\]", r"\[ready\]"];
+    let strongest = |keywords: Vec<&'_ StatusKeyword>| {
+        keywords
+            .into_iter()
+            .rev()
+            .max_by_key(|k| patterns.strength(k))
+            .cloned()
+    };
 
-    for pattern in &testing_patterns {
-        if Regex::new(pattern).unwrap().is_match(&message_lower) {
-            let keyword_name = if pattern.contains("testing") {
-                "testing"
-            } else if pattern.contains("review") {
-                "review"
-            } else {
-                "ready"
-            };
-            keywords.push(StatusKeyword {
-                keyword: keyword_name.to_string(),
-                target_status: "testing".to_string(),
-            });
-            break; // Only add one "testing" keyword
+    task_ids
+        .iter()
+        .filter_map(|id| {
+            let keyword = trailers
+                .iter()
+                .rev()
+                .find(|(task_id, _)| task_id == id)
+                .map(|(_, keyword)| keyword.clone())
+                .or_else(|| {
+                    strongest(
+                        bound
+                            .iter()
+                            .filter(|(task_id, _)| task_id == id)
+                            .map(|(_, k)| k)
+                            .collect(),
+                    )
+                })
+                .or_else(|| trailer_default.clone())
+                .or_else(|| strongest(defaults.iter().collect()))?;
+
+            Some(TaskAction {
+                task_id: id.clone(),
+                keyword: keyword.keyword,
+                target_status: keyword.target_status,
+            })
+        })
+        .collect()
+}
+
+/// Split a `Task-Status` trailer value into task IDs and the status
+///
+/// IDs may be written bare, and the status may also be given as one of the
+/// commit keywords, like `wip`.
+fn parse_trailer(value: &str, patterns: &Patterns) -> (Vec<String>, Option<StatusKeyword>) {
+    let mut ids = Vec::new();
+    let mut status = None;
+
+    for token in value.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.is_empty() {
+            continue;
+        }
+        if let Some(id) = patterns
+            .trailer_id
+            .captures(token)
+            .and_then(|caps| captured_id(&caps))
+        {
+            ids.push(id.as_str().to_string());
+        } else if status.is_none() {
+            let token = token.to_lowercase();
+            status = Some(
+                patterns
+                    .config
+                    .commit_keywords
+                    .iter()
+                    .find(|(k, _)| keyword_name(k) == token)
+                    .map_or(token, |(_, status)| status.clone()),
+            );
         }
     }
 
-    // Check for "in-progress" keywords
-    let in_progress_patterns = [r"\[wip\]", r"\[in-progress\]", r"\[started\]"];
+    let keyword = status.map(|status| StatusKeyword {
+        keyword: STATUS_TRAILER.to_string(),
+        target_status: status,
+    });
 
-    for pattern in &in_progress_patterns {
-        if Regex::new(pattern).unwrap().is_match(&message_lower) {
-            let keyword_name = if pattern.contains("wip") {
-                "wip"
-            } else if pattern.contains("in-progress") {
-                "in-progress"
-            } else {
-                "started"
-            };
-            keywords.push(StatusKeyword {
-                keyword: keyword_name.to_string(),
-                target_status: "in-progress".to_string(),
-            });
-            break; // Only add one "in-progress" keyword
+    (ids, keyword)
+}
+
+/// Unique task IDs referenced in some text, in order
+fn references(text: &str, regex: &Regex) -> Vec<String> {
+    let mut task_ids: Vec<String> = Vec::new();
+
+    for caps in regex.captures_iter(text) {
+        if let Some(id) = captured_id(&caps) {
+            // Only add unique task IDs
+            if !task_ids.iter().any(|t| t == id.as_str()) {
+                task_ids.push(id.as_str().to_string());
+            }
         }
     }
 
-    keywords
+    task_ids
+}
+
+/// The ID from whichever reference format matched
+fn captured_id<'t>(caps: &regex::Captures<'t>) -> Option<regex::Match<'t>> {
+    caps.iter().skip(1).flatten().next()
+}
+
+/// A keyword without its brackets (or other punctuation), lowercased
+fn keyword_name(keyword: &str) -> String {
+    keyword
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn keyword_for(keyword: &str, status: &str) -> StatusKeyword {
+    StatusKeyword {
+        keyword: keyword_name(keyword),
+        target_status: status.to_string(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn parse(message: &str) -> CommitInfo {
+        parse_commit_message(message, &Config::default(Some("test".to_string()))).unwrap()
+    }
+
     #[test]
     fn test_parse_empty_message() {
-        let info = parse_commit_message("");
+        let info = parse("");
         assert_eq!(info.task_ids.len(), 0);
         assert_eq!(info.status_keywords.len(), 0);
         assert!(!info.has_task_ids());
@@ -205,38 +480,38 @@ mod tests {
 
     #[test]
     fn test_extract_task_id_bracket_format() {
-        let info = parse_commit_message("[20260110142106] Add new feature");
+        let info = parse("[20260110142106] Add new feature");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
         assert_eq!(info.first_task_id(), Some("20260110142106"));
     }
 
     #[test]
     fn test_extract_task_id_hash_format() {
-        let info = parse_commit_message("Fix bug #20260110142106");
+        let info = parse("Fix bug #20260110142106");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
     }
 
     #[test]
     fn test_extract_task_id_task_prefix_format() {
-        let info = parse_commit_message("Update task/20260110142106 implementation");
+        let info = parse("Update task/20260110142106 implementation");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
     }
 
     #[test]
     fn test_extract_task_id_closes_format() {
-        let info = parse_commit_message("Implement feature closes #20260110142106");
+        let info = parse("Implement feature closes #20260110142106");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
     }
 
     #[test]
     fn test_extract_task_id_fixes_format() {
-        let info = parse_commit_message("Fix critical bug fixes #20260110142106");
+        let info = parse("Fix critical bug fixes #20260110142106");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
     }
 
     #[test]
     fn test_extract_multiple_task_ids() {
-        let info = parse_commit_message("[20260110142106] Related to #20260109120000");
+        let info = parse("[20260110142106] Related to #20260109120000");
         assert_eq!(info.task_ids.len(), 2);
         assert!(info.task_ids.contains(&"20260110142106".to_string()));
         assert!(info.task_ids.contains(&"20260109120000".to_string()));
@@ -244,13 +519,13 @@ mod tests {
 
     #[test]
     fn test_deduplicate_task_ids() {
-        let info = parse_commit_message("[20260110142106] Fix #20260110142106");
+        let info = parse("[20260110142106] Fix #20260110142106");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
     }
 
     #[test]
     fn test_extract_done_keyword() {
-        let info = parse_commit_message("[20260110142106] Complete feature [done]");
+        let info = parse("[20260110142106] Complete feature [done]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "done");
         assert_eq!(info.status_keywords[0].keyword, "done");
@@ -258,7 +533,7 @@ mod tests {
 
     #[test]
     fn test_extract_complete_keyword() {
-        let info = parse_commit_message("Feature implementation [complete]");
+        let info = parse("Feature implementation [complete]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "done");
         assert_eq!(info.status_keywords[0].keyword, "complete");
@@ -266,14 +541,14 @@ mod tests {
 
     #[test]
     fn test_extract_finished_keyword() {
-        let info = parse_commit_message("All tests passing [finished]");
+        let info = parse("All tests passing [finished]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "done");
     }
 
     #[test]
     fn test_extract_closes_keyword() {
-        let info = parse_commit_message("Implement auth closes #20260110142106");
+        let info = parse("Implement auth closes #20260110142106");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "done");
         assert_eq!(info.status_keywords[0].keyword, "closes");
@@ -281,7 +556,7 @@ mod tests {
 
     #[test]
     fn test_extract_testing_keyword() {
-        let info = parse_commit_message("Ready for QA [testing]");
+        let info = parse("Ready for QA [testing]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "testing");
         assert_eq!(info.status_keywords[0].keyword, "testing");
@@ -289,14 +564,14 @@ mod tests {
 
     #[test]
     fn test_extract_review_keyword() {
-        let info = parse_commit_message("Ready for code review [review]");
+        let info = parse("Ready for code review [review]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "testing");
     }
 
     #[test]
     fn test_extract_wip_keyword() {
-        let info = parse_commit_message("Work in progress [wip]");
+        let info = parse("Work in progress [wip]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "in-progress");
         assert_eq!(info.status_keywords[0].keyword, "wip");
@@ -304,14 +579,14 @@ mod tests {
 
     #[test]
     fn test_extract_in_progress_keyword() {
-        let info = parse_commit_message("Starting implementation [in-progress]");
+        let info = parse("Starting implementation [in-progress]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "in-progress");
     }
 
     #[test]
     fn test_combined_task_id_and_status() {
-        let info = parse_commit_message("[20260110142106] Implement auth [done]");
+        let info = parse("[20260110142106] Implement auth [done]");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "done");
@@ -321,14 +596,14 @@ mod tests {
 
     #[test]
     fn test_case_insensitive_keywords() {
-        let info = parse_commit_message("Feature complete [DONE]");
+        let info = parse("Feature complete [DONE]");
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "done");
     }
 
     #[test]
     fn test_no_false_positives_for_partial_matches() {
-        let info = parse_commit_message("Update documentation for task 123");
+        let info = parse("Update documentation for task 123");
         assert_eq!(info.task_ids.len(), 0); // "123" is not a valid 14-digit task ID
     }
 
@@ -340,7 +615,7 @@ This commit adds JWT-based authentication to the API.
 Includes login, logout, and token refresh.
 
 [done]"#;
-        let info = parse_commit_message(message);
+        let info = parse(message);
         assert_eq!(info.task_ids, vec!["20260110142106"]);
         assert_eq!(info.status_keywords.len(), 1);
         assert_eq!(info.status_keywords[0].target_status, "done");
@@ -349,23 +624,215 @@ Includes login, logout, and token refresh.
     #[test]
     fn test_real_world_commit_examples() {
         // Example 1: Feature with task ID and done marker
-        let info = parse_commit_message("[20260110142106] Add user profile page [done]");
+        let info = parse("[20260110142106] Add user profile page [done]");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
         assert_eq!(info.status_keywords[0].target_status, "done");
 
         // Example 2: Bug fix with closes
-        let info = parse_commit_message("Fix login bug closes #20260109120000");
+        let info = parse("Fix login bug closes #20260109120000");
         assert_eq!(info.task_ids, vec!["20260109120000"]);
         assert_eq!(info.status_keywords[0].target_status, "done");
 
         // Example 3: WIP commit
-        let info = parse_commit_message("#20260110142106 Work on API endpoints [wip]");
+        let info = parse("#20260110142106 Work on API endpoints [wip]");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
         assert_eq!(info.status_keywords[0].target_status, "in-progress");
 
         // Example 4: Ready for review
-        let info = parse_commit_message("task/20260110142106 Refactor auth module [review]");
+        let info = parse("task/20260110142106 Refactor auth module [review]");
         assert_eq!(info.task_ids, vec!["20260110142106"]);
         assert_eq!(info.status_keywords[0].target_status, "testing");
     }
+
+    fn action(task_id: &str, keyword: &str, status: &str) -> TaskAction {
+        TaskAction {
+            task_id: task_id.to_string(),
+            keyword: keyword.to_string(),
+            target_status: status.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_actions_follow_their_references() {
+        let info = parse("Parser closes #20260110142106, [wip] #20260110142107");
+        assert_eq!(
+            info.actions,
+            vec![
+                action("20260110142106", "closes", "done"),
+                action("20260110142107", "wip", "in-progress"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_actions_closing_list() {
+        let info = parse(
+            "Fixes #20260110142106, #20260110142107 and #20260110142108, see #20260110142109",
+        );
+        assert_eq!(info.actions.len(), 3);
+        assert_eq!(info.action_for("20260110142108").unwrap().keyword, "fixes");
+        assert_eq!(info.action_for("20260110142109"), None);
+    }
+
+    #[test]
+    fn test_actions_trailing_bracket_keyword() {
+        let info = parse("[20260110142106] Implement auth [DONE]");
+        assert_eq!(info.actions, vec![action("20260110142106", "done", "done")]);
+    }
+
+    #[test]
+    fn test_actions_keyword_line_applies_to_unclaimed_tasks() {
+        let message = "[20260110142106] Parser\n\nCloses #20260110142107\n\n[review]";
+        let info = parse(message);
+        assert_eq!(
+            info.actions,
+            vec![
+                action("20260110142106", "review", "testing"),
+                action("20260110142107", "closes", "done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_actions_mentions_without_keywords() {
+        let info = parse("[wip] [20260110142106] Parser\n\nSee #20260110142107");
+        assert_eq!(
+            info.actions,
+            vec![action("20260110142106", "wip", "in-progress")]
+        );
+    }
+
+    #[test]
+    fn test_actions_strongest_keyword_wins() {
+        let info = parse("[wip] #20260110142106\n\nCloses #20260110142106");
+        assert_eq!(
+            info.actions,
+            vec![action("20260110142106", "closes", "done")]
+        );
+    }
+
+    #[test]
+    fn test_actions_status_trailers() {
+        let message = "[done] [20260110142106] Parser\n\n\
+            Task-Status: #20260110142106 testing\n\
+            task-status: [20260110142107], 20260110142108 wip";
+        let info = parse(message);
+
+        assert_eq!(
+            info.task_ids,
+            vec!["20260110142106", "20260110142107", "20260110142108"]
+        );
+        assert_eq!(
+            info.actions,
+            vec![
+                action("20260110142106", "Task-Status", "testing"),
+                action("20260110142107", "Task-Status", "in-progress"),
+                action("20260110142108", "Task-Status", "in-progress"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_actions_status_trailer_default() {
+        let message = "[20260110142106] Parser, closes #20260110142107\n\nTask-Status: testing";
+        let info = parse(message);
+        assert_eq!(
+            info.actions,
+            vec![
+                action("20260110142106", "Task-Status", "testing"),
+                action("20260110142107", "closes", "done"),
+            ]
+        );
+    }
+
+    fn config_with_scheme(scheme: crate::IdScheme) -> Config {
+        let mut config = Config::default(Some("repo-tasks".to_string()));
+        config.id_scheme = scheme;
+        config
+    }
+
+    #[test]
+    fn test_parse_sequential_ids() {
+        let config = config_with_scheme(crate::IdScheme::Sequential);
+
+        let info = parse_commit_message("[RT-42] Add parser, see #RT-7 [wip]", &config).unwrap();
+        assert_eq!(info.task_ids, vec!["RT-42", "RT-7"]);
+        assert_eq!(info.status_keywords[0].target_status, "in-progress");
+
+        let info = parse_commit_message("Fix crash Closes #RT-42", &config).unwrap();
+        assert_eq!(info.task_ids, vec!["RT-42"]);
+        assert_eq!(info.status_keywords[0].keyword, "closes");
+        assert_eq!(info.actions, vec![action("RT-42", "closes", "done")]);
+
+        // Timestamp IDs aren't task references in a sequential project
+        let info = parse_commit_message("[20260110142106] Old style", &config).unwrap();
+        assert!(!info.has_task_ids());
+    }
+
+    #[test]
+    fn test_parse_ulid_ids() {
+        let config = config_with_scheme(crate::IdScheme::Ulid);
+
+        let info = parse_commit_message(
+            "fixes #01HZX3K4Q2W9Y8V7T6S5R4P3N2 and task/01HZX3K4Q2W9Y8V7T6S5R4P3N3",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            info.task_ids,
+            vec!["01HZX3K4Q2W9Y8V7T6S5R4P3N2", "01HZX3K4Q2W9Y8V7T6S5R4P3N3"]
+        );
+        assert_eq!(info.status_keywords[0].keyword, "fixes");
+    }
+
+    #[test]
+    fn test_parse_configured_vocabulary() {
+        let mut config = Config::default(Some("test".to_string()));
+        config.statuses = vec!["backlog".into(), "doing".into(), "shipped".into()];
+        config.commit_keywords = [
+            ("(doing)".to_string(), "doing".to_string()),
+            ("ships".to_string(), "shipped".to_string()),
+        ]
+        .into();
+        config.reference_formats = vec!["TASK-{id}".to_string()];
+
+        let info = parse_commit_message(
+            "(Doing) TASK-20260110142106, ships TASK-20260110142107 and #20260110142108 [done]",
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(info.task_ids, vec!["20260110142106", "20260110142107"]);
+        assert_eq!(
+            info.actions,
+            vec![
+                action("20260110142106", "doing", "doing"),
+                action("20260110142107", "ships", "shipped"),
+            ]
+        );
+        assert_eq!(info.status_keywords[0].target_status, "shipped");
+        assert_eq!(info.status_keywords[1].target_status, "doing");
+    }
+
+    #[test]
+    fn test_parse_strength_follows_statuses() {
+        let mut config = Config::default(Some("test".to_string()));
+        config.statuses.reverse();
+
+        // `done` now comes first, so it is the weakest
+        let info = parse_commit_message("[done] #20260110142106\n\n[wip] #20260110142106", &config)
+            .unwrap();
+        assert_eq!(info.status_keywords[0].target_status, "in-progress");
+        assert_eq!(info.actions[0].target_status, "in-progress");
+    }
+
+    #[test]
+    fn test_parse_reports_unusable_patterns() {
+        let mut config = Config::default(Some("test".to_string()));
+        config.reference_formats = (0..200).map(|i| format!("#{}-{{id}}", i)).collect();
+
+        let err = parse_commit_message("Closes #20260110142106", &config).unwrap_err();
+        assert_eq!(errors::classify(&err), ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("reference_formats"));
+    }
 }
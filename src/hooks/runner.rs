@@ -0,0 +1,344 @@
+use anyhow::Result;
+use regex::Regex;
+
+use super::parser::{parse_commit_message, TaskAction};
+use crate::{Config, Task};
+
+/// Work out which tasks a commit message moves, and to which status
+///
+/// Each task moves according to the keywords that apply to it. Moves to
+/// statuses that aren't configured are ignored.
+pub fn status_updates(message: &str, config: &Config) -> Result<Vec<TaskAction>> {
+    Ok(parse_commit_message(message, config)?
+        .actions
+        .into_iter()
+        .filter(|action| config.statuses.contains(&action.target_status))
+        .collect())
+}
+
+/// Extract the task ID from a branch named `{id}-{slug}` or `task/{id}`
+pub fn branch_task_id(branch: &str, config: &Config) -> Option<String> {
+    let pattern = config.task_id_pattern();
+    let regex = Regex::new(&format!(r"^(?:task/({0})|({0})-)", pattern)).ok()?;
+
+    let captures = regex.captures(branch)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(2))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Pick out the staged paths that are task files
+pub fn staged_task_files(paths: &[String]) -> Vec<&str> {
+    paths
+        .iter()
+        .map(String::as_str)
+        .filter(|path| path.starts_with(".repo-tasks/"))
+        .collect()
+}
+
+/// Whether `prepare-commit-msg` should add task context for a commit source
+///
+/// Only plain new commits qualify; merges, squashes, amends and messages
+/// given with `-m` are left alone.
+pub fn prepares_message(source: Option<&str>) -> bool {
+    matches!(source, None | Some("") | Some("template"))
+}
+
+/// The part of a commit message file git keeps
+///
+/// `commit-msg` sees comment lines and anything below the scissors line of
+/// `git commit -v`, which git strips afterwards.
+pub fn strip_comments(message: &str) -> String {
+    message
+        .lines()
+        .take_while(|line| {
+            !line.starts_with("# ------------------------ >8 ------------------------")
+        })
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Check a commit message against the commit rules of the hook policy
+///
+/// Returns a description of each problem. `new_commit` is false when
+/// checking commits that were already made, where `reject_closed_references`
+/// doesn't apply: their closing keywords will have moved the tasks on since.
+pub fn commit_policy_problems(
+    message: &str,
+    config: &Config,
+    tasks: &[Task],
+    new_commit: bool,
+) -> Result<Vec<String>> {
+    let policy = &config.hook_policy;
+    let task_ids = parse_commit_message(message, config)?.task_ids;
+    let mut problems = Vec::new();
+
+    if policy.require_task_reference && task_ids.is_empty() {
+        problems.push("doesn't reference a task".to_string());
+    }
+
+    for id in &task_ids {
+        match tasks.iter().find(|t| t.id == *id) {
+            None if policy.require_task_reference => {
+                problems.push(format!("references task {}, which doesn't exist", id));
+            }
+            Some(task)
+                if new_commit
+                    && policy.reject_closed_references
+                    && config.is_closed(&task.status) =>
+            {
+                problems.push(format!(
+                    "references task {}, which is already {}",
+                    id, task.status
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Check a pushed branch against the push rules of the hook policy
+pub fn push_policy_problem(branch: &str, config: &Config, tasks: &[Task]) -> Option<String> {
+    if !config.hook_policy.block_unstarted_push {
+        return None;
+    }
+
+    let id = branch_task_id(branch, config)?;
+    let task = tasks.iter().find(|t| t.id == id)?;
+
+    (config.statuses.first() == Some(&task.status)).then(|| {
+        format!(
+            "branch {} belongs to task {} ({}), which is still in {}",
+            branch, task.id, task.title, task.status
+        )
+    })
+}
+
+/// Commented task context placed above a new commit message
+pub fn commit_message_context(task: &Task) -> String {
+    format!(
+        "# Task: {}\n# ID: {}\n# Status: {}\n#\n# Closes #{}\n\n",
+        task.title, task.id, task.status, task.id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IdScheme;
+
+    fn config() -> Config {
+        Config::default(Some("repo-tasks".to_string()))
+    }
+
+    fn update(task_id: &str, keyword: &str, status: &str) -> TaskAction {
+        TaskAction {
+            task_id: task_id.to_string(),
+            keyword: keyword.to_string(),
+            target_status: status.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_status_updates_per_task() {
+        let updates = status_updates(
+            "[wip] [20260110142106] Parser\n\nCloses #20260110142107",
+            &config(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            updates,
+            vec![
+                update("20260110142106", "wip", "in-progress"),
+                update("20260110142107", "closes", "done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_updates_need_a_keyword() {
+        assert!(status_updates("[20260110142106] Parser", &config())
+            .unwrap()
+            .is_empty());
+        assert!(status_updates("[done] Tidy up", &config())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_status_updates_skip_unconfigured_trailer_status() {
+        let updates = status_updates(
+            "Parser\n\nTask-Status: #20260110142106 blocked\nTask-Status: #20260110142107 testing",
+            &config(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            updates,
+            vec![update("20260110142107", "Task-Status", "testing")]
+        );
+    }
+
+    #[test]
+    fn test_status_updates_skip_unconfigured_statuses() {
+        let mut config = config();
+        config.statuses.retain(|s| s != "testing");
+
+        let updates = status_updates("[review] [20260110142106] Parser", &config).unwrap();
+        assert!(updates.is_empty());
+    }
+
+    #[test]
+    fn test_status_updates_follow_id_scheme() {
+        let mut config = config();
+        config.id_scheme = IdScheme::Sequential;
+
+        let updates = status_updates("[done] [RT-42] Parser", &config).unwrap();
+        assert_eq!(updates, vec![update("RT-42", "done", "done")]);
+    }
+
+    #[test]
+    fn test_branch_task_id() {
+        let config = config();
+
+        assert_eq!(
+            branch_task_id("20260110142106-create-parser", &config).as_deref(),
+            Some("20260110142106")
+        );
+        assert_eq!(
+            branch_task_id("task/20260110142106", &config).as_deref(),
+            Some("20260110142106")
+        );
+        assert_eq!(branch_task_id("main", &config), None);
+        assert_eq!(branch_task_id("feature/20260110142106-x", &config), None);
+    }
+
+    #[test]
+    fn test_branch_task_id_sequential() {
+        let mut config = config();
+        config.id_scheme = IdScheme::Sequential;
+
+        assert_eq!(
+            branch_task_id("RT-42-create-parser", &config).as_deref(),
+            Some("RT-42")
+        );
+    }
+
+    #[test]
+    fn test_staged_task_files() {
+        let staged = vec![
+            "src/main.rs".to_string(),
+            ".repo-tasks/tasks/todo/1-a.md".to_string(),
+        ];
+
+        assert_eq!(
+            staged_task_files(&staged),
+            vec![".repo-tasks/tasks/todo/1-a.md"]
+        );
+    }
+
+    #[test]
+    fn test_prepares_message() {
+        assert!(prepares_message(None));
+        assert!(prepares_message(Some("template")));
+        assert!(!prepares_message(Some("message")));
+        assert!(!prepares_message(Some("merge")));
+    }
+
+    #[test]
+    fn test_commit_message_context() {
+        let mut task = Task::new("Create parser".to_string(), "High".to_string());
+        task.id = "20260110142106".to_string();
+
+        assert_eq!(
+            commit_message_context(&task),
+            "# Task: Create parser\n# ID: 20260110142106\n# Status: todo\n#\n# Closes #20260110142106\n\n"
+        );
+    }
+
+    fn task(id: &str, status: &str) -> Task {
+        let mut task = Task::new(format!("Task {}", id), "High".to_string());
+        task.id = id.to_string();
+        task.status = status.to_string();
+        task
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let message = "Parser\n\n# Task: Parser\nCloses #1\n# ------------------------ >8 ------------------------\ndiff --git a b\n";
+        assert_eq!(strip_comments(message), "Parser\n\nCloses #1");
+    }
+
+    #[test]
+    fn test_commit_policy_problems() {
+        let mut config = config();
+        let tasks = vec![
+            task("20260110142106", "todo"),
+            task("20260110142107", "done"),
+        ];
+
+        // Nothing is enforced by default
+        assert!(commit_policy_problems("Tidy up", &config, &tasks, true)
+            .unwrap()
+            .is_empty());
+
+        config.hook_policy.require_task_reference = true;
+        config.hook_policy.reject_closed_references = true;
+
+        assert_eq!(
+            commit_policy_problems("Tidy up", &config, &tasks, true).unwrap(),
+            vec!["doesn't reference a task"]
+        );
+        assert_eq!(
+            commit_policy_problems("[20260110142199] Parser", &config, &tasks, true).unwrap(),
+            vec!["references task 20260110142199, which doesn't exist"]
+        );
+        assert_eq!(
+            commit_policy_problems("Fix #20260110142107", &config, &tasks, true).unwrap(),
+            vec!["references task 20260110142107, which is already done"]
+        );
+        assert!(
+            commit_policy_problems("Fix #20260110142107", &config, &tasks, false)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            commit_policy_problems("[20260110142106] Parser", &config, &tasks, true)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_push_policy_problem() {
+        let mut config = config();
+        let tasks = vec![
+            task("20260110142106", "todo"),
+            task("20260110142107", "in-progress"),
+        ];
+
+        assert_eq!(
+            push_policy_problem("20260110142106-parser", &config, &tasks),
+            None
+        );
+
+        config.hook_policy.block_unstarted_push = true;
+
+        assert_eq!(
+            push_policy_problem("20260110142106-parser", &config, &tasks).unwrap(),
+            "branch 20260110142106-parser belongs to task 20260110142106 (Task 20260110142106), which is still in todo"
+        );
+        assert_eq!(
+            push_policy_problem("20260110142107-lexer", &config, &tasks),
+            None
+        );
+        assert_eq!(push_policy_problem("main", &config, &tasks), None);
+    }
+}
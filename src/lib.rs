@@ -2,5 +2,7 @@ pub mod hooks;
 pub mod models;
 pub mod utils;
 
-pub use hooks::{parse_commit_message, CommitInfo, StatusKeyword};
-pub use models::{Config, Task};
+pub use hooks::{parse_commit_message, CommitInfo, StatusKeyword, TaskAction};
+pub use models::{
+    Config, DependencyGraph, IdScheme, NewTask, Repository, Task, TaskStore, TaskUpdate,
+};
@@ -1,18 +1,33 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
 
 mod commands;
-mod models;
-mod utils;
 
-use commands::{hooks_install, hooks_list, hooks_uninstall, init, list, move_task, new, open, save, search, show, start, update};
-use models::{Config, Task};
+use commands::{
+    config_add_priority, config_add_status, config_get, config_remove_priority,
+    config_remove_status, config_rename_status, config_set, doctor, graph, history_scan,
+    hooks_install, hooks_list, hooks_log, hooks_run, hooks_uninstall, init, list, mcp, move_task,
+    new, next, open, save, schema, search, show, start, update, UpdateFlags,
+};
+use repo_tasks::models::root;
+use repo_tasks::utils::errors::ErrorKind;
+use repo_tasks::{hooks, models, utils};
+use repo_tasks::{Config, DependencyGraph, NewTask, Repository, Task, TaskStore, TaskUpdate};
 
 #[derive(Parser)]
 #[command(name = "tasks")]
 #[command(about = "Fast task management for git repositories", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// Print machine-readable JSON (errors go to stderr as JSON too)
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Repository to work in (defaults to the nearest one above the current directory)
+    #[arg(long, global = true, env = "REPO_TASKS_DIR", value_name = "PATH")]
+    repo: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -42,7 +57,7 @@ enum Commands {
     },
     /// List tasks in a given status
     List {
-        /// Status to list (defaults to "todo")
+        /// Status to list (defaults to the first status, e.g. "todo")
         status: Option<String>,
         /// Filter by priority
         #[arg(short, long)]
@@ -60,9 +75,38 @@ enum Commands {
         slug_or_id: String,
     },
     /// Update task properties
+    ///
+    /// With no options (and a terminal), asks what to change.
     Update {
         /// Task slug or ID
         slug_or_id: String,
+        /// New title (renames the file)
+        #[arg(short, long)]
+        title: Option<String>,
+        /// New priority level
+        #[arg(short, long)]
+        priority: Option<String>,
+        /// Add tags (comma-separated or repeated)
+        #[arg(long, value_name = "TAG", value_delimiter = ',')]
+        add_tag: Vec<String>,
+        /// Remove tags (comma-separated or repeated)
+        #[arg(long, value_name = "TAG", value_delimiter = ',')]
+        remove_tag: Vec<String>,
+        /// Replace the description ("-" reads it from stdin)
+        #[arg(long, value_name = "TEXT")]
+        set_body: Option<String>,
+        /// Add to the end of the description ("-" reads it from stdin)
+        #[arg(long, value_name = "TEXT")]
+        append_body: Option<String>,
+        /// Add tasks that must be done first (comma-separated or repeated)
+        #[arg(long, value_name = "TASK", value_delimiter = ',')]
+        depends_on: Vec<String>,
+        /// Add tasks that can't start until this one is done (comma-separated or repeated)
+        #[arg(long, value_name = "TASK", value_delimiter = ',')]
+        blocks: Vec<String>,
+        /// Set another frontmatter field, e.g. Assignee=ada (an empty value removes it)
+        #[arg(long, value_name = "KEY=VALUE")]
+        set: Vec<String>,
     },
     /// Move a task to a different status
     Move {
@@ -70,11 +114,17 @@ enum Commands {
         slug_or_id: String,
         /// New status (todo, in-progress, testing, done)
         new_status: String,
+        /// Move even if the task's dependencies aren't done
+        #[arg(short, long)]
+        force: bool,
     },
     /// Start working on a task (move to in-progress + create git branch)
     Start {
         /// Task slug or ID
         slug_or_id: String,
+        /// Start even if the task's dependencies aren't done
+        #[arg(short, long)]
+        force: bool,
     },
     /// Open a task in your default editor
     Open {
@@ -86,6 +136,33 @@ enum Commands {
         /// Search query (regex supported)
         query: String,
     },
+    /// Show tasks ready to work on (dependencies done), best first
+    #[command(alias = "ready")]
+    Next {
+        /// Show at most this many tasks
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        /// Start the top task (move to in-progress + create git branch)
+        #[arg(short, long)]
+        start: bool,
+    },
+    /// Run a Model Context Protocol server on stdio for LLM clients
+    Mcp,
+    /// Visualize task dependencies as a tree, Graphviz DOT or Mermaid
+    Graph {
+        /// Output format: tree, dot or mermaid (defaults to "tree")
+        #[arg(short = 'f', long, value_name = "FORMAT")]
+        format: Option<String>,
+        /// Only include tasks in this status
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Only include tasks with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Only include tasks connected to this task (slug or ID)
+        #[arg(short, long)]
+        root: Option<String>,
+    },
     /// Commit changes to the repository
     Save {
         /// Commit message (auto-generated if not provided)
@@ -95,30 +172,197 @@ enum Commands {
         #[arg(short, long)]
         push: bool,
     },
+    /// Check task files and directories for problems
+    Doctor {
+        /// Apply the safe repairs (renames, identical duplicates, directories)
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Print the JSON Schema for config.json or task frontmatter
+    Schema {
+        /// Which schema to print: config or task
+        target: String,
+    },
     /// Manage git hooks for task automation
     Hooks {
         #[command(subcommand)]
         subcommand: HooksSubcommand,
     },
+    /// Apply task keywords from past commits
+    History {
+        #[command(subcommand)]
+        subcommand: HistorySubcommand,
+    },
+    /// Entry point for installed git hooks
+    #[command(hide = true)]
+    Hook {
+        #[command(subcommand)]
+        subcommand: HookSubcommand,
+    },
+    /// View or change project configuration
+    Config {
+        #[command(subcommand)]
+        subcommand: ConfigSubcommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigSubcommand {
+    /// Print a setting (or the whole config)
+    Get {
+        /// Setting name, e.g. auto_commit
+        key: Option<String>,
+    },
+    /// Change a setting (project_name, auto_commit, output_format, id_scheme, id_prefix, closed_statuses, commit_keywords, reference_formats, hook_policy.*)
+    Set {
+        /// Setting name
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// Add a workflow status
+    AddStatus {
+        /// Status name (lowercase letters, digits and dashes)
+        name: String,
+        /// Position in the workflow, starting at 1 (defaults to last)
+        #[arg(short, long)]
+        position: Option<usize>,
+    },
+    /// Remove a workflow status
+    RemoveStatus {
+        /// Status name
+        name: String,
+        /// Move the status's tasks here instead of refusing
+        #[arg(short, long, value_name = "STATUS")]
+        move_to: Option<String>,
+    },
+    /// Rename a workflow status and its directory
+    RenameStatus {
+        /// Current status name
+        old: String,
+        /// New status name
+        new: String,
+    },
+    /// Add a priority level
+    AddPriority {
+        /// Priority name
+        name: String,
+        /// Position from lowest, starting at 1 (defaults to highest)
+        #[arg(short, long)]
+        position: Option<usize>,
+    },
+    /// Remove a priority level
+    RemovePriority {
+        /// Priority name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
 enum HooksSubcommand {
     /// Install git hooks for task automation
     Install {
-        /// Install specific hook (pre-commit, post-commit, prepare-commit-msg, post-checkout)
+        /// Install specific hook (pre-commit, commit-msg, post-commit, prepare-commit-msg, post-checkout, pre-push)
         hook_name: Option<String>,
+        /// Install into .repo-tasks/hooks, to commit for the whole team, and set core.hooksPath
+        #[arg(long)]
+        shared: bool,
     },
     /// Uninstall repo-tasks git hooks
-    Uninstall,
+    Uninstall {
+        /// Also remove the shared hooks from .repo-tasks/hooks
+        #[arg(long)]
+        shared: bool,
+    },
     /// List installed git hooks
     List,
+    /// Show what the hooks have done
+    Log {
+        /// Only show activity for this task
+        #[arg(long)]
+        task: Option<String>,
+        /// Only show activity since an age (30m, 12h, 7d, 2w) or a date (2026-01-10)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only show failures
+        #[arg(long)]
+        failures: bool,
+        /// Show at most this many of the most recent entries
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        /// Delete the log
+        #[arg(long, conflicts_with_all = ["task", "since", "failures", "limit"])]
+        clear: bool,
+    },
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+#[derive(Subcommand)]
+enum HistorySubcommand {
+    /// Report (or apply) the task moves and commit links in past commit messages
+    Scan {
+        /// Revision or range to scan, e.g. v1.0..HEAD (defaults to everything since the last --apply)
+        range: Option<String>,
+        /// Move the tasks and record the commits on them
+        #[arg(long)]
+        apply: bool,
+    },
+}
 
-    match cli.command {
+#[derive(Subcommand)]
+enum HookSubcommand {
+    /// Run a git hook
+    Run {
+        /// Hook name (pre-commit, commit-msg, post-commit, prepare-commit-msg, post-checkout, pre-push)
+        name: String,
+        /// Arguments git passed to the hook
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+fn main() {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) => {
+            // Report bad arguments as JSON too when it was asked for
+            if err.use_stderr() && std::env::args().any(|arg| arg == "--json") {
+                exit_with_error(
+                    &utils::errors::error(ErrorKind::Usage, err.to_string()),
+                    true,
+                );
+            }
+            err.exit();
+        }
+    };
+
+    if let Err(err) = enter_root(cli.repo.as_deref()).and_then(|_| run(cli.command, cli.json)) {
+        exit_with_error(&err, cli.json);
+    }
+}
+
+/// Work from the repository root, so task paths resolve from any subdirectory
+fn enter_root(repo: Option<&Path>) -> Result<()> {
+    let root = match repo {
+        Some(path) => root::from_override(path)?,
+        None => root::discover(&std::env::current_dir()?),
+    };
+
+    std::env::set_current_dir(&root)
+        .context(format!("Failed to enter repository: {}", root.display()))
+}
+
+/// Print an error (as JSON in `--json` mode) and exit with its kind's code
+fn exit_with_error(err: &anyhow::Error, json: bool) -> ! {
+    if json {
+        eprintln!("{}", utils::errors::to_json(err));
+    } else {
+        eprintln!("Error: {:?}", err);
+    }
+    std::process::exit(utils::errors::classify(err).exit_code());
+}
+
+fn run(command: Commands, json: bool) -> Result<()> {
+    match command {
         Commands::Init { project_name } => {
             init(project_name)?;
         }
@@ -128,7 +372,7 @@ fn main() -> Result<()> {
             tags,
             notes,
         } => {
-            new(title, priority, tags, notes)?;
+            new(title, priority, tags, notes, json)?;
         }
         Commands::List {
             status,
@@ -136,42 +380,127 @@ fn main() -> Result<()> {
             tag,
             format,
         } => {
-            list(status, priority, tag, format)?;
+            list(status, priority, tag, format, json)?;
         }
         Commands::Show { slug_or_id } => {
-            show(slug_or_id)?;
+            show(slug_or_id, json)?;
         }
-        Commands::Update { slug_or_id } => {
-            update(slug_or_id)?;
+        Commands::Update {
+            slug_or_id,
+            title,
+            priority,
+            add_tag,
+            remove_tag,
+            set_body,
+            append_body,
+            depends_on,
+            blocks,
+            set,
+        } => {
+            let flags = UpdateFlags {
+                title,
+                priority,
+                add_tags: add_tag,
+                remove_tags: remove_tag,
+                set_body,
+                append_body,
+                depends_on,
+                blocks,
+                set,
+            };
+            update(slug_or_id, flags, json)?;
         }
         Commands::Move {
             slug_or_id,
             new_status,
+            force,
         } => {
-            move_task(slug_or_id, new_status)?;
+            move_task(slug_or_id, new_status, force, json)?;
         }
-        Commands::Start { slug_or_id } => {
-            start(slug_or_id)?;
+        Commands::Start { slug_or_id, force } => {
+            start(slug_or_id, force, json)?;
         }
         Commands::Open { slug_or_id } => {
             open(slug_or_id)?;
         }
         Commands::Search { query } => {
-            search(query)?;
+            search(query, json)?;
+        }
+        Commands::Next { limit, start } => {
+            next(limit, start, json)?;
+        }
+        Commands::Mcp => {
+            mcp()?;
+        }
+        Commands::Graph {
+            format,
+            status,
+            tag,
+            root,
+        } => {
+            graph(format, status, tag, root)?;
         }
         Commands::Save { message, push } => {
-            save(message, push)?;
+            save(message, push, json)?;
+        }
+        Commands::Doctor { fix } => {
+            doctor(fix, json)?;
+        }
+        Commands::Schema { target } => {
+            schema(target)?;
         }
         Commands::Hooks { subcommand } => match subcommand {
-            HooksSubcommand::Install { hook_name } => {
-                hooks_install(hook_name)?;
+            HooksSubcommand::Install { hook_name, shared } => {
+                hooks_install(hook_name, shared)?;
             }
-            HooksSubcommand::Uninstall => {
-                hooks_uninstall()?;
+            HooksSubcommand::Uninstall { shared } => {
+                hooks_uninstall(shared)?;
             }
             HooksSubcommand::List => {
                 hooks_list()?;
             }
+            HooksSubcommand::Log {
+                task,
+                since,
+                failures,
+                limit,
+                clear,
+            } => {
+                hooks_log(task, since, failures, limit, clear, json)?;
+            }
+        },
+        Commands::History { subcommand } => match subcommand {
+            HistorySubcommand::Scan { range, apply } => {
+                history_scan(range, apply, json)?;
+            }
+        },
+        Commands::Hook { subcommand } => match subcommand {
+            HookSubcommand::Run { name, args } => {
+                hooks_run(name, args)?;
+            }
+        },
+        Commands::Config { subcommand } => match subcommand {
+            ConfigSubcommand::Get { key } => {
+                config_get(key, json)?;
+            }
+            ConfigSubcommand::Set { key, value } => {
+                config_set(key, value, json)?;
+            }
+            ConfigSubcommand::AddStatus { name, position } => {
+                config_add_status(name, position, json)?;
+            }
+            ConfigSubcommand::RemoveStatus { name, move_to } => {
+                config_remove_status(name, move_to, json)?;
+            }
+            ConfigSubcommand::RenameStatus { old, new } => {
+                config_rename_status(old, new, json)?;
+            }
+            ConfigSubcommand::AddPriority { name, position } => {
+                config_add_priority(name, position, json)?;
+            }
+            ConfigSubcommand::RemovePriority { name } => {
+                config_remove_priority(name, json)?;
+            }
         },
     }
 
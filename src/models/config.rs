@@ -1,21 +1,162 @@
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::id::{self, IdScheme};
+use super::root::TASKS_DIR;
+use super::schema;
+use crate::utils::errors::{self, ErrorKind};
+
+/// Settings that `tasks config set` can change
+///
+/// Statuses and priorities have their own commands, since changing them can
+/// affect task files.
+pub const SETTABLE_KEYS: [&str; 12] = [
+    "project_name",
+    "auto_commit",
+    "output_format",
+    "id_scheme",
+    "id_prefix",
+    "started_status",
+    "closed_statuses",
+    "commit_keywords",
+    "reference_formats",
+    "hook_policy.require_task_reference",
+    "hook_policy.reject_closed_references",
+    "hook_policy.block_unstarted_push",
+];
+
+/// Values accepted for `output_format`
+pub const OUTPUT_FORMATS: [&str; 2] = ["table", "list"];
 
 fn default_output_format() -> String {
     "table".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_started_status() -> String {
+    "in-progress".to_string()
+}
+
+fn default_closed_statuses() -> Vec<String> {
+    vec!["done".to_string()]
+}
+
+fn default_commit_keywords() -> BTreeMap<String, String> {
+    [
+        ("[done]", "done"),
+        ("[complete]", "done"),
+        ("[completed]", "done"),
+        ("[finished]", "done"),
+        ("closes", "done"),
+        ("fixes", "done"),
+        ("[testing]", "testing"),
+        ("[review]", "testing"),
+        ("[ready]", "testing"),
+        ("[wip]", "in-progress"),
+        ("[in-progress]", "in-progress"),
+        ("[started]", "in-progress"),
+    ]
+    .into_iter()
+    .map(|(keyword, status)| (keyword.to_string(), status.to_string()))
+    .collect()
+}
+
+fn default_reference_formats() -> Vec<String> {
+    vec![
+        "[{id}]".to_string(),
+        "#{id}".to_string(),
+        "task/{id}".to_string(),
+    ]
+}
+
+/// Rules enforced by the `commit-msg` and `pre-push` hooks, all off by default
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HookPolicy {
+    /// Every commit must reference an existing task
+    #[serde(default)]
+    pub require_task_reference: bool,
+    /// New commits may not reference tasks in a closed status
+    #[serde(default)]
+    pub reject_closed_references: bool,
+    /// Pushing a task's branch is refused while the task is in the first status
+    #[serde(default)]
+    pub block_unstarted_push: bool,
+}
+
+impl HookPolicy {
+    /// Whether the `commit-msg` hook has anything to check
+    pub fn checks_commits(&self) -> bool {
+        self.require_task_reference || self.reject_closed_references
+    }
+
+    /// Whether the `pre-push` hook has anything to check
+    pub fn checks_pushes(&self) -> bool {
+        self.require_task_reference || self.block_unstarted_push
+    }
+}
+
+/// Project settings, stored in `.repo-tasks/config.json`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(title = "repo-tasks config")]
 pub struct Config {
+    /// Display name for the project
+    #[schemars(length(min = 1))]
     pub project_name: String,
+    /// Workflow statuses in order; each is a directory under `.repo-tasks/tasks`
+    #[schemars(
+        length(min = 1),
+        inner(regex(pattern = r"^[a-z][a-z0-9-]*$")),
+        extend("uniqueItems" = true)
+    )]
     pub statuses: Vec<String>,
+    /// Priority levels, lowest first
+    #[schemars(
+        length(min = 1),
+        inner(length(min = 1)),
+        extend("uniqueItems" = true)
+    )]
     pub priorities: Vec<String>,
+    /// Commit automatically after each change
     #[serde(default)]
     pub auto_commit: bool,
+    /// Default output format for `tasks list`
     #[serde(default = "default_output_format")]
+    #[schemars(extend("enum" = OUTPUT_FORMATS))]
     pub output_format: String,
+    /// Scheme used to generate new task IDs
+    #[serde(default)]
+    pub id_scheme: IdScheme,
+    /// Prefix for sequential IDs (defaults to the project's initials)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(regex(pattern = r"^[A-Za-z0-9]+$"))]
+    pub id_prefix: Option<String>,
+    /// Status `tasks start` moves tasks to
+    #[serde(default = "default_started_status")]
+    pub started_status: String,
+    /// Statuses that count as finished when checking dependencies
+    #[serde(default = "default_closed_statuses")]
+    pub closed_statuses: Vec<String>,
+    /// Commit message keywords and the status each one moves tasks to
+    ///
+    /// Words like `closes` apply to the task references right after them;
+    /// anything else, like `[wip]`, applies to the references on its line.
+    #[serde(default = "default_commit_keywords")]
+    pub commit_keywords: BTreeMap<String, String>,
+    /// How commit messages refer to tasks, with `{id}` standing for the ID
+    #[serde(default = "default_reference_formats")]
+    #[schemars(
+        length(min = 1),
+        inner(regex(pattern = r"\{id\}")),
+        extend("uniqueItems" = true)
+    )]
+    pub reference_formats: Vec<String>,
+    /// Rules enforced by the `commit-msg` and `pre-push` hooks
+    #[serde(default)]
+    pub hook_policy: HookPolicy,
 }
 
 impl Config {
@@ -44,21 +185,343 @@ impl Config {
             ],
             auto_commit: false,
             output_format: "table".to_string(),
+            id_scheme: IdScheme::default(),
+            id_prefix: None,
+            started_status: default_started_status(),
+            closed_statuses: default_closed_statuses(),
+            commit_keywords: default_commit_keywords(),
+            reference_formats: default_reference_formats(),
+            hook_policy: HookPolicy::default(),
         }
     }
 
+    /// The status new tasks start in: the first of `statuses`
+    pub fn initial_status(&self) -> &str {
+        self.statuses.first().map_or("", String::as_str)
+    }
+
+    /// The priority new tasks get when none is given
+    ///
+    /// The middle of `priorities`, rounding towards the less important end,
+    /// so the default `Low, Medium, High, Critical` gives `Medium`.
+    pub fn default_priority(&self) -> &str {
+        let middle = self.priorities.len().saturating_sub(1) / 2;
+        self.priorities.get(middle).map_or("", String::as_str)
+    }
+
+    /// Check whether a status counts as finished for dependency purposes
+    pub fn is_closed(&self, status: &str) -> bool {
+        self.closed_statuses.iter().any(|s| s == status)
+    }
+
+    /// Get the prefix used for sequential task IDs
+    pub fn task_id_prefix(&self) -> String {
+        self.id_prefix
+            .clone()
+            .unwrap_or_else(|| id::default_prefix(&self.project_name))
+    }
+
+    /// Get a regex fragment matching task IDs in the configured scheme
+    pub fn task_id_pattern(&self) -> String {
+        self.id_scheme.pattern(&self.task_id_prefix())
+    }
+
+    /// Check the config for problems, reporting all of them at once
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        if self.project_name.trim().is_empty() {
+            problems.push("project_name: must not be empty".to_string());
+        }
+
+        check_names("statuses", &self.statuses, &mut problems);
+        for status in &self.statuses {
+            if !is_valid_status_name(status) {
+                problems.push(format!(
+                    "statuses: '{}' must start with a lowercase letter and contain only lowercase letters, digits and dashes",
+                    status
+                ));
+            }
+        }
+
+        check_names("priorities", &self.priorities, &mut problems);
+        for priority in &self.priorities {
+            if priority.trim().is_empty() {
+                problems.push("priorities: names must not be empty".to_string());
+            }
+        }
+
+        if !self.statuses.contains(&self.started_status) {
+            problems.push(format!(
+                "started_status: '{}' is not one of the statuses",
+                self.started_status
+            ));
+        }
+
+        for status in &self.closed_statuses {
+            if !self.statuses.contains(status) {
+                problems.push(format!(
+                    "closed_statuses: '{}' is not one of the statuses",
+                    status
+                ));
+            }
+        }
+
+        for (keyword, status) in &self.commit_keywords {
+            if keyword.trim().is_empty() {
+                problems.push("commit_keywords: keywords must not be empty".to_string());
+            }
+            if !self.statuses.contains(status) {
+                problems.push(format!(
+                    "commit_keywords: '{}' moves tasks to '{}', which is not one of the statuses",
+                    keyword, status
+                ));
+            }
+        }
+
+        if self.reference_formats.is_empty() {
+            problems.push("reference_formats: at least one is required".to_string());
+        }
+        for format in &self.reference_formats {
+            if format.matches("{id}").count() != 1 {
+                problems.push(format!(
+                    "reference_formats: '{}' must contain {{id}} exactly once",
+                    format
+                ));
+            }
+        }
+
+        if !OUTPUT_FORMATS.contains(&self.output_format.as_str()) {
+            problems.push(format!(
+                "output_format: '{}' must be one of: {}",
+                self.output_format,
+                OUTPUT_FORMATS.join(", ")
+            ));
+        }
+
+        if let Some(prefix) = &self.id_prefix {
+            if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+                problems.push(format!(
+                    "id_prefix: '{}' must be letters and digits only",
+                    prefix
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        let list: Vec<String> = problems.iter().map(|p| format!("  - {}", p)).collect();
+        Err(errors::error(
+            ErrorKind::InvalidInput,
+            format!("Invalid config:\n{}", list.join("\n")),
+        ))
+    }
+
+    /// Get a single setting as JSON
+    ///
+    /// Nested settings are named with dots, like `hook_policy.block_unstarted_push`.
+    pub fn get_value(&self, key: &str) -> Result<Value> {
+        let value = serde_json::to_value(self)?;
+        match value.pointer(&format!("/{}", key.replace('.', "/"))) {
+            Some(v) => Ok(v.clone()),
+            // Optional settings are left out when unset
+            None if key == "id_prefix" => Ok(Value::Null),
+            None => Err(errors::error(
+                ErrorKind::InvalidInput,
+                format!("Unknown setting '{}'", key),
+            )),
+        }
+    }
+
+    /// Change one of the [`SETTABLE_KEYS`]
+    ///
+    /// `value` is parsed according to the setting: `true`/`false` for
+    /// `auto_commit` and the `hook_policy` rules, a JSON array or comma-separated list for
+    /// `closed_statuses` and `reference_formats`, a JSON object for
+    /// `commit_keywords`, and an empty string unsets `id_prefix`.
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
+        if !SETTABLE_KEYS.contains(&key) {
+            let hint = match key {
+                "statuses" => {
+                    "\nUse 'tasks config add-status', 'remove-status' or 'rename-status' instead."
+                }
+                "priorities" => "\nUse 'tasks config add-priority' or 'remove-priority' instead.",
+                _ => "",
+            };
+            return Err(errors::error(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Cannot set '{}'. Settable keys: {}{}",
+                    key,
+                    SETTABLE_KEYS.join(", "),
+                    hint
+                ),
+            ));
+        }
+
+        let parsed = match key {
+            "auto_commit" | "hook_policy.require_task_reference"
+            | "hook_policy.reject_closed_references" | "hook_policy.block_unstarted_push" => {
+                Value::Bool(value.parse().map_err(|_| {
+                    errors::error(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Invalid value for '{}': '{}' (use true or false)",
+                            key, value
+                        ),
+                    )
+                })?)
+            }
+            "commit_keywords" => serde_json::from_str(value).map_err(|_| {
+                errors::error(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Invalid value for 'commit_keywords': '{}' (use a JSON object like {{\"[wip]\": \"in-progress\"}})",
+                        value
+                    ),
+                )
+            })?,
+            "closed_statuses" | "reference_formats" => match serde_json::from_str::<Vec<String>>(value) {
+                Ok(list) => Value::from(list),
+                Err(_) => Value::from(
+                    value
+                        .split(',')
+                        .map(|s| s.trim())
+                        .filter(|s| !s.is_empty())
+                        .collect::<Vec<_>>(),
+                ),
+            },
+            "id_prefix" if value.is_empty() => Value::Null,
+            _ => Value::String(value.to_string()),
+        };
+
+        let mut updated = serde_json::to_value(&*self)?;
+        match key.split_once('.') {
+            Some((section, field)) => updated[section][field] = parsed,
+            None => updated[key] = parsed,
+        }
+
+        let updated: Config = serde_json::from_value(updated).map_err(|e| {
+            errors::error(
+                ErrorKind::InvalidInput,
+                format!("Invalid value for '{}': {}", key, e),
+            )
+        })?;
+
+        self.replace_with(updated)
+    }
+
+    /// Add a status, at `index` (0-based) or at the end
+    pub fn add_status(&mut self, status: &str, index: Option<usize>) -> Result<()> {
+        let mut updated = self.clone();
+        insert_name(&mut updated.statuses, "Status", status, index)?;
+        self.replace_with(updated)
+    }
+
+    /// Remove a status (and stop treating it as closed or moving tasks to it)
+    pub fn remove_status(&mut self, status: &str) -> Result<()> {
+        let mut updated = self.clone();
+        remove_name(&mut updated.statuses, "Status", status)?;
+        updated.closed_statuses.retain(|s| s != status);
+        updated.commit_keywords.retain(|_, s| s != status);
+        self.replace_with(updated)
+    }
+
+    /// Rename a status, keeping its position, whether it's the started or a
+    /// closed status, and the commit keywords moving tasks to it
+    pub fn rename_status(&mut self, old: &str, new: &str) -> Result<()> {
+        let mut updated = self.clone();
+        let Some(position) = updated.statuses.iter().position(|s| s == old) else {
+            return Err(not_configured("Status", old, &self.statuses));
+        };
+        if updated.statuses.iter().any(|s| s == new) {
+            return Err(already_exists("Status", new));
+        }
+
+        updated.statuses[position] = new.to_string();
+        if updated.started_status == old {
+            updated.started_status = new.to_string();
+        }
+        for closed in updated.closed_statuses.iter_mut().filter(|s| *s == old) {
+            *closed = new.to_string();
+        }
+        for target in updated.commit_keywords.values_mut().filter(|s| *s == old) {
+            *target = new.to_string();
+        }
+        self.replace_with(updated)
+    }
+
+    /// Add a priority, at `index` (0-based) or at the end (most important)
+    pub fn add_priority(&mut self, priority: &str, index: Option<usize>) -> Result<()> {
+        let mut updated = self.clone();
+        insert_name(&mut updated.priorities, "Priority", priority, index)?;
+        self.replace_with(updated)
+    }
+
+    /// Remove a priority
+    pub fn remove_priority(&mut self, priority: &str) -> Result<()> {
+        let mut updated = self.clone();
+        remove_name(&mut updated.priorities, "Priority", priority)?;
+        self.replace_with(updated)
+    }
+
+    /// Swap in a modified copy, but only if it validates
+    fn replace_with(&mut self, updated: Config) -> Result<()> {
+        updated.validate()?;
+        *self = updated;
+        Ok(())
+    }
+
     /// Load config from .repo-tasks/config.json
     pub fn load() -> Result<Self> {
-        let path = Self::config_path();
+        Self::load_from(Path::new(""))
+    }
+
+    /// Load the config of the repository at `root`
+    pub fn load_from(root: &Path) -> Result<Self> {
+        let path = Self::config_path(root);
         let content = fs::read_to_string(&path)
             .context(format!("Failed to read config file: {}", path.display()))?;
 
-        serde_json::from_str(&content).context("Failed to parse config.json")
+        let value: Value = serde_json::from_str(&content).context("Failed to parse config.json")?;
+        schema::check(&schema::CONFIG_VALIDATOR, &value, "config.json")?;
+
+        let mut config: Config =
+            serde_json::from_value(value.clone()).context("Failed to parse config.json")?;
+
+        // Configs from before commit keywords existed only get the default
+        // keywords for statuses they actually have
+        if value.get("commit_keywords").is_none() {
+            let statuses = config.statuses.clone();
+            config.commit_keywords.retain(|_, s| statuses.contains(s));
+        }
+
+        // Older configs without `in-progress` start tasks in the second status
+        if value.get("started_status").is_none()
+            && !config.statuses.contains(&config.started_status)
+        {
+            if let Some(status) = config.statuses.get(1).or(config.statuses.first()) {
+                config.started_status = status.clone();
+            }
+        }
+
+        // The schema can't see how the fields relate, like closed statuses
+        // that aren't statuses
+        config.validate()?;
+
+        Ok(config)
     }
 
     /// Write config to .repo-tasks/config.json
     pub fn write(&self) -> Result<()> {
-        let path = Self::config_path();
+        self.write_to(Path::new(""))
+    }
+
+    /// Write config to the repository at `root`
+    pub fn write_to(&self, root: &Path) -> Result<()> {
+        let path = Self::config_path(root);
 
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
@@ -77,15 +540,98 @@ impl Config {
 
     /// Check if repo-tasks is initialized (config file exists)
     pub fn is_initialized() -> bool {
-        Self::config_path().exists()
+        Self::is_initialized_in(Path::new(""))
+    }
+
+    /// Check if the repository at `root` is initialized
+    pub fn is_initialized_in(root: &Path) -> bool {
+        Self::config_path(root).exists()
     }
 
-    /// Get the path to the config file
-    fn config_path() -> PathBuf {
-        PathBuf::from(".repo-tasks").join("config.json")
+    /// Get the path to the config file of the repository at `root`
+    fn config_path(root: &Path) -> PathBuf {
+        root.join(TASKS_DIR).join("config.json")
     }
 }
 
+/// Status names double as directory names, so keep them simple
+fn is_valid_status_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Require a non-empty list without duplicates
+fn check_names(field: &str, names: &[String], problems: &mut Vec<String>) {
+    if names.is_empty() {
+        problems.push(format!("{}: at least one is required", field));
+    }
+
+    for (i, name) in names.iter().enumerate() {
+        if names[..i].contains(name) {
+            problems.push(format!("{}: '{}' is listed more than once", field, name));
+        }
+    }
+}
+
+fn insert_name(
+    names: &mut Vec<String>,
+    kind: &str,
+    name: &str,
+    index: Option<usize>,
+) -> Result<()> {
+    if names.iter().any(|n| n == name) {
+        return Err(already_exists(kind, name));
+    }
+
+    let index = index.unwrap_or(names.len());
+    if index > names.len() {
+        return Err(errors::error(
+            ErrorKind::InvalidInput,
+            format!(
+                "Position {} is out of range (there are {} {})",
+                index + 1,
+                names.len(),
+                if kind == "Status" {
+                    "statuses"
+                } else {
+                    "priorities"
+                }
+            ),
+        ));
+    }
+
+    names.insert(index, name.to_string());
+    Ok(())
+}
+
+fn remove_name(names: &mut Vec<String>, kind: &str, name: &str) -> Result<()> {
+    let Some(position) = names.iter().position(|n| n == name) else {
+        return Err(not_configured(kind, name, names));
+    };
+    names.remove(position);
+    Ok(())
+}
+
+fn already_exists(kind: &str, name: &str) -> anyhow::Error {
+    errors::error(
+        ErrorKind::InvalidInput,
+        format!("{} '{}' already exists", kind, name),
+    )
+}
+
+fn not_configured(kind: &str, name: &str, names: &[String]) -> anyhow::Error {
+    errors::error(
+        ErrorKind::InvalidInput,
+        format!(
+            "{} '{}' is not configured. Current: {}",
+            kind,
+            name,
+            names.join(", ")
+        ),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,5 +665,229 @@ mod tests {
         assert_eq!(config.statuses, deserialized.statuses);
         assert_eq!(config.priorities, deserialized.priorities);
         assert_eq!(config.output_format, deserialized.output_format);
+        assert_eq!(config.id_scheme, deserialized.id_scheme);
+    }
+
+    #[test]
+    fn test_id_scheme_defaults_when_missing() {
+        let json = r#"{"project_name": "repo-tasks", "statuses": ["todo"], "priorities": ["Low"]}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.id_scheme, IdScheme::Timestamp);
+        assert_eq!(config.task_id_prefix(), "RT");
+        assert_eq!(config.closed_statuses, vec!["done"]);
+        assert!(config.is_closed("done"));
+        assert!(!config.is_closed("testing"));
+    }
+
+    #[test]
+    fn test_sequential_id_prefix_override() {
+        let json = r#"{"project_name": "repo-tasks", "statuses": ["todo"], "priorities": ["Low"],
+            "id_scheme": "sequential", "id_prefix": "TASK"}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.id_scheme, IdScheme::Sequential);
+        assert_eq!(config.task_id_pattern(), r"TASK-\d+");
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(Config::default(Some("test".to_string())).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let mut config = Config::default(Some("test".to_string()));
+        config.statuses = vec![
+            "todo".to_string(),
+            "todo".to_string(),
+            "In Review".to_string(),
+        ];
+        config.priorities = Vec::new();
+        config.closed_statuses = vec!["done".to_string()];
+        config.reference_formats = vec!["#{id}".to_string(), "task".to_string()];
+
+        let message = config.validate().unwrap_err().to_string();
+
+        assert!(message.contains("'todo' is listed more than once"));
+        assert!(message.contains("'In Review' must start with a lowercase letter"));
+        assert!(message.contains("priorities: at least one is required"));
+        assert!(message.contains("closed_statuses: 'done' is not one of the statuses"));
+        assert!(message.contains(
+            "commit_keywords: '[wip]' moves tasks to 'in-progress', which is not one of the statuses"
+        ));
+        assert!(message.contains("reference_formats: 'task' must contain {id} exactly once"));
+    }
+
+    #[test]
+    fn test_add_and_remove_status() {
+        let mut config = Config::default(Some("test".to_string()));
+
+        config.add_status("review", Some(3)).unwrap();
+        assert_eq!(
+            config.statuses,
+            vec!["todo", "in-progress", "testing", "review", "done"]
+        );
+
+        assert!(config.add_status("review", None).is_err());
+        assert!(config.add_status("Bad Name", None).is_err());
+        assert!(config.add_status("later", Some(10)).is_err());
+
+        config.remove_status("done").unwrap();
+        assert!(config.closed_statuses.is_empty());
+        assert!(!config.commit_keywords.contains_key("closes"));
+        assert!(config.remove_status("done").is_err());
+
+        // Tasks need somewhere to start
+        let message = config.remove_status("in-progress").unwrap_err().to_string();
+        assert!(message.contains("started_status: 'in-progress' is not one of the statuses"));
+        assert!(config.statuses.contains(&"in-progress".to_string()));
+    }
+
+    #[test]
+    fn test_rename_status_keeps_closed_and_keywords() {
+        let mut config = Config::default(Some("test".to_string()));
+
+        config.rename_status("in-progress", "doing").unwrap();
+        assert_eq!(config.started_status, "doing");
+        assert_eq!(config.commit_keywords["[wip]"], "doing");
+
+        config.rename_status("done", "shipped").unwrap();
+
+        assert_eq!(config.statuses.last().unwrap(), "shipped");
+        assert_eq!(config.closed_statuses, vec!["shipped"]);
+        assert_eq!(config.commit_keywords["closes"], "shipped");
+        assert!(config.rename_status("todo", "testing").is_err());
+    }
+
+    #[test]
+    fn test_defaults_for_new_tasks_follow_config() {
+        let mut config = Config::default(Some("test".to_string()));
+        assert_eq!(config.initial_status(), "todo");
+        assert_eq!(config.default_priority(), "Medium");
+
+        config.rename_status("todo", "backlog").unwrap();
+        config.remove_priority("Medium").unwrap();
+        assert_eq!(config.initial_status(), "backlog");
+        assert_eq!(config.default_priority(), "High");
+    }
+
+    #[test]
+    fn test_started_status_defaults_for_older_configs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default(Some("test".to_string()));
+        config.statuses = vec![
+            "backlog".to_string(),
+            "doing".to_string(),
+            "done".to_string(),
+        ];
+        config.commit_keywords.clear();
+
+        let mut value = serde_json::to_value(&config).unwrap();
+        value.as_object_mut().unwrap().remove("started_status");
+        let path = Config::config_path(temp_dir.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, value.to_string()).unwrap();
+
+        assert_eq!(
+            Config::load_from(temp_dir.path()).unwrap().started_status,
+            "doing"
+        );
+    }
+
+    #[test]
+    fn test_load_validates_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default(Some("test".to_string()));
+        config.closed_statuses = vec!["shipped".to_string()];
+        config.write_to(temp_dir.path()).unwrap();
+
+        let err = Config::load_from(temp_dir.path()).unwrap_err();
+        assert_eq!(errors::classify(&err), ErrorKind::InvalidInput);
+        assert!(err
+            .to_string()
+            .contains("closed_statuses: 'shipped' is not one of the statuses"));
+    }
+
+    #[test]
+    fn test_failed_change_leaves_config_untouched() {
+        let mut config = Config::default(Some("test".to_string()));
+        config.priorities = vec!["Only".to_string()];
+
+        assert!(config.remove_priority("Only").is_err());
+        assert_eq!(config.priorities, vec!["Only"]);
+    }
+
+    #[test]
+    fn test_set_value() {
+        let mut config = Config::default(Some("test".to_string()));
+
+        config.set_value("auto_commit", "true").unwrap();
+        config.set_value("id_scheme", "sequential").unwrap();
+        config
+            .set_value("closed_statuses", "testing, done")
+            .unwrap();
+        config.set_value("id_prefix", "RT").unwrap();
+        config
+            .set_value(
+                "commit_keywords",
+                r#"{"[wip]": "in-progress", "ships": "done"}"#,
+            )
+            .unwrap();
+        config
+            .set_value("reference_formats", "#{id}, GH-{id}")
+            .unwrap();
+
+        assert!(config.auto_commit);
+        assert_eq!(config.id_scheme, IdScheme::Sequential);
+        assert_eq!(config.closed_statuses, vec!["testing", "done"]);
+        assert_eq!(config.get_value("id_prefix").unwrap(), "RT");
+        assert_eq!(config.commit_keywords.len(), 2);
+        assert_eq!(config.reference_formats, vec!["#{id}", "GH-{id}"]);
+
+        config.set_value("id_prefix", "").unwrap();
+        assert_eq!(config.get_value("id_prefix").unwrap(), Value::Null);
+
+        assert!(config.set_value("auto_commit", "yes").is_err());
+        assert!(config.set_value("id_scheme", "uuid").is_err());
+        assert!(config.set_value("output_format", "csv").is_err());
+        assert!(config.set_value("closed_statuses", "nope").is_err());
+        assert!(config.set_value("commit_keywords", "[wip]").is_err());
+        assert!(config
+            .set_value("commit_keywords", r#"{"[wip]": "doing"}"#)
+            .is_err());
+        assert!(config.set_value("reference_formats", "#id").is_err());
+        assert!(config.set_value("statuses", "todo").is_err());
+        assert!(config.get_value("nope").is_err());
+    }
+
+    #[test]
+    fn test_set_hook_policy() {
+        let mut config = Config::default(Some("test".to_string()));
+        assert!(!config.hook_policy.checks_commits());
+        assert!(!config.hook_policy.checks_pushes());
+
+        config
+            .set_value("hook_policy.require_task_reference", "true")
+            .unwrap();
+        config
+            .set_value("hook_policy.block_unstarted_push", "true")
+            .unwrap();
+
+        assert!(config.hook_policy.require_task_reference);
+        assert!(!config.hook_policy.reject_closed_references);
+        assert!(config.hook_policy.checks_commits());
+        assert!(config.hook_policy.checks_pushes());
+        assert_eq!(
+            config
+                .get_value("hook_policy.block_unstarted_push")
+                .unwrap(),
+            Value::Bool(true)
+        );
+
+        assert!(config
+            .set_value("hook_policy.require_task_reference", "on")
+            .is_err());
+        assert!(config.set_value("hook_policy.nope", "true").is_err());
     }
 }
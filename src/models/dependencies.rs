@@ -0,0 +1,41 @@
+use anyhow::Result;
+
+use super::{Config, Task, TaskStore};
+use crate::utils::errors;
+
+/// Refuse to move a task forward while its dependencies are still open
+///
+/// Moving back to the first configured status (e.g. `todo`) is always allowed,
+/// as is any move when `force` is set.
+pub fn ensure_dependencies_met(
+    store: &TaskStore,
+    config: &Config,
+    task: &Task,
+    new_status: &str,
+    force: bool,
+) -> Result<()> {
+    if force || config.statuses.first().is_some_and(|s| s == new_status) {
+        return Ok(());
+    }
+
+    let graph = store.dependency_graph()?;
+    let unmet = graph.unmet_dependencies_of(&task.id, &config.closed_statuses);
+
+    if unmet.is_empty() {
+        Ok(())
+    } else {
+        Err(errors::unmet_dependencies(task, new_status, &unmet))
+    }
+}
+
+/// Tasks that became actionable because `task` was closed
+pub fn newly_unblocked(store: &TaskStore, config: &Config, task: &Task) -> Result<Vec<Task>> {
+    if !config.is_closed(&task.status) {
+        return Ok(Vec::new());
+    }
+
+    let graph = store.dependency_graph()?;
+    let unblocked = graph.unblocked_by(&task.id, &config.closed_statuses);
+
+    Ok(unblocked.into_iter().cloned().collect())
+}
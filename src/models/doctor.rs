@@ -0,0 +1,639 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::graph::DependencyField;
+use super::{Config, DependencyGraph, Task, TaskStore};
+
+/// Kind of problem found by [`diagnose`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemKind {
+    /// Configured status without a directory
+    MissingStatusDir,
+    /// Status directory that isn't in `config.statuses`
+    UnknownStatus,
+    /// Frontmatter that doesn't parse or doesn't match the schema
+    InvalidFrontmatter,
+    /// File not named `{ID}-{slug}.md`
+    MisnamedFile,
+    /// The same task stored in more than one status directory
+    DuplicateTask,
+    /// Different tasks sharing an ID
+    DuplicateId,
+    /// Priority that isn't in `config.priorities`
+    UnknownPriority,
+    /// `DependsOn`/`Blocks` entry that doesn't match any task
+    DanglingReference,
+    /// Dependency recorded on only one of the two tasks
+    OneSidedDependency,
+}
+
+impl ProblemKind {
+    /// Human-readable name for the problem
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProblemKind::MissingStatusDir => "missing status directory",
+            ProblemKind::UnknownStatus => "unknown status",
+            ProblemKind::InvalidFrontmatter => "invalid frontmatter",
+            ProblemKind::MisnamedFile => "misnamed file",
+            ProblemKind::DuplicateTask => "duplicate task",
+            ProblemKind::DuplicateId => "duplicate ID",
+            ProblemKind::UnknownPriority => "unknown priority",
+            ProblemKind::DanglingReference => "dangling reference",
+            ProblemKind::OneSidedDependency => "one-sided dependency",
+        }
+    }
+}
+
+/// A repair that can't lose data, applied by [`repair`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Fix {
+    /// Rename the file
+    Rename { to: PathBuf },
+    /// Delete the file, which is an identical copy of `keep`
+    RemoveCopy { keep: PathBuf },
+    /// Create the directory
+    CreateDir,
+    /// Remove the directory, which is empty
+    RemoveDir,
+    /// Add the task `id` to the `Blocks` or `DependsOn` list
+    AddReference { field: DependencyField, id: String },
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fix::Rename { to } => write!(f, "rename to {}", file_name(to)),
+            Fix::RemoveCopy { keep } => write!(f, "delete this copy of {}", keep.display()),
+            Fix::CreateDir => f.write_str("create the directory"),
+            Fix::RemoveDir => f.write_str("remove the empty directory"),
+            Fix::AddReference { field, id } => write!(f, "add {} to {}", id, field.key()),
+        }
+    }
+}
+
+/// A problem in the task repository
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    /// File or directory the problem is in
+    pub path: PathBuf,
+    /// ID of the task, when the file parsed
+    pub task_id: Option<String>,
+    pub message: String,
+    /// Safe repair, if there is one
+    pub fix: Option<Fix>,
+}
+
+/// Check the store for problems that commands would otherwise skip over
+///
+/// Problems are sorted by path, so those in the same file are listed together.
+pub fn diagnose(store: &TaskStore, config: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    check_status_dirs(store, config, &mut problems);
+
+    let mut tasks = Vec::new();
+    for path in store.task_files() {
+        match Task::from_file(&path) {
+            Ok(task) => tasks.push(task),
+            Err(e) => problems.push(Problem {
+                kind: ProblemKind::InvalidFrontmatter,
+                path,
+                task_id: None,
+                message: format!("{:#}", e),
+                fix: None,
+            }),
+        }
+    }
+
+    check_file_names(&tasks, &mut problems);
+    check_duplicates(store, &tasks, &mut problems);
+    check_priorities(&tasks, config, &mut problems);
+    check_references(&tasks, &mut problems);
+    check_symmetry(&tasks, &mut problems);
+
+    problems.sort_by(|a, b| (&a.path, a.kind).cmp(&(&b.path, b.kind)));
+    problems
+}
+
+/// Apply a problem's fix
+///
+/// Returns false if there is no fix, or if an earlier repair already dealt
+/// with the file.
+pub fn repair(problem: &Problem) -> Result<bool> {
+    let Some(fix) = &problem.fix else {
+        return Ok(false);
+    };
+    let path = &problem.path;
+
+    match fix {
+        Fix::Rename { to } => {
+            if !path.exists() || to.exists() {
+                return Ok(false);
+            }
+            fs::rename(path, to).context(format!(
+                "Failed to rename {} to {}",
+                path.display(),
+                to.display()
+            ))?;
+        }
+        Fix::RemoveCopy { keep } => {
+            if !path.exists() || !keep.exists() {
+                return Ok(false);
+            }
+            fs::remove_file(path)
+                .context(format!("Failed to remove task file: {}", path.display()))?;
+        }
+        Fix::CreateDir => {
+            fs::create_dir_all(path)
+                .context(format!("Failed to create directory: {}", path.display()))?;
+        }
+        Fix::RemoveDir => {
+            fs::remove_dir(path)
+                .context(format!("Failed to remove directory: {}", path.display()))?;
+        }
+        Fix::AddReference { field, id } => {
+            if !path.exists() {
+                return Ok(false);
+            }
+            let mut task = Task::from_file(path)?;
+            let ids = match field {
+                DependencyField::Blocks => &mut task.blocks,
+                DependencyField::DependsOn => &mut task.depends_on,
+            }
+            .get_or_insert_with(Vec::new);
+            if ids.contains(id) {
+                return Ok(false);
+            }
+            ids.push(id.clone());
+            task.to_file(path)?;
+        }
+    }
+
+    Ok(true)
+}
+
+fn check_status_dirs(store: &TaskStore, config: &Config, problems: &mut Vec<Problem>) {
+    let dirs = store.status_dirs();
+
+    for status in &config.statuses {
+        if !dirs.contains(status) {
+            problems.push(Problem {
+                kind: ProblemKind::MissingStatusDir,
+                path: store.status_dir(status),
+                task_id: None,
+                message: format!("No directory for status '{}'", status),
+                fix: Some(Fix::CreateDir),
+            });
+        }
+    }
+
+    for status in dirs.iter().filter(|s| !config.statuses.contains(s)) {
+        let path = store.status_dir(status);
+        let tasks = store.status_files(status).len();
+        let empty = fs::read_dir(&path).is_ok_and(|mut entries| entries.next().is_none());
+
+        let message = if tasks > 0 {
+            format!(
+                "'{}' is not a configured status, but holds {} task(s)",
+                status, tasks
+            )
+        } else {
+            format!("'{}' is not a configured status", status)
+        };
+
+        problems.push(Problem {
+            kind: ProblemKind::UnknownStatus,
+            path,
+            task_id: None,
+            message,
+            fix: empty.then_some(Fix::RemoveDir),
+        });
+    }
+}
+
+fn check_file_names(tasks: &[Task], problems: &mut Vec<Problem>) {
+    // Renaming changes a task's slug, which would break references to it
+    let references: HashSet<&str> = tasks
+        .iter()
+        .flat_map(|t| t.depends_on.iter().chain(t.blocks.iter()).flatten())
+        .map(|r| r.trim())
+        .collect();
+
+    for task in tasks {
+        let Some(path) = &task.path else {
+            continue;
+        };
+
+        // Keep the existing slug where possible, since it's used to refer to
+        // the task (and in branch names); only fall back to the title
+        let mut slug = Task::generate_slug(&task.slug);
+        if slug.is_empty() {
+            slug = Task::generate_slug(&task.title);
+        }
+
+        let expected = format!("{}-{}.md", task.id, slug);
+        if file_name(path) == expected {
+            continue;
+        }
+
+        let target = path.with_file_name(&expected);
+        let safe = !slug.is_empty()
+            && !target.exists()
+            && (task.slug == slug || !references.contains(task.slug.as_str()));
+
+        problems.push(Problem {
+            kind: ProblemKind::MisnamedFile,
+            path: path.clone(),
+            task_id: Some(task.id.clone()),
+            message: format!("Expected file name {}", expected),
+            fix: safe.then_some(Fix::Rename { to: target }),
+        });
+    }
+}
+
+fn check_duplicates(store: &TaskStore, tasks: &[Task], problems: &mut Vec<Problem>) {
+    let mut by_id: BTreeMap<&str, Vec<&Task>> = BTreeMap::new();
+    for task in tasks {
+        by_id.entry(&task.id).or_default().push(task);
+    }
+
+    for (id, group) in by_id.into_iter().filter(|(_, g)| g.len() > 1) {
+        // Copies of one task (e.g. from an interrupted move) share a file name
+        let mut by_name: BTreeMap<String, Vec<&Path>> = BTreeMap::new();
+        for path in group.iter().filter_map(|t| t.path.as_deref()) {
+            by_name.entry(file_name(path)).or_default().push(path);
+        }
+
+        for copies in by_name.values().filter(|c| c.len() > 1) {
+            let keep = newest(copies);
+            for &copy in copies.iter().filter(|&&c| c != keep) {
+                let identical = fs::read(copy).ok() == fs::read(keep).ok();
+
+                problems.push(Problem {
+                    kind: ProblemKind::DuplicateTask,
+                    path: copy.to_path_buf(),
+                    task_id: Some(id.to_string()),
+                    message: if identical {
+                        format!("Identical to the newer copy in {}", relative(store, keep))
+                    } else {
+                        format!("Also stored (with changes) in {}", relative(store, keep))
+                    },
+                    fix: identical.then(|| Fix::RemoveCopy {
+                        keep: keep.to_path_buf(),
+                    }),
+                });
+            }
+        }
+
+        if by_name.len() > 1 {
+            let first_copies: Vec<&Path> = by_name.values().map(|c| c[0]).collect();
+            for &path in &first_copies {
+                let others: Vec<String> = first_copies
+                    .iter()
+                    .filter(|&&p| p != path)
+                    .map(|p| relative(store, p))
+                    .collect();
+
+                problems.push(Problem {
+                    kind: ProblemKind::DuplicateId,
+                    path: path.to_path_buf(),
+                    task_id: Some(id.to_string()),
+                    message: format!("ID '{}' is also used by {}", id, others.join(", ")),
+                    fix: None,
+                });
+            }
+        }
+    }
+}
+
+fn check_priorities(tasks: &[Task], config: &Config, problems: &mut Vec<Problem>) {
+    for task in tasks {
+        let (Some(priority), Some(path)) = (&task.priority, &task.path) else {
+            continue;
+        };
+
+        if !config.priorities.contains(priority) {
+            problems.push(Problem {
+                kind: ProblemKind::UnknownPriority,
+                path: path.clone(),
+                task_id: Some(task.id.clone()),
+                message: format!(
+                    "Priority '{}' is not one of: {}",
+                    priority,
+                    config.priorities.join(", ")
+                ),
+                fix: None,
+            });
+        }
+    }
+}
+
+fn check_references(tasks: &[Task], problems: &mut Vec<Problem>) {
+    let graph = DependencyGraph::build(tasks.iter().cloned());
+
+    for dangling in graph.dangling_references() {
+        let Some(path) = dangling.path.clone() else {
+            continue;
+        };
+
+        problems.push(Problem {
+            kind: ProblemKind::DanglingReference,
+            path,
+            task_id: Some(dangling.task_id.clone()),
+            message: format!(
+                "{} entry '{}' doesn't match any task",
+                dangling.field.key(),
+                dangling.reference
+            ),
+            fix: None,
+        });
+    }
+}
+
+fn check_symmetry(tasks: &[Task], problems: &mut Vec<Problem>) {
+    // Symmetrizing only ever appends, so anything past the original entries
+    // is missing from the file
+    let mut symmetric = tasks.to_vec();
+    DependencyGraph::symmetrize(&mut symmetric);
+
+    for (task, expected) in tasks.iter().zip(&symmetric) {
+        let Some(path) = &task.path else {
+            continue;
+        };
+
+        for (field, before, after) in [
+            (
+                DependencyField::DependsOn,
+                &task.depends_on,
+                &expected.depends_on,
+            ),
+            (DependencyField::Blocks, &task.blocks, &expected.blocks),
+        ] {
+            let known = before.as_ref().map_or(0, Vec::len);
+            for id in after.iter().flatten().skip(known) {
+                let relation = match field {
+                    DependencyField::DependsOn => "blocks",
+                    DependencyField::Blocks => "depends on",
+                };
+
+                problems.push(Problem {
+                    kind: ProblemKind::OneSidedDependency,
+                    path: path.clone(),
+                    task_id: Some(task.id.clone()),
+                    message: format!(
+                        "{} {} this task, but {} doesn't list it",
+                        id,
+                        relation,
+                        field.key()
+                    ),
+                    fix: Some(Fix::AddReference {
+                        field,
+                        id: id.clone(),
+                    }),
+                });
+            }
+        }
+    }
+}
+
+/// Pick the most recently modified file (the last one on ties)
+fn newest<'a>(paths: &[&'a Path]) -> &'a Path {
+    paths
+        .iter()
+        .copied()
+        .max_by_key(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+        .expect("at least one path")
+}
+
+/// Display a path relative to the tasks directory
+fn relative(store: &TaskStore, path: &Path) -> String {
+    path.strip_prefix(store.root())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, TaskStore, Config) {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TaskStore::new(temp_dir.path().join("tasks"));
+        let config = Config::default(Some("test".to_string()));
+        for status in &config.statuses {
+            store.create_status_dir(status).unwrap();
+        }
+        (temp_dir, store, config)
+    }
+
+    fn write(store: &TaskStore, status: &str, name: &str, frontmatter: &str) -> PathBuf {
+        let path = store.status_dir(status).join(name);
+        fs::write(&path, format!("---\n{}---\n\nBody\n", frontmatter)).unwrap();
+        path
+    }
+
+    fn kinds(problems: &[Problem]) -> Vec<ProblemKind> {
+        problems.iter().map(|p| p.kind).collect()
+    }
+
+    #[test]
+    fn test_healthy_store_has_no_problems() {
+        let (_dir, store, config) = setup();
+        write(
+            &store,
+            "todo",
+            "1-first.md",
+            "ID: '1'\nTitle: First\nBlocks:\n- '2'\n",
+        );
+        write(
+            &store,
+            "done",
+            "2-second.md",
+            "ID: '2'\nTitle: Second\nPriority: High\nDependsOn:\n- first\n",
+        );
+
+        assert!(diagnose(&store, &config).is_empty());
+    }
+
+    #[test]
+    fn test_reports_each_kind_of_problem() {
+        let (_dir, store, mut config) = setup();
+        config.statuses.push("archived".to_string());
+        fs::create_dir_all(store.status_dir("old")).unwrap();
+
+        write(&store, "todo", "1-broken.md", "ID: 1\nTitle: Broken\n");
+        write(
+            &store,
+            "todo",
+            "2-Wrong Name.md",
+            "ID: '2'\nTitle: Wrong Name\n",
+        );
+        write(&store, "todo", "3-a.md", "ID: '3'\nTitle: A\n");
+        write(&store, "done", "3-b.md", "ID: '3'\nTitle: B\n");
+        write(
+            &store,
+            "todo",
+            "4-c.md",
+            "ID: '4'\nTitle: C\nPriority: Urgent\n",
+        );
+        write(
+            &store,
+            "todo",
+            "5-d.md",
+            "ID: '5'\nTitle: D\nBlocks:\n- nope\n",
+        );
+
+        let problems = diagnose(&store, &config);
+
+        assert_eq!(
+            kinds(&problems),
+            vec![
+                ProblemKind::MissingStatusDir,
+                ProblemKind::DuplicateId,
+                ProblemKind::UnknownStatus,
+                ProblemKind::InvalidFrontmatter,
+                ProblemKind::MisnamedFile,
+                ProblemKind::DuplicateId,
+                ProblemKind::UnknownPriority,
+                ProblemKind::DanglingReference,
+            ]
+        );
+        assert!(problems[3].message.contains("ID: 1 is not of type"));
+        assert!(problems[4].message.contains("2-wrong-name.md"));
+        assert!(problems[7].message.contains("Blocks entry 'nope'"));
+    }
+
+    #[test]
+    fn test_fix_repairs_safe_problems() {
+        let (_dir, store, config) = setup();
+        fs::remove_dir(store.status_dir("testing")).unwrap();
+        fs::create_dir_all(store.status_dir("old")).unwrap();
+
+        let misnamed = write(&store, "todo", "01-name.md", "ID: '1'\nTitle: Name\n");
+        let stale = write(&store, "todo", "2-moved.md", "ID: '2'\nTitle: Moved\n");
+        let moved = write(&store, "done", "2-moved.md", "ID: '2'\nTitle: Moved\n");
+        // Make sure the copy in done/ is the newer one
+        let now = std::time::SystemTime::now();
+        fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(now - std::time::Duration::from_secs(60))
+            .unwrap();
+
+        let problems = diagnose(&store, &config);
+        assert_eq!(problems.len(), 4);
+        for problem in &problems {
+            assert!(repair(problem).unwrap(), "{:?}", problem);
+        }
+
+        assert!(diagnose(&store, &config).is_empty());
+        assert!(!misnamed.exists());
+        assert!(store.status_dir("todo").join("1-name.md").exists());
+        assert!(!stale.exists());
+        assert!(moved.exists());
+        assert!(store.status_dir("testing").exists());
+        assert!(!store.status_dir("old").exists());
+    }
+
+    #[test]
+    fn test_fix_adds_missing_dependency_entries() {
+        let (_dir, store, config) = setup();
+        let first = write(&store, "todo", "1-first.md", "ID: '1'\nTitle: First\n");
+        write(
+            &store,
+            "todo",
+            "2-second.md",
+            "ID: '2'\nTitle: Second\nDependsOn:\n- first\n",
+        );
+        let third = write(
+            &store,
+            "todo",
+            "3-third.md",
+            "ID: '3'\nTitle: Third\nBlocks:\n- second # after review\n",
+        );
+
+        let problems = diagnose(&store, &config);
+        assert_eq!(
+            kinds(&problems),
+            vec![
+                ProblemKind::OneSidedDependency,
+                ProblemKind::OneSidedDependency
+            ]
+        );
+        assert_eq!(problems[0].path, first);
+        assert_eq!(
+            problems[0].message,
+            "2 depends on this task, but Blocks doesn't list it"
+        );
+        assert!(problems[1].message.contains("3 blocks this task"));
+
+        for problem in &problems {
+            assert!(repair(problem).unwrap(), "{:?}", problem);
+        }
+
+        assert!(diagnose(&store, &config).is_empty());
+        let second = store.find("second").unwrap();
+        assert_eq!(
+            second.depends_on,
+            Some(vec!["first".to_string(), "3".to_string()])
+        );
+        assert_eq!(
+            Task::from_file(&first).unwrap().blocks,
+            Some(vec!["2".to_string()])
+        );
+        // Untouched files keep their comments
+        assert!(fs::read_to_string(third)
+            .unwrap()
+            .contains("# after review"));
+    }
+
+    #[test]
+    fn test_unsafe_problems_have_no_fix() {
+        let (_dir, store, config) = setup();
+        write(
+            &store,
+            "todo",
+            "1-Old Name.md",
+            "ID: '1'\nTitle: Old Name\nBlocks:\n- '2'\n",
+        );
+        write(
+            &store,
+            "todo",
+            "2-other.md",
+            "ID: '2'\nTitle: Other\nDependsOn:\n- Old Name\n",
+        );
+        write(&store, "todo", "3-edited.md", "ID: '3'\nTitle: Edited\n");
+        write(
+            &store,
+            "done",
+            "3-edited.md",
+            "ID: '3'\nTitle: Edited\nTags:\n- x\n",
+        );
+
+        let problems = diagnose(&store, &config);
+        let mut found = kinds(&problems);
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![ProblemKind::MisnamedFile, ProblemKind::DuplicateTask]
+        );
+        assert!(problems.iter().all(|p| p.fix.is_none()));
+    }
+}
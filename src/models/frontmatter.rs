@@ -0,0 +1,505 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+use std::sync::LazyLock;
+use toml_edit::DocumentMut;
+
+use crate::utils::errors::{self, ErrorKind};
+
+const BOM: char = '\u{feff}';
+
+/// A line number in a YAML error message
+static LINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bline (\d+)").unwrap());
+
+/// Frontmatter syntax, set by the delimiter a task file opens with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Between `---` lines
+    Yaml,
+    /// Between `+++` lines
+    Toml,
+}
+
+impl Format {
+    /// The line that opens and closes the frontmatter
+    pub fn delimiter(self) -> &'static str {
+        match self {
+            Format::Yaml => "---",
+            Format::Toml => "+++",
+        }
+    }
+}
+
+/// A task file split into frontmatter and body, kept so the file can be
+/// written back without disturbing anything that didn't change
+///
+/// Fields are rewritten one at a time: an unchanged field keeps its exact
+/// text, including quoting, comments and position; a changed field is
+/// reformatted where it stands; a removed one is dropped and a new one is
+/// added at the end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    format: Format,
+    /// Whether the file starts with a byte order mark
+    bom: bool,
+    /// The opening delimiter line
+    open: String,
+    /// The frontmatter between the delimiter lines
+    text: String,
+    /// The closing delimiter line
+    close: String,
+    /// Everything after the closing delimiter line, i.e. the body
+    tail: String,
+    /// The frontmatter as parsed, to tell which fields changed
+    values: Mapping,
+    /// The file's line ending, used for lines we write
+    newline: &'static str,
+}
+
+/// A run of YAML lines: one top-level field, or the blank and comment lines
+/// between fields
+struct Entry<'a> {
+    /// The field's key, if this is a field
+    key: Option<Value>,
+    text: &'a str,
+}
+
+impl Source {
+    /// Split a task file into frontmatter and body, and parse the frontmatter
+    ///
+    /// The file must open with a `---` (YAML) or `+++` (TOML) line, and the
+    /// frontmatter ends at the next line that's the same delimiter.
+    pub fn parse(content: &str) -> Result<Self> {
+        let (bom, content) = match content.strip_prefix(BOM) {
+            Some(content) => (true, content),
+            None => (false, content),
+        };
+
+        let mut lines = content.split_inclusive('\n');
+        let open = lines.next().unwrap_or_default();
+        let format = match open.trim_end() {
+            "---" => Format::Yaml,
+            "+++" => Format::Toml,
+            _ => {
+                return Err(invalid(
+                    1,
+                    "the file must start with a '---' (YAML) or '+++' (TOML) frontmatter line",
+                ))
+            }
+        };
+
+        let mut end = open.len();
+        let mut close = None;
+        for line in lines {
+            if line.trim_end() == format.delimiter() {
+                close = Some(line);
+                break;
+            }
+            end += line.len();
+        }
+        let Some(close) = close else {
+            return Err(invalid(
+                1,
+                format!(
+                    "the frontmatter is never closed with a '{}' line",
+                    format.delimiter()
+                ),
+            ));
+        };
+
+        let text = &content[open.len()..end];
+        let values = match format {
+            Format::Yaml => parse_yaml(text)?,
+            Format::Toml => parse_toml(text)?,
+        };
+
+        Ok(Source {
+            format,
+            bom,
+            open: open.to_string(),
+            text: text.to_string(),
+            close: close.to_string(),
+            tail: content[end + close.len()..].to_string(),
+            values,
+            newline: if open.ends_with("\r\n") { "\r\n" } else { "\n" },
+        })
+    }
+
+    /// The frontmatter syntax
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// The frontmatter fields
+    pub fn values(&self) -> &Mapping {
+        &self.values
+    }
+
+    /// The body, without surrounding whitespace
+    pub fn body(&self) -> &str {
+        self.tail.trim()
+    }
+
+    /// The file with its fields set to `values` and its body set to `body`
+    pub fn render(&self, values: &Mapping, body: &str) -> Result<String> {
+        let text = match self.format {
+            Format::Yaml => self.render_yaml(values)?,
+            Format::Toml => self.render_toml(values)?,
+        };
+
+        Ok(format!(
+            "{}{}{}{}{}",
+            if self.bom { "\u{feff}" } else { "" },
+            self.open,
+            text,
+            self.close,
+            self.tail_with(body)
+        ))
+    }
+
+    fn render_yaml(&self, values: &Mapping) -> Result<String> {
+        let Some(entries) = self.entries() else {
+            // Fields we couldn't pick apart are written afresh
+            let text = serde_yaml::to_string(values)?;
+            return Ok(self.with_newlines(&text));
+        };
+
+        let mut text = String::with_capacity(self.text.len());
+        for entry in &entries {
+            let Some(key) = &entry.key else {
+                text.push_str(entry.text);
+                continue;
+            };
+
+            // A field that's null reads the same as one that isn't there
+            let old = self.values.get(key).unwrap_or(&Value::Null);
+            let new = values.get(key).unwrap_or(&Value::Null);
+            if old == new {
+                text.push_str(entry.text);
+            } else if !new.is_null() {
+                text.push_str(&self.with_newlines(&yaml_field(key, new)?));
+            }
+        }
+
+        for (key, value) in values {
+            if !self.values.contains_key(key) && !value.is_null() {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push_str(self.newline);
+                }
+                text.push_str(&self.with_newlines(&yaml_field(key, value)?));
+            }
+        }
+
+        Ok(text)
+    }
+
+    fn render_toml(&self, values: &Mapping) -> Result<String> {
+        let mut doc: DocumentMut = self
+            .text
+            .parse()
+            .context("Failed to parse task frontmatter")?;
+
+        let changed = self
+            .values
+            .keys()
+            .chain(values.keys().filter(|k| !self.values.contains_key(*k)));
+        for key in changed {
+            let old = self.values.get(key).unwrap_or(&Value::Null);
+            let new = values.get(key).unwrap_or(&Value::Null);
+            let Some(name) = key.as_str() else {
+                continue;
+            };
+
+            if old == new {
+                continue;
+            } else if new.is_null() {
+                doc.remove(name);
+            } else {
+                doc.insert(name, toml_field(name, key, new)?);
+            }
+        }
+
+        Ok(self.with_newlines(&doc.to_string()))
+    }
+
+    /// Split the YAML into fields, or None if the fields found don't account
+    /// for exactly the parsed keys
+    fn entries(&self) -> Option<Vec<Entry<'_>>> {
+        let mut spans: Vec<(usize, usize, bool)> = Vec::new();
+        let mut offset = 0;
+
+        for line in self.text.split_inclusive('\n') {
+            let end = offset + line.len();
+            let starts_field = !line.starts_with([' ', '\t', '#', '-', '\r', '\n']);
+            let continues_field = !starts_field
+                && !line.starts_with('#')
+                && spans.last().is_some_and(|(_, _, field)| *field);
+
+            match spans.last_mut() {
+                Some(span) if continues_field => span.1 = end,
+                _ if starts_field => spans.push((offset, end, true)),
+                Some(span) if !span.2 => span.1 = end,
+                _ => spans.push((offset, end, false)),
+            }
+            offset = end;
+        }
+
+        let mut entries = Vec::new();
+        for (start, end, field) in spans {
+            let text = &self.text[start..end];
+            if !field {
+                entries.push(Entry { key: None, text });
+                continue;
+            }
+
+            // Blank lines at the end of a field sit between fields
+            let content_len = text
+                .split_inclusive('\n')
+                .scan(0, |end, line| {
+                    *end += line.len();
+                    Some((*end, line))
+                })
+                .filter(|(_, line)| !line.trim().is_empty())
+                .last()
+                .map_or(text.len(), |(end, _)| end);
+            let content = &text[..content_len];
+            let mapping: Mapping = serde_yaml::from_str(content).ok()?;
+            if mapping.len() != 1 {
+                return None;
+            }
+
+            entries.push(Entry {
+                key: mapping.keys().next().cloned(),
+                text: content,
+            });
+            if content.len() < text.len() {
+                entries.push(Entry {
+                    key: None,
+                    text: &text[content.len()..],
+                });
+            }
+        }
+
+        let keys: Vec<&Value> = entries.iter().filter_map(|e| e.key.as_ref()).collect();
+        let accounted =
+            keys.len() == self.values.len() && self.values.keys().all(|k| keys.contains(&k));
+
+        accounted.then_some(entries)
+    }
+
+    /// The original tail with `body` in place of the old body, keeping the
+    /// whitespace around it
+    fn tail_with(&self, body: &str) -> String {
+        let trimmed = self.tail.trim();
+        if trimmed == body {
+            return self.tail.clone();
+        }
+
+        let start = self.tail.len() - self.tail.trim_start().len();
+        let end = start + trimmed.len();
+        let lead = match &self.tail[..start] {
+            lead if lead.contains('\n') => lead.to_string(),
+            _ if self.close.ends_with('\n') => self.newline.to_string(),
+            _ => self.newline.repeat(2),
+        };
+        format!("{}{}{}", lead, body, &self.tail[end..])
+    }
+
+    /// Text we generated, with the file's line endings
+    fn with_newlines(&self, text: &str) -> String {
+        match self.newline {
+            "\n" => text.to_string(),
+            newline => text.replace("\r\n", "\n").replace('\n', newline),
+        }
+    }
+}
+
+fn parse_yaml(text: &str) -> Result<Mapping> {
+    let value: Value = serde_yaml::from_str(text).map_err(|e| {
+        let message = e.to_string();
+        match e.location() {
+            Some(location) => {
+                // Lines are counted from the frontmatter, which starts on line 2
+                let suffix = format!(" at line {} column {}", location.line(), location.column());
+                let message = message.replacen(&suffix, "", 1);
+                let message = LINE.replace_all(&message, |caps: &regex::Captures| {
+                    let line: usize = caps[1].parse().unwrap_or_default();
+                    format!("line {}", line + 1)
+                });
+                invalid(location.line() + 1, message)
+            }
+            None => errors::error(
+                ErrorKind::InvalidInput,
+                format!("Invalid frontmatter: {}", message),
+            ),
+        }
+    })?;
+
+    match value {
+        Value::Mapping(values) => Ok(values),
+        Value::Null => Ok(Mapping::new()),
+        _ => Err(invalid(
+            2,
+            "the frontmatter must be fields like 'Title: ...'",
+        )),
+    }
+}
+
+fn parse_toml(text: &str) -> Result<Mapping> {
+    toml_edit::de::from_str(text).map_err(|e| {
+        let offset = e.span().map_or(0, |span| span.start);
+        let line = text[..offset.min(text.len())].matches('\n').count() + 2;
+        invalid(line, e.message().trim_end())
+    })
+}
+
+/// An error in a task file, at a line counted from the top of the file
+fn invalid(line: usize, message: impl std::fmt::Display) -> anyhow::Error {
+    errors::error(
+        ErrorKind::InvalidInput,
+        format!("Invalid task file at line {}: {}", line, message),
+    )
+}
+
+/// A single field as YAML, ending in a newline
+fn yaml_field(key: &Value, value: &Value) -> Result<String> {
+    let mut field = Mapping::new();
+    field.insert(key.clone(), value.clone());
+    serde_yaml::to_string(&field).context("Failed to serialize task frontmatter")
+}
+
+/// A single field as a TOML item
+fn toml_field(name: &str, key: &Value, value: &Value) -> Result<toml_edit::Item> {
+    let mut field = Mapping::new();
+    field.insert(key.clone(), value.clone());
+    let mut doc =
+        toml_edit::ser::to_document(&field).context("Failed to serialize task frontmatter")?;
+    doc.remove(name)
+        .context("Failed to serialize task frontmatter")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(yaml: &str) -> Mapping {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const YAML: &str = "ID: '20260108120000'   # keep this\nTitle: Parser\n\n# Who's on it\nAssignee: ada\nTags:\n- cli\n- parser\nDue: 2026-02-01\n";
+
+    #[test]
+    fn test_parse_splits_on_delimiter_lines_only() {
+        let content = "---\nID: '1'\nTitle: A --- B\n---\n\nBody with\n---\na rule\n";
+        let source = Source::parse(content).unwrap();
+
+        assert_eq!(source.format(), Format::Yaml);
+        assert_eq!(source.values()["Title"], "A --- B");
+        assert_eq!(source.body(), "Body with\n---\na rule");
+    }
+
+    #[test]
+    fn test_parse_crlf_and_bom() {
+        let content = "\u{feff}---\r\nID: '1'\r\nTitle: Parser\r\n---\r\n\r\nBody\r\n";
+        let source = Source::parse(content).unwrap();
+
+        assert_eq!(source.values()["Title"], "Parser");
+        assert_eq!(source.body(), "Body");
+
+        let mut new = source.values().clone();
+        new.insert("Priority".into(), "High".into());
+        assert_eq!(
+            source.render(&new, "Body").unwrap(),
+            "\u{feff}---\r\nID: '1'\r\nTitle: Parser\r\nPriority: High\r\n---\r\n\r\nBody\r\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_have_line_numbers() {
+        let message = |content: &str| Source::parse(content).unwrap_err().to_string();
+
+        assert!(message("Title: Parser\n---\n").starts_with("Invalid task file at line 1:"));
+        assert!(message("\n---\nTitle: Parser\n---\n").starts_with("Invalid task file at line 1:"));
+        assert!(message("---\nTitle: Parser\n").contains("never closed with a '---' line"));
+        assert!(message("---\nID: '1'\nTitle: Parser: Lexer\n---\n")
+            .starts_with("Invalid task file at line 3:"));
+        assert!(message("---\nID: '1'\nTitle: [Parser\n---\n")
+            .ends_with("while parsing a flow sequence at line 3 column 8"));
+        assert!(
+            message("+++\nID = '1'\nTitle = \n+++\n").starts_with("Invalid task file at line 3:")
+        );
+    }
+
+    #[test]
+    fn test_render_unchanged() {
+        let content = format!("---\n{}---\n\nBody text\n", YAML);
+        let source = Source::parse(&content).unwrap();
+
+        assert_eq!(source.render(&values(YAML), "Body text").unwrap(), content);
+    }
+
+    #[test]
+    fn test_render_changed_fields_in_place() {
+        let source = Source::parse(&format!("---\n{}---\n\nBody text\n", YAML)).unwrap();
+        let mut new = values(YAML);
+        new.insert("Title".into(), "Lexer".into());
+        new.remove("Due");
+        new.insert(
+            "Tags".into(),
+            Value::Sequence(vec!["cli".into(), "lexer".into()]),
+        );
+        new.insert("Priority".into(), "High".into());
+
+        let content = source.render(&new, "New body").unwrap();
+        assert_eq!(
+            content,
+            "---\nID: '20260108120000'   # keep this\nTitle: Lexer\n\n# Who's on it\nAssignee: ada\nTags:\n- cli\n- lexer\nPriority: High\n---\n\nNew body\n"
+        );
+    }
+
+    #[test]
+    fn test_render_null_field_unchanged() {
+        let content = "---\nID: '1'\nBlocks:\nTitle: Parser\n---\n";
+        let source = Source::parse(content).unwrap();
+        let mut new = source.values().clone();
+        new.remove("Blocks");
+
+        assert_eq!(source.render(&new, "").unwrap(), content);
+    }
+
+    #[test]
+    fn test_render_unsplittable_rewrites_all() {
+        let source = Source::parse("---\n{ID: '1', Title: Parser}\n---\n\nBody").unwrap();
+        let mut new = source.values().clone();
+        new.insert("Title".into(), "Lexer".into());
+
+        assert_eq!(
+            source.render(&new, "Body").unwrap(),
+            "---\nID: '1'\nTitle: Lexer\n---\n\nBody"
+        );
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let content = "+++\nID = \"1\"\nTitle = \"Parser\"  # working title\n\n# Who's on it\nAssignee = \"ada\"\nTags = [\"cli\"]\n+++\n\nBody\n";
+        let source = Source::parse(content).unwrap();
+
+        assert_eq!(source.format(), Format::Toml);
+        assert_eq!(source.values()["Assignee"], "ada");
+        assert_eq!(
+            source.render(&source.values().clone(), "Body").unwrap(),
+            content
+        );
+
+        let mut new = source.values().clone();
+        new.insert(
+            "Tags".into(),
+            Value::Sequence(vec!["cli".into(), "parser".into()]),
+        );
+        new.insert("Priority".into(), "High".into());
+        assert_eq!(
+            source.render(&new, "Body").unwrap(),
+            "+++\nID = \"1\"\nTitle = \"Parser\"  # working title\n\n# Who's on it\nAssignee = \"ada\"\nTags = [\"cli\", \"parser\"]\nPriority = \"High\"\n+++\n\nBody\n"
+        );
+    }
+}
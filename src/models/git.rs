@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use git2::{BranchType, IndexAddOption, Repository, Signature, StatusOptions};
+use serde::Serialize;
+use std::path::Path;
+
+use super::root::TASKS_DIR;
+use super::Task;
+use crate::utils::errors;
+
+/// A change under `.repo-tasks/` picked up by `tasks save`
+#[derive(Debug, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    /// "new file", "modified", "deleted" or "changed"
+    pub change: &'static str,
+}
+
+/// What `tasks save` did, as printed by `--json`
+#[derive(Debug, Serialize)]
+pub struct SaveOutcome {
+    pub committed: bool,
+    pub commit: Option<String>,
+    pub message: Option<String>,
+    pub files: Vec<FileChange>,
+    pub pushed: bool,
+}
+
+/// Open the git repository holding the task repository at `root`
+pub fn open(root: &Path) -> Result<Repository> {
+    // An empty root is the current directory
+    let start = if root.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        root
+    };
+
+    Repository::discover(start)
+        .context("Not in a git repository. Initialize git with 'git init' first.")
+}
+
+/// The `.repo-tasks/` directory of `root`, relative to the top of the git
+/// worktree and with a trailing slash, the way git names directories
+pub fn tasks_pathspec(repo: &Repository, root: &Path) -> Result<String> {
+    let workdir = repo
+        .workdir()
+        .context("The git repository has no working directory")?
+        .canonicalize()?;
+    let tasks_dir = root.join(TASKS_DIR).canonicalize()?;
+
+    let relative = tasks_dir
+        .strip_prefix(&workdir)
+        .context("The task repository is outside the git worktree")?;
+
+    Ok(format!(
+        "{}/",
+        relative.to_string_lossy().replace('\\', "/")
+    ))
+}
+
+/// Commit everything under `pathspec` (see [`tasks_pathspec`])
+///
+/// Refuses to run when files outside it are staged, so they don't get swept
+/// into the task commit. Nothing is pushed; `pushed` is always false.
+pub fn commit_task_changes(
+    repo: &Repository,
+    pathspec: &str,
+    message: Option<String>,
+) -> Result<SaveOutcome> {
+    // Check if there are any changes in .repo-tasks/
+    let mut status_opts = StatusOptions::new();
+    status_opts.include_untracked(true);
+    status_opts.pathspec(pathspec);
+
+    let statuses = repo.statuses(Some(&mut status_opts))?;
+
+    // Work out what will be committed
+    let files: Vec<FileChange> = statuses
+        .iter()
+        .map(|entry| {
+            let status = entry.status();
+
+            let change = if status.is_wt_new() {
+                "new file"
+            } else if status.is_wt_modified() {
+                "modified"
+            } else if status.is_wt_deleted() {
+                "deleted"
+            } else {
+                "changed"
+            };
+
+            FileChange {
+                path: entry.path().unwrap_or("unknown").to_string(),
+                change,
+            }
+        })
+        .collect();
+
+    if files.is_empty() {
+        return Ok(SaveOutcome {
+            committed: false,
+            commit: None,
+            message: None,
+            files,
+            pushed: false,
+        });
+    }
+
+    // Get or generate commit message
+    let commit_message = message.unwrap_or_else(|| generate_commit_message(&files));
+
+    // Check for staged files outside .repo-tasks/
+    let index = repo.index()?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let head_tree = head_commit.tree()?;
+    let diff = repo.diff_tree_to_index(Some(&head_tree), Some(&index), None)?;
+
+    let mut non_task_files = Vec::new();
+    diff.foreach(
+        &mut |delta, _| {
+            if let Some(path) = delta.new_file().path() {
+                let path_str = path.to_string_lossy();
+                if !path_str.starts_with(pathspec) {
+                    non_task_files.push(path_str.to_string());
+                }
+            }
+            true
+        },
+        None,
+        None,
+        None,
+    )?;
+
+    if !non_task_files.is_empty() {
+        return Err(errors::staged_outside_tasks(&non_task_files));
+    }
+
+    // Stage .repo-tasks/ directory
+    let mut index = repo.index()?;
+    index.add_all([pathspec].iter(), IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+
+    // Create the commit
+    let tree_id = index.write_tree()?;
+    let tree = repo.find_tree(tree_id)?;
+
+    let parent_commit = repo.head()?.peel_to_commit()?;
+    let sig = Signature::now("repo-tasks", "tasks@local")?;
+
+    let commit_id = repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        &commit_message,
+        &tree,
+        &[&parent_commit],
+    )?;
+
+    Ok(SaveOutcome {
+        committed: true,
+        commit: Some(commit_id.to_string()),
+        message: Some(commit_message),
+        files,
+        pushed: false,
+    })
+}
+
+/// Create (if needed) and check out the `{id}-{slug}` branch for a task
+///
+/// Returns the branch name and whether it was newly created.
+pub fn checkout_task_branch(repo: &Repository, task: &Task) -> Result<(String, bool)> {
+    // Generate branch name: {id}-{slug}
+    let branch_name = format!("{}-{}", task.id, task.slug);
+
+    // Check if branch already exists
+    let branch_exists = repo.find_branch(&branch_name, BranchType::Local).is_ok();
+
+    if !branch_exists {
+        // Create new branch from HEAD
+        let head = repo.head()?;
+        let commit = head.peel_to_commit()?;
+
+        repo.branch(&branch_name, &commit, false)
+            .context("Failed to create branch")?;
+    }
+
+    // Checkout the branch
+    let obj = repo.revparse_single(&format!("refs/heads/{}", branch_name))?;
+    repo.checkout_tree(&obj, None)?;
+    repo.set_head(&format!("refs/heads/{}", branch_name))?;
+
+    Ok((branch_name, !branch_exists))
+}
+
+/// Generate a commit message based on the changes
+fn generate_commit_message(files: &[FileChange]) -> String {
+    let mut added = 0;
+    let mut modified = 0;
+    let mut deleted = 0;
+
+    for file in files {
+        match file.change {
+            "new file" => added += 1,
+            "modified" => modified += 1,
+            "deleted" => deleted += 1,
+            _ => {}
+        }
+    }
+
+    let mut parts = Vec::new();
+    if added > 0 {
+        parts.push(format!("{} added", added));
+    }
+    if modified > 0 {
+        parts.push(format!("{} modified", modified));
+    }
+    if deleted > 0 {
+        parts.push(format!("{} deleted", deleted));
+    }
+
+    if parts.is_empty() {
+        "Update tasks".to_string()
+    } else {
+        format!("Update tasks: {}", parts.join(", "))
+    }
+}
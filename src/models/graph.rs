@@ -0,0 +1,638 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::PathBuf;
+
+use super::id;
+use super::{Config, Task};
+
+/// Which frontmatter field a dependency reference came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DependencyField {
+    Blocks,
+    DependsOn,
+}
+
+impl DependencyField {
+    /// Get the frontmatter key for this field
+    pub fn key(&self) -> &'static str {
+        match self {
+            DependencyField::Blocks => "Blocks",
+            DependencyField::DependsOn => "DependsOn",
+        }
+    }
+}
+
+/// A `Blocks` or `DependsOn` entry that doesn't point at any known task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    /// ID of the task containing the reference
+    pub task_id: String,
+    /// File of the task containing the reference, if it was loaded from one
+    pub path: Option<PathBuf>,
+    /// Field the reference appears in
+    pub field: DependencyField,
+    /// The unresolved reference as written
+    pub reference: String,
+}
+
+/// A task that can be worked on now, as ranked by [`DependencyGraph::ready_tasks`]
+#[derive(Debug, Clone)]
+pub struct ReadyTask<'a> {
+    pub task: &'a Task,
+    /// Number of open tasks that are (directly or indirectly) waiting on it
+    pub unblocks: usize,
+}
+
+/// Dependency graph across all tasks, built from `DependsOn` and `Blocks`
+///
+/// Both fields describe the same edge from opposite ends: `A DependsOn B`
+/// is equivalent to `B Blocks A`. The graph takes the union of the two, so
+/// an edge declared on only one side is still honoured. References may be
+/// task IDs or slugs; they are resolved to IDs when the graph is built.
+///
+/// Tasks that share an ID are all kept, but references can't tell them
+/// apart, so they share one set of edges. [`DependencyGraph::shared_ids`]
+/// lists them.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// task ID → every task with that ID (normally just one)
+    tasks: BTreeMap<String, Vec<Task>>,
+    /// task ID → IDs of the tasks it depends on
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// task ID → IDs of the tasks it blocks
+    dependents: BTreeMap<String, BTreeSet<String>>,
+    dangling: Vec<DanglingReference>,
+}
+
+impl DependencyGraph {
+    /// Build the graph from a set of tasks (normally every task in the store)
+    pub fn build(tasks: impl IntoIterator<Item = Task>) -> Self {
+        let mut graph = DependencyGraph::default();
+
+        for task in tasks {
+            graph.tasks.entry(task.id.clone()).or_default().push(task);
+        }
+
+        let slugs: HashMap<&str, &str> = graph
+            .tasks()
+            .map(|t| (t.slug.as_str(), t.id.as_str()))
+            .collect();
+
+        let resolve = |reference: &str| -> Option<String> {
+            let reference = reference.trim();
+            if graph.tasks.contains_key(reference) {
+                Some(reference.to_string())
+            } else {
+                slugs.get(reference).map(|id| id.to_string())
+            }
+        };
+
+        let mut edges = Vec::new();
+        let mut dangling = Vec::new();
+
+        for task in graph.tasks() {
+            let fields = [
+                (DependencyField::DependsOn, &task.depends_on),
+                (DependencyField::Blocks, &task.blocks),
+            ];
+
+            for (field, references) in fields {
+                for reference in references.iter().flatten() {
+                    match resolve(reference) {
+                        // Store every edge as (dependent, dependency)
+                        Some(other) => edges.push(match field {
+                            DependencyField::DependsOn => (task.id.clone(), other),
+                            DependencyField::Blocks => (other, task.id.clone()),
+                        }),
+                        None => dangling.push(DanglingReference {
+                            task_id: task.id.clone(),
+                            path: task.path.clone(),
+                            field,
+                            reference: reference.clone(),
+                        }),
+                    }
+                }
+            }
+        }
+
+        for (dependent, dependency) in edges {
+            graph
+                .dependencies
+                .entry(dependent.clone())
+                .or_default()
+                .insert(dependency.clone());
+            graph
+                .dependents
+                .entry(dependency)
+                .or_default()
+                .insert(dependent);
+        }
+        graph.dangling = dangling;
+
+        graph
+    }
+
+    /// Get a task in the graph by ID (the first one, if several share it)
+    pub fn task(&self, id: &str) -> Option<&Task> {
+        self.tasks.get(id).and_then(|tasks| tasks.first())
+    }
+
+    /// Every task with the given ID
+    pub fn tasks_with_id(&self, id: &str) -> &[Task] {
+        self.tasks.get(id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterate over all tasks in the graph, ordered by ID
+    pub fn tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.values().flatten()
+    }
+
+    /// IDs used by more than one task, with the tasks using them
+    pub fn shared_ids(&self) -> impl Iterator<Item = (&str, &[Task])> {
+        self.tasks
+            .iter()
+            .filter(|(_, tasks)| tasks.len() > 1)
+            .map(|(id, tasks)| (id.as_str(), tasks.as_slice()))
+    }
+
+    /// Iterate over every edge as `(dependent, dependency)` pairs
+    pub fn edges(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.dependencies.iter().flat_map(|(dependent, deps)| {
+            deps.iter()
+                .map(move |dependency| (dependent.as_str(), dependency.as_str()))
+        })
+    }
+
+    /// IDs of the tasks `id` directly depends on
+    pub fn dependencies_of(&self, id: &str) -> Vec<&str> {
+        Self::neighbours(&self.dependencies, id)
+    }
+
+    /// IDs of the tasks directly blocked by `id`
+    pub fn dependents_of(&self, id: &str) -> Vec<&str> {
+        Self::neighbours(&self.dependents, id)
+    }
+
+    /// IDs of every task that must be finished before `id`, nearest first
+    pub fn transitive_dependencies_of(&self, id: &str) -> Vec<&str> {
+        Self::reachable(&self.dependencies, id)
+    }
+
+    /// IDs of every task that is (directly or indirectly) blocked by `id`
+    pub fn transitive_dependents_of(&self, id: &str) -> Vec<&str> {
+        Self::reachable(&self.dependents, id)
+    }
+
+    /// Direct dependencies of `id` that aren't in a closed status yet
+    pub fn unmet_dependencies_of(&self, id: &str, closed_statuses: &[String]) -> Vec<&Task> {
+        self.dependencies_of(id)
+            .into_iter()
+            .flat_map(|dep| self.tasks_with_id(dep))
+            .filter(|dep| !closed_statuses.contains(&dep.status))
+            .collect()
+    }
+
+    /// Open tasks blocked by `id` whose dependencies are now all closed
+    ///
+    /// Call this after `id` has been closed to find the work it unblocked.
+    pub fn unblocked_by(&self, id: &str, closed_statuses: &[String]) -> Vec<&Task> {
+        self.dependents_of(id)
+            .into_iter()
+            .flat_map(|dependent| self.tasks_with_id(dependent))
+            .filter(|t| !closed_statuses.contains(&t.status))
+            .filter(|t| {
+                self.unmet_dependencies_of(&t.id, closed_statuses)
+                    .is_empty()
+            })
+            .collect()
+    }
+
+    /// Tasks in the first status whose dependencies are all closed, best first
+    ///
+    /// Ranked by priority (using the order of `config.priorities`, last is
+    /// most important), then age (oldest first, derived from the ID), then
+    /// by how many open tasks each one unblocks.
+    pub fn ready_tasks(&self, config: &Config) -> Vec<ReadyTask<'_>> {
+        let Some(initial_status) = config.statuses.first() else {
+            return Vec::new();
+        };
+
+        let priority_rank = |task: &Task| {
+            task.priority
+                .as_ref()
+                .and_then(|p| config.priorities.iter().position(|x| x == p))
+                .map_or(0, |i| i + 1)
+        };
+
+        let mut ready: Vec<ReadyTask> = self
+            .tasks()
+            .filter(|t| &t.status == initial_status)
+            .filter(|t| {
+                self.unmet_dependencies_of(&t.id, &config.closed_statuses)
+                    .is_empty()
+            })
+            .map(|task| ReadyTask {
+                task,
+                unblocks: self
+                    .transitive_dependents_of(&task.id)
+                    .into_iter()
+                    .flat_map(|id| self.tasks_with_id(id))
+                    .filter(|t| !config.is_closed(&t.status))
+                    .count(),
+            })
+            .collect();
+
+        ready.sort_by(|a, b| {
+            priority_rank(b.task)
+                .cmp(&priority_rank(a.task))
+                .then_with(|| id::age_key(&a.task.id).cmp(&id::age_key(&b.task.id)))
+                .then_with(|| b.unblocks.cmp(&a.unblocks))
+                .then_with(|| a.task.id.cmp(&b.task.id))
+                .then_with(|| a.task.path.cmp(&b.task.path))
+        });
+
+        ready
+    }
+
+    /// References that don't resolve to any task
+    pub fn dangling_references(&self) -> &[DanglingReference] {
+        &self.dangling
+    }
+
+    /// Find dependency cycles
+    ///
+    /// Each cycle is a strongly connected component of the graph (or a task
+    /// that depends on itself), listed as sorted task IDs.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut tarjan = Tarjan {
+            graph: &self.dependencies,
+            index: 0,
+            indices: HashMap::new(),
+            lowlinks: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            components: Vec::new(),
+        };
+
+        for id in self.tasks.keys() {
+            if !tarjan.indices.contains_key(id.as_str()) {
+                tarjan.visit(id);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = tarjan
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || self
+                        .dependencies
+                        .get(component[0])
+                        .is_some_and(|deps| deps.contains(component[0]))
+            })
+            .map(|component| {
+                let mut ids: Vec<String> = component.into_iter().map(String::from).collect();
+                ids.sort();
+                ids
+            })
+            .collect();
+
+        cycles.sort();
+        cycles
+    }
+
+    /// Make `Blocks` and `DependsOn` symmetric across the given tasks
+    ///
+    /// Every edge in the graph is written to both ends: the dependent lists
+    /// the dependency under `DependsOn` and the dependency lists the dependent
+    /// under `Blocks`. Existing entries keep their order and spelling (e.g. a
+    /// slug is not rewritten to an ID). Returns the IDs of the tasks that
+    /// changed and need saving. Tasks sharing an ID are left alone, since
+    /// references can't tell them apart.
+    pub fn symmetrize(tasks: &mut [Task]) -> Vec<String> {
+        let graph = Self::build(tasks.iter().cloned());
+        let mut changed = BTreeSet::new();
+
+        for task in tasks.iter_mut() {
+            if graph.tasks_with_id(&task.id).len() > 1 {
+                continue;
+            }
+
+            let expected = [
+                (graph.dependencies_of(&task.id), &mut task.depends_on),
+                (graph.dependents_of(&task.id), &mut task.blocks),
+            ];
+
+            for (ids, field) in expected {
+                for id in ids {
+                    let present = field.iter().flatten().any(|r| {
+                        r.trim() == id || graph.tasks_with_id(id).iter().any(|t| t.slug == r.trim())
+                    });
+
+                    if !present {
+                        field.get_or_insert_with(Vec::new).push(id.to_string());
+                        changed.insert(task.id.clone());
+                    }
+                }
+            }
+        }
+
+        changed.into_iter().collect()
+    }
+
+    fn neighbours<'a>(edges: &'a BTreeMap<String, BTreeSet<String>>, id: &str) -> Vec<&'a str> {
+        edges
+            .get(id)
+            .map(|ids| ids.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    fn reachable<'a>(edges: &'a BTreeMap<String, BTreeSet<String>>, id: &str) -> Vec<&'a str> {
+        let mut seen = BTreeSet::new();
+        let mut order = Vec::new();
+        let mut queue: VecDeque<&str> = VecDeque::from(Self::neighbours(edges, id));
+
+        while let Some(next) = queue.pop_front() {
+            if next == id || !seen.insert(next) {
+                continue;
+            }
+            order.push(next);
+            queue.extend(Self::neighbours(edges, next));
+        }
+
+        order
+    }
+}
+
+/// Tarjan's strongly connected components algorithm over task IDs
+struct Tarjan<'a> {
+    graph: &'a BTreeMap<String, BTreeSet<String>>,
+    index: usize,
+    indices: HashMap<&'a str, usize>,
+    lowlinks: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn visit(&mut self, id: &'a str) {
+        self.indices.insert(id, self.index);
+        self.lowlinks.insert(id, self.index);
+        self.index += 1;
+        self.stack.push(id);
+        self.on_stack.insert(id);
+
+        for next in self.graph.get(id).into_iter().flatten() {
+            let next = next.as_str();
+            if !self.indices.contains_key(next) {
+                self.visit(next);
+                let low = self.lowlinks[id].min(self.lowlinks[next]);
+                self.lowlinks.insert(id, low);
+            } else if self.on_stack.contains(next) {
+                let low = self.lowlinks[id].min(self.indices[next]);
+                self.lowlinks.insert(id, low);
+            }
+        }
+
+        if self.lowlinks[id] == self.indices[id] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == id {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, depends_on: &[&str], blocks: &[&str]) -> Task {
+        let mut task = Task::new(format!("Task {}", id), "Medium".to_string());
+        task.id = id.to_string();
+        task.slug = format!("task-{}", id);
+        let list =
+            |ids: &[&str]| (!ids.is_empty()).then(|| ids.iter().map(|s| s.to_string()).collect());
+        task.depends_on = list(depends_on);
+        task.blocks = list(blocks);
+        task
+    }
+
+    #[test]
+    fn test_blocks_and_depends_on_are_merged() {
+        // a depends on b (declared on a), c blocks a (declared on c)
+        let graph = DependencyGraph::build(vec![
+            task("a", &["b"], &[]),
+            task("b", &[], &[]),
+            task("c", &[], &["a"]),
+        ]);
+
+        assert_eq!(graph.dependencies_of("a"), vec!["b", "c"]);
+        assert_eq!(graph.dependents_of("b"), vec!["a"]);
+        assert_eq!(graph.dependents_of("c"), vec!["a"]);
+        assert!(graph.dangling_references().is_empty());
+    }
+
+    #[test]
+    fn test_references_resolve_by_slug() {
+        let graph = DependencyGraph::build(vec![task("a", &["task-b"], &[]), task("b", &[], &[])]);
+
+        assert_eq!(graph.dependencies_of("a"), vec!["b"]);
+    }
+
+    #[test]
+    fn test_transitive_queries() {
+        // d → c → b → a (each depends on the next)
+        let graph = DependencyGraph::build(vec![
+            task("a", &[], &[]),
+            task("b", &["a"], &[]),
+            task("c", &["b"], &[]),
+            task("d", &["c"], &[]),
+        ]);
+
+        assert_eq!(graph.transitive_dependencies_of("d"), vec!["c", "b", "a"]);
+        assert_eq!(graph.transitive_dependents_of("a"), vec!["b", "c", "d"]);
+        assert!(graph.transitive_dependencies_of("a").is_empty());
+        assert!(graph.cycles().is_empty());
+    }
+
+    #[test]
+    fn test_unmet_and_unblocked() {
+        let closed = vec!["done".to_string()];
+        let mut a = task("a", &[], &[]);
+        a.status = "done".to_string();
+        let mut c = task("c", &["a", "b"], &[]);
+        c.status = "todo".to_string();
+        let mut d = task("d", &["a"], &[]);
+        d.status = "todo".to_string();
+        let graph = DependencyGraph::build(vec![a, task("b", &[], &[]), c, d]);
+
+        let unmet: Vec<&str> = graph
+            .unmet_dependencies_of("c", &closed)
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(unmet, vec!["b"]);
+
+        // Closing a unblocks d, but c still waits on b
+        let unblocked: Vec<&str> = graph
+            .unblocked_by("a", &closed)
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(unblocked, vec!["d"]);
+    }
+
+    #[test]
+    fn test_ready_tasks_ranking() {
+        let config = Config::default(Some("test".to_string()));
+        let with = |id: &str, priority: &str, status: &str, depends_on: &[&str]| {
+            let mut t = task(id, depends_on, &[]);
+            t.priority = Some(priority.to_string());
+            t.status = status.to_string();
+            t
+        };
+
+        let graph = DependencyGraph::build(vec![
+            with("20260101000003", "High", "todo", &[]),
+            with("20260101000001", "High", "todo", &[]),
+            with("20260101000002", "Critical", "todo", &["20260101000009"]),
+            with("20260101000004", "Low", "todo", &["20260101000005"]),
+            with("20260101000005", "Low", "done", &[]),
+            with("20260101000006", "High", "in-progress", &[]),
+            with("20260101000009", "Low", "todo", &[]),
+        ]);
+
+        let ready: Vec<(&str, usize)> = graph
+            .ready_tasks(&config)
+            .iter()
+            .map(|r| (r.task.id.as_str(), r.unblocks))
+            .collect();
+
+        // ...02 is blocked, ...06 isn't in todo; High before Low, older first
+        assert_eq!(
+            ready,
+            vec![
+                ("20260101000001", 0),
+                ("20260101000003", 0),
+                ("20260101000004", 0),
+                ("20260101000009", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ready_tasks_tie_break_on_unblocks() {
+        let config = Config::default(Some("test".to_string()));
+        let mut a = task("RT-1", &[], &[]);
+        a.id = "custom-a".to_string();
+        let mut b = task("RT-2", &[], &[]);
+        b.id = "custom-b".to_string();
+        let c = task("RT-3", &["custom-b"], &[]);
+
+        let graph = DependencyGraph::build(vec![a, b, c]);
+        let ready: Vec<&str> = graph
+            .ready_tasks(&config)
+            .iter()
+            .map(|r| r.task.id.as_str())
+            .collect();
+
+        // Same priority, no comparable age: the task unblocking work wins
+        assert_eq!(ready, vec!["custom-b", "custom-a"]);
+    }
+
+    #[test]
+    fn test_detects_cycles() {
+        let graph = DependencyGraph::build(vec![
+            task("a", &["b"], &[]),
+            task("b", &["c"], &[]),
+            task("c", &["a"], &[]),
+            task("d", &["d"], &[]),
+            task("e", &["a"], &[]),
+        ]);
+
+        assert_eq!(
+            graph.cycles(),
+            vec![
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                vec!["d".to_string()]
+            ]
+        );
+
+        // Traversal terminates even with cycles
+        assert_eq!(graph.transitive_dependencies_of("e"), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_detects_dangling_references() {
+        let graph = DependencyGraph::build(vec![task("a", &["missing"], &["gone"])]);
+
+        let dangling = graph.dangling_references();
+        assert_eq!(dangling.len(), 2);
+        assert_eq!(dangling[0].field, DependencyField::DependsOn);
+        assert_eq!(dangling[0].reference, "missing");
+        assert_eq!(dangling[1].field, DependencyField::Blocks);
+    }
+
+    #[test]
+    fn test_shared_ids_keep_every_task() {
+        let config = Config::default(Some("test".to_string()));
+        let mut first = task("a", &[], &[]);
+        first.path = Some(PathBuf::from("todo/a-first.md"));
+        let mut second = task("a", &["b"], &[]);
+        second.slug = "second".to_string();
+        second.path = Some(PathBuf::from("todo/a-second.md"));
+        let mut b = task("b", &[], &[]);
+        b.status = "done".to_string();
+
+        let graph = DependencyGraph::build(vec![first, second, b]);
+
+        assert_eq!(graph.tasks().count(), 3);
+        assert_eq!(graph.tasks_with_id("a").len(), 2);
+        let shared: Vec<&str> = graph.shared_ids().map(|(id, _)| id).collect();
+        assert_eq!(shared, vec!["a"]);
+
+        // Both are ready, and the edge from the second applies to the ID
+        let ready: Vec<&str> = graph
+            .ready_tasks(&config)
+            .iter()
+            .map(|r| r.task.slug.as_str())
+            .collect();
+        assert_eq!(ready, vec!["task-a", "second"]);
+        assert_eq!(graph.dependencies_of("a"), vec!["b"]);
+
+        // Only b is written to; the tasks sharing `a` can't be told apart
+        let mut tasks: Vec<Task> = graph.tasks().cloned().collect();
+        assert_eq!(DependencyGraph::symmetrize(&mut tasks), vec!["b"]);
+        assert_eq!(tasks[0].depends_on, None);
+    }
+
+    #[test]
+    fn test_symmetrize() {
+        let mut tasks = vec![
+            task("a", &["b"], &[]),
+            task("b", &[], &[]),
+            task("c", &[], &["task-a"]),
+        ];
+
+        let changed = DependencyGraph::symmetrize(&mut tasks);
+
+        assert_eq!(changed, vec!["a", "b"]);
+        assert_eq!(
+            tasks[0].depends_on,
+            Some(vec!["b".to_string(), "c".to_string()])
+        );
+        assert_eq!(tasks[1].blocks, Some(vec!["a".to_string()]));
+        // Already symmetric via the slug reference, so left untouched
+        assert_eq!(tasks[2].blocks, Some(vec!["task-a".to_string()]));
+
+        // Running again is a no-op
+        assert!(DependencyGraph::symmetrize(&mut tasks).is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use ulid::Ulid;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// How new task IDs are generated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdScheme {
+    /// Local time, `YYYYMMDDHHmmSS` (the original format)
+    #[default]
+    Timestamp,
+    /// UTC time, `YYYYMMDDHHmmSS`
+    UtcTimestamp,
+    /// 26-character ULID, e.g. `01HZX3K4Q2W9Y8V7T6S5R4P3N2`
+    Ulid,
+    /// Project-prefixed counter, e.g. `RT-42`
+    Sequential,
+}
+
+impl IdScheme {
+    /// Generate an ID that isn't already in `existing`
+    ///
+    /// Timestamp schemes step forward one second at a time until they find a
+    /// free slot, so IDs keep their format and still sort by creation order.
+    /// Sequential IDs take the next number after the highest one in use.
+    pub fn generate(&self, prefix: &str, existing: &HashSet<String>) -> String {
+        match self {
+            IdScheme::Timestamp => next_free_timestamp(Local::now().naive_local(), existing),
+            IdScheme::UtcTimestamp => next_free_timestamp(Utc::now().naive_utc(), existing),
+            IdScheme::Ulid => loop {
+                let id = Ulid::new().to_string();
+                if !existing.contains(&id) {
+                    break id;
+                }
+            },
+            IdScheme::Sequential => {
+                let next = existing
+                    .iter()
+                    .filter_map(|id| sequence_number(id, prefix))
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+                format!("{}-{}", prefix, next)
+            }
+        }
+    }
+
+    /// Regex fragment matching an ID in this scheme
+    pub fn pattern(&self, prefix: &str) -> String {
+        match self {
+            IdScheme::Timestamp | IdScheme::UtcTimestamp => r"\d{14}".to_string(),
+            IdScheme::Ulid => "[0-9A-HJKMNP-TV-Z]{26}".to_string(),
+            IdScheme::Sequential => format!(r"{}-\d+", regex::escape(prefix)),
+        }
+    }
+}
+
+/// Recover when a task was created from its ID, if the scheme encodes it
+///
+/// Works for timestamp IDs (read as local time, which is also correct for
+/// ordering UTC timestamps against each other) and ULIDs.
+pub fn created_at(id: &str) -> Option<NaiveDateTime> {
+    if id.len() == 14 && id.chars().all(|c| c.is_ascii_digit()) {
+        return NaiveDateTime::parse_from_str(id, TIMESTAMP_FORMAT).ok();
+    }
+
+    let ulid = Ulid::from_string(id).ok()?;
+    DateTime::from_timestamp_millis(ulid.timestamp_ms() as i64).map(|t| t.naive_local())
+}
+
+/// Sort key putting older tasks first
+///
+/// Timestamped IDs sort by creation time and sequential IDs by their number.
+/// IDs from different schemes group by scheme, and unknown formats go last
+/// (tying with each other).
+pub fn age_key(id: &str) -> (u8, i64) {
+    if let Some(time) = created_at(id) {
+        return (0, time.and_utc().timestamp());
+    }
+
+    let number = id.rsplit_once('-').and_then(|(_, n)| n.parse::<i64>().ok());
+
+    match number {
+        Some(n) => (1, n),
+        None => (2, 0),
+    }
+}
+
+/// Derive a default ID prefix from a project name (`repo-tasks` → `RT`)
+pub fn default_prefix(project_name: &str) -> String {
+    let prefix: String = project_name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter_map(|word| word.chars().next())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if prefix.is_empty() {
+        "T".to_string()
+    } else {
+        prefix
+    }
+}
+
+/// Find the first timestamp ID at or after `start` that isn't taken
+fn next_free_timestamp(start: NaiveDateTime, existing: &HashSet<String>) -> String {
+    let mut time = start;
+    loop {
+        let id = time.format(TIMESTAMP_FORMAT).to_string();
+        if !existing.contains(&id) {
+            return id;
+        }
+        time += Duration::seconds(1);
+    }
+}
+
+/// Extract the counter from a sequential ID with the given prefix
+fn sequence_number(id: &str, prefix: &str) -> Option<u64> {
+    id.strip_prefix(prefix)?.strip_prefix('-')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_timestamp_skips_existing_ids() {
+        let start = NaiveDateTime::parse_from_str("20260110142106", TIMESTAMP_FORMAT).unwrap();
+        let existing: HashSet<String> = ["20260110142106", "20260110142107"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(next_free_timestamp(start, &existing), "20260110142108");
+    }
+
+    #[test]
+    fn test_generate_is_unique_in_bulk() {
+        for scheme in [
+            IdScheme::Timestamp,
+            IdScheme::UtcTimestamp,
+            IdScheme::Ulid,
+            IdScheme::Sequential,
+        ] {
+            let mut existing = HashSet::new();
+            for _ in 0..50 {
+                let id = scheme.generate("RT", &existing);
+                assert!(existing.insert(id), "{:?} generated a duplicate", scheme);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequential_ids() {
+        let existing: HashSet<String> = ["RT-1", "RT-41", "OTHER-99", "20260110142106"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(IdScheme::Sequential.generate("RT", &existing), "RT-42");
+        assert_eq!(IdScheme::Sequential.generate("RT", &HashSet::new()), "RT-1");
+    }
+
+    #[test]
+    fn test_patterns_match_generated_ids() {
+        for scheme in [
+            IdScheme::Timestamp,
+            IdScheme::UtcTimestamp,
+            IdScheme::Ulid,
+            IdScheme::Sequential,
+        ] {
+            let id = scheme.generate("RT", &HashSet::new());
+            let regex = Regex::new(&format!("^{}$", scheme.pattern("RT"))).unwrap();
+            assert!(regex.is_match(&id), "{:?} pattern rejects {}", scheme, id);
+        }
+    }
+
+    #[test]
+    fn test_created_at() {
+        let time = created_at("20260110142106").unwrap();
+        assert_eq!(time.format(TIMESTAMP_FORMAT).to_string(), "20260110142106");
+
+        let ulid = Ulid::new().to_string();
+        assert!(created_at(&ulid).is_some());
+
+        assert!(created_at("RT-42").is_none());
+        assert!(created_at("20261399999999").is_none());
+    }
+
+    #[test]
+    fn test_age_key_orders_oldest_first() {
+        let mut ids = vec![
+            "RT-10",
+            "20260110142106",
+            "RT-9",
+            "20250101000000",
+            "custom",
+        ];
+        ids.sort_by_key(|id| (age_key(id), *id));
+
+        assert_eq!(
+            ids,
+            vec![
+                "20250101000000",
+                "20260110142106",
+                "RT-9",
+                "RT-10",
+                "custom"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_prefix() {
+        assert_eq!(default_prefix("repo-tasks"), "RT");
+        assert_eq!(default_prefix("my project"), "MP");
+        assert_eq!(default_prefix("---"), "T");
+    }
+
+    #[test]
+    fn test_scheme_serialization() {
+        assert_eq!(
+            serde_json::to_string(&IdScheme::UtcTimestamp).unwrap(),
+            "\"utc-timestamp\""
+        );
+        let scheme: IdScheme = serde_json::from_str("\"sequential\"").unwrap();
+        assert_eq!(scheme, IdScheme::Sequential);
+    }
+}
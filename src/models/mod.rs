@@ -1,5 +1,20 @@
 pub mod config;
+pub mod dependencies;
+pub mod doctor;
+pub mod frontmatter;
+pub mod git;
+pub mod graph;
+pub mod id;
+pub mod repository;
+pub mod root;
+pub mod schema;
+pub mod search;
+pub mod store;
 pub mod task;
 
-pub use config::Config;
+pub use config::{Config, HookPolicy};
+pub use graph::{DependencyGraph, ReadyTask};
+pub use id::IdScheme;
+pub use repository::{MoveOutcome, NewTask, Repository, StartOutcome, TaskUpdate, UpdateOutcome};
+pub use store::TaskStore;
 pub use task::Task;
@@ -0,0 +1,785 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use super::dependencies::{ensure_dependencies_met, newly_unblocked};
+use super::git::{self, SaveOutcome};
+use super::root::{self, TASKS_DIR};
+use super::search::{search_tasks, SearchMatch};
+use super::{Config, DependencyGraph, Task, TaskStore};
+use crate::utils::errors::{self, ErrorKind};
+
+/// Frontmatter fields [`TaskUpdate::set`] can't touch
+const BUILT_IN_FIELDS: &[&str] = &[
+    "ID",
+    "Title",
+    "Priority",
+    "Blocks",
+    "DependsOn",
+    "Tags",
+    "Commits",
+];
+
+/// A task repository: the directory holding `.repo-tasks/`
+///
+/// Every path is resolved against the directory the repository was opened
+/// at, never the current directory, so a program can work with several
+/// repositories at once. This is what the `tasks` commands are built on.
+///
+/// ```no_run
+/// use repo_tasks::{NewTask, Repository};
+///
+/// let repo = Repository::open("/path/to/project")?;
+/// let task = repo.create(NewTask::titled("Write the parser"))?;
+/// repo.move_task(task, "done", false)?;
+/// # anyhow::Ok(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Repository {
+    root: PathBuf,
+}
+
+/// A task to create with [`Repository::create`]
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    pub title: String,
+    /// One of the configured priorities; [`Config::default_priority`] if not given
+    pub priority: Option<String>,
+    pub tags: Vec<String>,
+    /// Markdown body
+    pub body: String,
+}
+
+impl NewTask {
+    /// A task with just a title
+    pub fn titled(title: impl Into<String>) -> Self {
+        NewTask {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Changes to make with [`Repository::update`]; anything left empty is kept
+#[derive(Debug, Clone, Default)]
+pub struct TaskUpdate {
+    pub title: Option<String>,
+    /// One of the configured priorities
+    pub priority: Option<String>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    /// Replaces the body
+    pub body: Option<String>,
+    /// Added to the end of the body, after a blank line
+    pub append_body: Option<String>,
+    /// Tasks that must be done first, by ID, slug or unique prefix
+    pub depends_on: Vec<String>,
+    /// Tasks that can't start until this one is done, by ID, slug or unique prefix
+    pub blocks: Vec<String>,
+    /// Other frontmatter fields, like `Assignee`; null removes the field
+    pub set: Vec<(String, serde_yaml::Value)>,
+}
+
+impl TaskUpdate {
+    /// Whether there's nothing to change
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.priority.is_none()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.body.is_none()
+            && self.append_body.is_none()
+            && self.depends_on.is_empty()
+            && self.blocks.is_empty()
+            && self.set.is_empty()
+    }
+}
+
+/// What [`Repository::update`] did
+#[derive(Debug, Clone)]
+pub struct UpdateOutcome {
+    pub task: Task,
+    /// The task's old file, if a new title gave it a new name
+    pub renamed_from: Option<PathBuf>,
+    /// Other tasks given the matching `Blocks` or `DependsOn` entry
+    pub linked: Vec<Task>,
+}
+
+/// What [`Repository::move_task`] did
+#[derive(Debug, Clone)]
+pub struct MoveOutcome {
+    pub task: Task,
+    pub from: String,
+    /// False if the task was already in the target status
+    pub moved: bool,
+    /// Tasks that became actionable because this one was closed
+    pub unblocked: Vec<Task>,
+}
+
+/// What [`Repository::start`] did
+#[derive(Debug, Clone)]
+pub struct StartOutcome {
+    pub task: Task,
+    pub from: String,
+    /// False if the task was already in the started status
+    pub moved: bool,
+    pub branch: String,
+    /// False if the branch already existed
+    pub branch_created: bool,
+}
+
+impl Repository {
+    /// Create a repository at `root`, with the default config
+    ///
+    /// The project is named after `root` unless `project_name` is given.
+    pub fn init(root: impl Into<PathBuf>, project_name: Option<String>) -> Result<Self> {
+        let repo = Repository { root: root.into() };
+
+        // Check if already initialized
+        if repo.tasks_dir().exists() {
+            bail!("Repository already initialized");
+        }
+
+        let project_name = project_name.or_else(|| {
+            let dir = if repo.root.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                &repo.root
+            };
+            dir.canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        });
+        let config = Config::default(project_name);
+
+        // Create directory structure
+        let store = repo.store();
+        for status in &config.statuses {
+            store.create_status_dir(status)?;
+        }
+        repo.write_config(&config)?;
+
+        Ok(repo)
+    }
+
+    /// Open the repository at `root`, the directory holding `.repo-tasks/`
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let repo = Repository { root: root.into() };
+
+        if !Config::is_initialized_in(&repo.root) {
+            return Err(errors::error(
+                ErrorKind::NotInitialized,
+                format!(
+                    "Not a repo-tasks repository: {} (run 'tasks init' there first)",
+                    repo.tasks_dir().display()
+                ),
+            ));
+        }
+
+        Ok(repo)
+    }
+
+    /// Open the repository in the current directory
+    pub fn current() -> Result<Self> {
+        Self::open(PathBuf::new())
+    }
+
+    /// Open the nearest repository at or above `start`, the way `tasks` does
+    pub fn discover(start: &Path) -> Result<Self> {
+        Self::open(root::discover(start))
+    }
+
+    /// The directory holding `.repo-tasks/`
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The `.repo-tasks/` directory
+    pub fn tasks_dir(&self) -> PathBuf {
+        self.root.join(TASKS_DIR)
+    }
+
+    /// Load the config
+    pub fn config(&self) -> Result<Config> {
+        Config::load_from(&self.root)
+    }
+
+    /// Validate and write the config
+    pub fn write_config(&self, config: &Config) -> Result<()> {
+        config.validate()?;
+        config.write_to(&self.root)
+    }
+
+    /// The task files
+    pub fn store(&self) -> TaskStore {
+        TaskStore::new(self.tasks_dir().join("tasks"))
+    }
+
+    /// Find a task by ID, slug or unique prefix of either
+    pub fn find(&self, reference: &str) -> Result<Task> {
+        self.store().find(reference)
+    }
+
+    /// Every task that can be read, in every status
+    pub fn all(&self) -> Result<Vec<Task>> {
+        self.store().all()
+    }
+
+    /// Tasks in `status`, optionally with a given priority and tag, most
+    /// important first
+    pub fn list(
+        &self,
+        status: &str,
+        priority: Option<&str>,
+        tag: Option<&str>,
+    ) -> Result<Vec<Task>> {
+        let config = self.config()?;
+
+        if !config.statuses.iter().any(|s| s == status) {
+            return Err(errors::invalid_status(status, &config.statuses));
+        }
+        if let Some(p) = priority {
+            if !config.priorities.iter().any(|x| x == p) {
+                return Err(errors::invalid_priority(p, &config.priorities));
+            }
+        }
+
+        let mut tasks = self.store().list(status)?;
+        tasks.retain(|t| priority.is_none() || t.priority.as_deref() == priority);
+        if let Some(tag) = tag {
+            tasks.retain(|t| t.tags.iter().flatten().any(|t| t == tag));
+        }
+
+        // Following the configured priority order, which runs from least important
+        let rank = |t: &Task| {
+            t.priority
+                .as_ref()
+                .and_then(|p| config.priorities.iter().position(|x| x == p))
+                .map_or(0, |i| i + 1)
+        };
+        tasks.sort_by_key(|t| std::cmp::Reverse(rank(t)));
+
+        Ok(tasks)
+    }
+
+    /// Create a task in the first status, with an ID unique in this repository
+    pub fn create(&self, new: NewTask) -> Result<Task> {
+        let config = self.config()?;
+
+        let title = new.title.trim();
+        if title.is_empty() {
+            return Err(errors::error(
+                ErrorKind::InvalidInput,
+                "Task title cannot be empty",
+            ));
+        }
+
+        let priority = match new.priority {
+            Some(p) if !config.priorities.contains(&p) => {
+                return Err(errors::invalid_priority(&p, &config.priorities));
+            }
+            Some(p) => p,
+            None => config.default_priority().to_string(),
+        };
+
+        let mut task = Task::new(title.to_string(), priority);
+        task.status = config.initial_status().to_string();
+
+        let tags: Vec<String> = new
+            .tags
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if !tags.is_empty() {
+            task.tags = Some(tags);
+        }
+        task.body = new.body;
+
+        self.store().create(&mut task, &config)?;
+        Ok(task)
+    }
+
+    /// Change a task's fields, checking each change against the config
+    ///
+    /// Nothing is written unless every change is valid.
+    pub fn update(&self, mut task: Task, changes: TaskUpdate) -> Result<UpdateOutcome> {
+        let config = self.config()?;
+        let store = self.store();
+        let invalid = |message: String| errors::error(ErrorKind::InvalidInput, message);
+
+        if let Some(title) = changes.title {
+            let title = title.trim();
+            if title.is_empty() {
+                return Err(invalid("Task title cannot be empty".to_string()));
+            }
+            task.title = title.to_string();
+            task.slug = Task::generate_slug(title);
+        }
+
+        if let Some(priority) = changes.priority {
+            if !config.priorities.contains(&priority) {
+                return Err(errors::invalid_priority(&priority, &config.priorities));
+            }
+            task.priority = Some(priority);
+        }
+
+        if !changes.add_tags.is_empty() || !changes.remove_tags.is_empty() {
+            let mut tags = task.tags.take().unwrap_or_default();
+            for tag in changes.add_tags.iter().map(|t| t.trim()) {
+                if tag.is_empty() {
+                    return Err(invalid("Tags cannot be empty".to_string()));
+                }
+                if !tags.iter().any(|t| t == tag) {
+                    tags.push(tag.to_string());
+                }
+            }
+            tags.retain(|t| !changes.remove_tags.iter().any(|r| r.trim() == t));
+            task.tags = (!tags.is_empty()).then_some(tags);
+        }
+
+        if let Some(body) = changes.body {
+            task.body = body.trim().to_string();
+        }
+        if let Some(text) = changes.append_body {
+            let text = text.trim();
+            task.body = match task.body.trim_end() {
+                "" => text.to_string(),
+                body => format!("{}\n\n{}", body, text),
+            };
+        }
+
+        let mut linked: Vec<Task> = Vec::new();
+        for (references, field) in [
+            (&changes.depends_on, &mut task.depends_on),
+            (&changes.blocks, &mut task.blocks),
+        ] {
+            for reference in references {
+                let other = store.find(reference.trim())?;
+                if other.id == task.id {
+                    return Err(invalid(format!(
+                        "Task {} can't depend on or block itself",
+                        task.id
+                    )));
+                }
+                let ids = field.get_or_insert_with(Vec::new);
+                if !ids.contains(&other.id) {
+                    ids.push(other.id.clone());
+                }
+                if !linked.iter().any(|t| t.id == other.id) {
+                    linked.push(other);
+                }
+            }
+        }
+
+        for (key, value) in changes.set {
+            if BUILT_IN_FIELDS.contains(&key.as_str()) {
+                return Err(invalid(format!(
+                    "Can't set '{}' as a custom field: it's one of the task's own fields",
+                    key
+                )));
+            }
+            if key.trim().is_empty() {
+                return Err(invalid("Field names cannot be empty".to_string()));
+            }
+
+            let key = serde_yaml::Value::String(key);
+            if value.is_null() {
+                task.extra.remove(&key);
+            } else {
+                task.extra.insert(key, value);
+            }
+        }
+
+        // The store removes the old file if the slug changed
+        let old_path = task.path.clone();
+        let new_path = store.save(&mut task)?;
+
+        // Give the linked tasks the other end of each new edge
+        let mut tasks = vec![task];
+        tasks.append(&mut linked);
+        let changed = DependencyGraph::symmetrize(&mut tasks);
+        for other in tasks.iter_mut().filter(|t| changed.contains(&t.id)) {
+            store.save(other)?;
+        }
+
+        let task = tasks.remove(0);
+        Ok(UpdateOutcome {
+            task,
+            renamed_from: old_path.filter(|p| *p != new_path),
+            linked: tasks
+                .into_iter()
+                .filter(|t| changed.contains(&t.id))
+                .collect(),
+        })
+    }
+
+    /// Move a task to another status
+    ///
+    /// Refuses to move ahead of unfinished dependencies unless `force` is set.
+    pub fn move_task(&self, mut task: Task, status: &str, force: bool) -> Result<MoveOutcome> {
+        let config = self.config()?;
+
+        if !config.statuses.iter().any(|s| s == status) {
+            return Err(errors::invalid_status(status, &config.statuses));
+        }
+
+        let from = task.status.clone();
+        if from == status {
+            return Ok(MoveOutcome {
+                task,
+                from,
+                moved: false,
+                unblocked: Vec::new(),
+            });
+        }
+
+        let store = self.store();
+        ensure_dependencies_met(&store, &config, &task, status, force)?;
+        store.move_task(&mut task, status)?;
+        let unblocked = newly_unblocked(&store, &config, &task)?;
+
+        Ok(MoveOutcome {
+            task,
+            from,
+            moved: true,
+            unblocked,
+        })
+    }
+
+    /// Start work on a task: move it to the configured `started_status` and
+    /// check out its `{id}-{slug}` branch, creating the branch if needed
+    pub fn start(&self, mut task: Task, force: bool) -> Result<StartOutcome> {
+        let config = self.config()?;
+        let from = task.status.clone();
+        let moved = from != config.started_status;
+
+        if moved {
+            let store = self.store();
+            ensure_dependencies_met(&store, &config, &task, &config.started_status, force)?;
+            store.move_task(&mut task, &config.started_status)?;
+        }
+
+        let (branch, branch_created) = git::checkout_task_branch(&self.git()?, &task)?;
+
+        Ok(StartOutcome {
+            task,
+            from,
+            moved,
+            branch,
+            branch_created,
+        })
+    }
+
+    /// Every line of every task file matching `query`, a regex
+    pub fn search(&self, query: &str) -> Result<Vec<SearchMatch>> {
+        search_tasks(&self.store(), query)
+    }
+
+    /// Commit the changes under `.repo-tasks/`, with a generated message
+    /// unless one is given
+    ///
+    /// Refuses to run when anything outside `.repo-tasks/` is staged.
+    pub fn save(&self, message: Option<String>) -> Result<SaveOutcome> {
+        let repo = self.git()?;
+        let pathspec = git::tasks_pathspec(&repo, &self.root)?;
+        git::commit_task_changes(&repo, &pathspec, message)
+    }
+
+    /// Open the git repository the task repository is in
+    pub fn git(&self) -> Result<git2::Repository> {
+        git::open(&self.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn repository() -> (TempDir, Repository) {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path(), Some("test".to_string())).unwrap();
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_init_names_project_after_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("my-app");
+        fs::create_dir(&root).unwrap();
+
+        let repo = Repository::init(&root, None).unwrap();
+        assert_eq!(repo.config().unwrap().project_name, "my-app");
+    }
+
+    #[test]
+    fn test_open_requires_init() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let err = Repository::open(temp_dir.path()).unwrap_err();
+        assert_eq!(errors::classify(&err), ErrorKind::NotInitialized);
+    }
+
+    #[test]
+    fn test_create_list_and_find() {
+        let (_temp_dir, repo) = repository();
+
+        let low = repo
+            .create(NewTask {
+                title: "Tidy up".to_string(),
+                priority: Some("Low".to_string()),
+                tags: vec!["chore".to_string(), " ".to_string()],
+                body: String::new(),
+            })
+            .unwrap();
+        let high = repo
+            .create(NewTask {
+                priority: Some("High".to_string()),
+                ..NewTask::titled("  Write parser  ")
+            })
+            .unwrap();
+
+        assert_eq!(high.title, "Write parser");
+        assert_eq!(low.tags, Some(vec!["chore".to_string()]));
+
+        let todo = repo.list("todo", None, None).unwrap();
+        let ids: Vec<&str> = todo.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec![high.id.as_str(), low.id.as_str()]);
+
+        assert_eq!(repo.list("todo", None, Some("chore")).unwrap().len(), 1);
+        assert_eq!(repo.list("todo", Some("High"), None).unwrap().len(), 1);
+        assert!(repo.list("someday", None, None).is_err());
+        assert!(repo.list("todo", Some("Urgent"), None).is_err());
+
+        assert_eq!(repo.find("write-parser").unwrap().id, high.id);
+    }
+
+    #[test]
+    fn test_create_rejects_bad_input() {
+        let (_temp_dir, repo) = repository();
+
+        assert!(repo.create(NewTask::titled(" ")).is_err());
+        assert!(repo
+            .create(NewTask {
+                priority: Some("Urgent".to_string()),
+                ..NewTask::titled("Parser")
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_move_task_checks_dependencies() {
+        let (_temp_dir, repo) = repository();
+
+        let first = repo.create(NewTask::titled("First")).unwrap();
+        let mut second = repo.create(NewTask::titled("Second")).unwrap();
+        second.depends_on = Some(vec![first.id.clone()]);
+        repo.store().save(&mut second).unwrap();
+
+        let err = repo.move_task(second.clone(), "done", false).unwrap_err();
+        assert_eq!(errors::classify(&err), ErrorKind::Blocked);
+
+        let outcome = repo.move_task(first, "done", false).unwrap();
+        assert!(outcome.moved);
+        assert_eq!(outcome.from, "todo");
+        assert_eq!(outcome.task.status, "done");
+        assert_eq!(outcome.unblocked.len(), 1);
+
+        let outcome = repo.move_task(outcome.task, "done", false).unwrap();
+        assert!(!outcome.moved);
+
+        assert!(repo.move_task(second, "someday", false).is_err());
+    }
+
+    #[test]
+    fn test_update() {
+        let (_temp_dir, repo) = repository();
+        let first = repo.create(NewTask::titled("First")).unwrap();
+        let second = repo
+            .create(NewTask {
+                tags: vec!["cli".to_string(), "old".to_string()],
+                body: "Notes".to_string(),
+                ..NewTask::titled("Second")
+            })
+            .unwrap();
+
+        let outcome = repo
+            .update(
+                second,
+                TaskUpdate {
+                    title: Some("Second, renamed".to_string()),
+                    priority: Some("High".to_string()),
+                    add_tags: vec!["parser".to_string(), "cli".to_string()],
+                    remove_tags: vec!["old".to_string()],
+                    append_body: Some("More notes".to_string()),
+                    depends_on: vec!["first".to_string()],
+                    set: vec![
+                        ("Assignee".to_string(), "ada".into()),
+                        ("Estimate".to_string(), 3.into()),
+                    ],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let task = repo.find(&outcome.task.id).unwrap();
+        assert!(outcome.renamed_from.is_some());
+        assert_eq!(task.slug, "second-renamed");
+        assert_eq!(task.priority.as_deref(), Some("High"));
+        assert_eq!(
+            task.tags,
+            Some(vec!["cli".to_string(), "parser".to_string()])
+        );
+        assert_eq!(task.body, "Notes\n\nMore notes");
+        assert_eq!(task.depends_on, Some(vec![first.id.clone()]));
+        assert_eq!(task.extra["Assignee"], "ada");
+
+        // The other end of the dependency is written too
+        assert_eq!(outcome.linked.len(), 1);
+        let first = repo.find(&first.id).unwrap();
+        assert_eq!(first.blocks, Some(vec![task.id.clone()]));
+
+        let third = repo.create(NewTask::titled("Third")).unwrap();
+        let outcome = repo
+            .update(
+                first,
+                TaskUpdate {
+                    blocks: vec!["third".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            outcome.task.blocks,
+            Some(vec![task.id.clone(), third.id.clone()])
+        );
+        assert_eq!(
+            repo.find(&third.id).unwrap().depends_on,
+            Some(vec![outcome.task.id.clone()])
+        );
+
+        let outcome = repo
+            .update(
+                task,
+                TaskUpdate {
+                    set: vec![("Assignee".to_string(), serde_yaml::Value::Null)],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(outcome.renamed_from.is_none());
+        assert!(!outcome.task.extra.contains_key("Assignee"));
+    }
+
+    #[test]
+    fn test_update_rejects_bad_input() {
+        let (_temp_dir, repo) = repository();
+        let task = repo.create(NewTask::titled("Parser")).unwrap();
+
+        let bad = [
+            TaskUpdate {
+                priority: Some("Urgent".to_string()),
+                ..Default::default()
+            },
+            TaskUpdate {
+                title: Some(" ".to_string()),
+                ..Default::default()
+            },
+            TaskUpdate {
+                depends_on: vec!["missing".to_string()],
+                ..Default::default()
+            },
+            TaskUpdate {
+                blocks: vec![task.id.clone()],
+                ..Default::default()
+            },
+            TaskUpdate {
+                set: vec![("Priority".to_string(), "High".into())],
+                ..Default::default()
+            },
+        ];
+        for changes in bad {
+            assert!(repo.update(task.clone(), changes).is_err());
+        }
+
+        // Nothing was written
+        let unchanged = repo.find(&task.id).unwrap();
+        assert_eq!(unchanged.priority.as_deref(), Some("Medium"));
+        assert!(unchanged.blocks.is_none());
+    }
+
+    #[test]
+    fn test_search() {
+        let (_temp_dir, repo) = repository();
+        repo.create(NewTask {
+            body: "Handle CRLF line endings".to_string(),
+            ..NewTask::titled("Parser")
+        })
+        .unwrap();
+
+        let matches = repo.search("CRLF").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].slug, "parser");
+    }
+
+    #[test]
+    fn test_start_and_save() {
+        let (temp_dir, repo) = repository();
+        let git = git2::Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = git
+            .find_tree(git.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        git.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+
+        let task = repo.create(NewTask::titled("Parser")).unwrap();
+        let outcome = repo.start(task, false).unwrap();
+
+        assert!(outcome.moved && outcome.branch_created);
+        assert_eq!(outcome.task.status, "in-progress");
+        assert_eq!(outcome.branch, format!("{}-parser", outcome.task.id));
+        assert_eq!(
+            git.head().unwrap().shorthand(),
+            Some(outcome.branch.as_str())
+        );
+
+        let saved = repo.save(None).unwrap();
+        assert!(saved.committed);
+        assert!(saved
+            .files
+            .iter()
+            .all(|f| f.path.starts_with(".repo-tasks/")));
+        assert!(!repo.save(None).unwrap().committed);
+    }
+
+    #[test]
+    fn test_start_uses_configured_status() {
+        let (temp_dir, repo) = repository();
+        let git = git2::Repository::init(temp_dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = git
+            .find_tree(git.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        git.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+            .unwrap();
+
+        let mut config = repo.config().unwrap();
+        config.rename_status("in-progress", "doing").unwrap();
+        repo.store()
+            .rename_status_dir("in-progress", "doing")
+            .unwrap();
+        repo.write_config(&config).unwrap();
+
+        let task = repo.create(NewTask::titled("Parser")).unwrap();
+        let outcome = repo.start(task, false).unwrap();
+
+        assert_eq!(outcome.task.status, "doing");
+        assert!(repo
+            .store()
+            .status_dir("doing")
+            .join(outcome.task.file_name())
+            .exists());
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::utils::errors::{self, ErrorKind};
+
+/// Directory holding the tasks and config, at the repository root
+pub const TASKS_DIR: &str = ".repo-tasks";
+
+/// Find the repository root for a command run in `start`, the way git does
+///
+/// That's the nearest directory at or above `start` holding
+/// `.repo-tasks/config.json`. Failing that, it's the top of the git worktree
+/// `start` is in (where `tasks init` puts `.repo-tasks`), or `start` itself.
+pub fn discover(start: &Path) -> PathBuf {
+    if let Some(root) = start
+        .ancestors()
+        .find(|dir| dir.join(TASKS_DIR).join("config.json").is_file())
+    {
+        return root.to_path_buf();
+    }
+
+    git2::Repository::discover(start)
+        .ok()
+        .and_then(|repo| repo.workdir().map(Path::to_path_buf))
+        .unwrap_or_else(|| start.to_path_buf())
+}
+
+/// The root named by `--repo` or `REPO_TASKS_DIR`
+///
+/// Either the directory holding `.repo-tasks` or `.repo-tasks` itself.
+pub fn from_override(path: &Path) -> Result<PathBuf> {
+    if !path.is_dir() {
+        return Err(errors::error(
+            ErrorKind::InvalidInput,
+            format!("Repository directory not found: {}", path.display()),
+        ));
+    }
+
+    match path.parent() {
+        // A bare relative `.repo-tasks` has an empty parent
+        Some(parent) if path.file_name().is_some_and(|name| name == TASKS_DIR) => {
+            if parent.as_os_str().is_empty() {
+                Ok(PathBuf::from("."))
+            } else {
+                Ok(parent.to_path_buf())
+            }
+        }
+        _ => Ok(path.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn init_tasks(dir: &Path) {
+        fs::create_dir_all(dir.join(TASKS_DIR)).unwrap();
+        fs::write(dir.join(TASKS_DIR).join("config.json"), "{}").unwrap();
+    }
+
+    #[test]
+    fn test_discover_nearest_tasks_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("app");
+        let deep = nested.join("src/models");
+        fs::create_dir_all(&deep).unwrap();
+
+        init_tasks(temp_dir.path());
+        assert_eq!(discover(&deep), temp_dir.path());
+
+        init_tasks(&nested);
+        assert_eq!(discover(&deep), nested);
+    }
+
+    #[test]
+    fn test_discover_git_worktree() {
+        let temp_dir = TempDir::new().unwrap();
+        let deep = temp_dir.path().join("src/models");
+        fs::create_dir_all(&deep).unwrap();
+
+        // A directory of its own, outside any repository
+        assert_eq!(discover(&deep), deep);
+
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        assert_eq!(
+            discover(&deep).canonicalize().unwrap(),
+            repo.workdir().unwrap().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_override() {
+        let temp_dir = TempDir::new().unwrap();
+        init_tasks(temp_dir.path());
+
+        assert_eq!(from_override(temp_dir.path()).unwrap(), temp_dir.path());
+        assert_eq!(
+            from_override(&temp_dir.path().join(TASKS_DIR)).unwrap(),
+            temp_dir.path()
+        );
+        assert!(from_override(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_from_override_relative() {
+        // Tests run from the crate root, which tracks its own tasks
+        assert_eq!(from_override(Path::new(TASKS_DIR)).unwrap(), Path::new("."));
+        assert_eq!(
+            from_override(&Path::new("src").join("..").join(TASKS_DIR)).unwrap(),
+            Path::new("src").join("..")
+        );
+        assert_eq!(from_override(Path::new("src")).unwrap(), Path::new("src"));
+    }
+}
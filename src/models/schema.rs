@@ -0,0 +1,177 @@
+use anyhow::Result;
+use jsonschema::Validator;
+use schemars::schema_for;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+
+use super::{Config, Task};
+use crate::utils::errors::{self, ErrorKind};
+
+/// Validator for config.json, without any project-specific values
+pub static CONFIG_VALIDATOR: LazyLock<Validator> = LazyLock::new(|| compile(&config_schema(None)));
+
+/// Validator for task frontmatter, without any project-specific values
+///
+/// Priorities aren't enforced when loading, so tasks stay readable after a
+/// priority is removed from the config.
+pub static TASK_VALIDATOR: LazyLock<Validator> = LazyLock::new(|| compile(&task_schema(None)));
+
+/// JSON Schema for `.repo-tasks/config.json`
+///
+/// Given a config, `started_status`, `closed_statuses` and the targets of
+/// `commit_keywords` are limited to its statuses.
+pub fn config_schema(config: Option<&Config>) -> Value {
+    let mut schema = schema_for!(Config).to_value();
+
+    if let Some(config) = config {
+        schema["properties"]["started_status"]["enum"] = json!(config.statuses);
+        schema["properties"]["closed_statuses"]["items"]["enum"] = json!(config.statuses);
+        schema["properties"]["commit_keywords"]["additionalProperties"]["enum"] =
+            json!(config.statuses);
+    }
+
+    schema
+}
+
+/// JSON Schema for task frontmatter
+///
+/// Given a config, `Priority` is limited to its priorities, and its statuses
+/// are listed under `$defs/Status` (a task's status is the directory it lives
+/// in, not a frontmatter field).
+pub fn task_schema(config: Option<&Config>) -> Value {
+    let mut schema = schema_for!(Task).to_value();
+
+    if let Some(config) = config {
+        schema["properties"]["Priority"]["enum"] = json!(config.priorities);
+        schema["$defs"]["Status"] = json!({
+            "description": "Task status: the directory under .repo-tasks/tasks holding the task file",
+            "type": "string",
+            "enum": config.statuses,
+        });
+    }
+
+    schema
+}
+
+/// Validate `value`, reporting every violation with the field it concerns
+pub fn check(validator: &Validator, value: &Value, what: &str) -> Result<()> {
+    let problems: Vec<String> = validator
+        .iter_errors(value)
+        .map(|e| match field_name(e.instance_path().as_str()) {
+            Some(field) => format!("  - {}: {}", field, e),
+            None => format!("  - {}", e),
+        })
+        .collect();
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    Err(errors::error(
+        ErrorKind::InvalidInput,
+        format!("Invalid {}:\n{}", what, problems.join("\n")),
+    ))
+}
+
+fn compile(schema: &Value) -> Validator {
+    jsonschema::validator_for(schema).expect("generated schemas are valid")
+}
+
+/// Turn a JSON pointer like `/statuses/1` into `statuses[1]`
+fn field_name(pointer: &str) -> Option<String> {
+    if pointer.is_empty() {
+        return None;
+    }
+
+    let mut name = String::new();
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if segment.chars().all(|c| c.is_ascii_digit()) && !name.is_empty() {
+            name.push_str(&format!("[{}]", segment));
+        } else {
+            if !name.is_empty() {
+                name.push('.');
+            }
+            name.push_str(&segment);
+        }
+    }
+
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_includes_configured_statuses() {
+        let config = Config::default(Some("test".to_string()));
+        let schema = config_schema(Some(&config));
+
+        assert_eq!(
+            schema["properties"]["closed_statuses"]["items"]["enum"],
+            json!(["todo", "in-progress", "testing", "done"])
+        );
+        assert_eq!(
+            schema["properties"]["commit_keywords"]["additionalProperties"]["enum"][3],
+            "done"
+        );
+        assert_eq!(
+            schema["required"],
+            json!(["project_name", "statuses", "priorities"])
+        );
+    }
+
+    #[test]
+    fn test_task_schema_includes_configured_priorities() {
+        let config = Config::default(Some("test".to_string()));
+        let schema = task_schema(Some(&config));
+
+        assert_eq!(
+            schema["properties"]["Priority"]["enum"],
+            json!(["Low", "Medium", "High", "Critical"])
+        );
+        assert_eq!(schema["$defs"]["Status"]["enum"][1], "in-progress");
+
+        // Only frontmatter fields are part of the schema
+        assert!(schema["properties"].get("body").is_none());
+    }
+
+    #[test]
+    fn test_check_reports_field_paths() {
+        let value = json!({
+            "project_name": "test",
+            "statuses": ["todo", "In Review"],
+            "priorities": [],
+            "auto_commit": "yes",
+        });
+
+        let message = check(&CONFIG_VALIDATOR, &value, "config.json")
+            .unwrap_err()
+            .to_string();
+
+        assert!(message.starts_with("Invalid config.json:"));
+        assert!(message.contains("  - statuses[1]: "));
+        assert!(message.contains("  - priorities: "));
+        assert!(message.contains("  - auto_commit: "));
+    }
+
+    #[test]
+    fn test_check_reports_missing_fields() {
+        let value = json!({ "Title": "No ID" });
+
+        let message = check(&TASK_VALIDATOR, &value, "frontmatter")
+            .unwrap_err()
+            .to_string();
+
+        assert!(message.contains("\"ID\" is a required property"));
+    }
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name(""), None);
+        assert_eq!(field_name("/statuses/1").unwrap(), "statuses[1]");
+        assert_eq!(field_name("/Tags/0").unwrap(), "Tags[0]");
+        assert_eq!(field_name("/a~1b").unwrap(), "a/b");
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use grep_regex::RegexMatcher;
+use grep_searcher::sinks::UTF8;
+use grep_searcher::Searcher;
+use serde::Serialize;
+
+use super::{Task, TaskStore};
+
+/// A single matching line, as printed by `tasks search --json`
+#[derive(Debug, Serialize)]
+pub struct SearchMatch {
+    pub id: Option<String>,
+    pub slug: String,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub path: String,
+    pub line: u64,
+    pub text: String,
+}
+
+/// Find every line in every task file matching `query` (a regex)
+///
+/// Files that can't be read are skipped; `tasks doctor` reports them.
+pub fn search_tasks(store: &TaskStore, query: &str) -> Result<Vec<SearchMatch>> {
+    // Create regex matcher
+    let matcher = RegexMatcher::new_line_matcher(query)?;
+    let mut searcher = Searcher::new();
+
+    let mut matches = Vec::new();
+
+    // Search each file
+    for path in store.task_files() {
+        let mut lines = Vec::new();
+
+        let result = searcher.search_path(
+            &matcher,
+            &path,
+            UTF8(|lnum, line| {
+                lines.push((lnum, line.trim().to_string()));
+                Ok(true)
+            }),
+        );
+
+        if result.is_err() || lines.is_empty() {
+            continue;
+        }
+
+        let task = Task::from_file(&path).ok();
+
+        // Fall back to the filename when the task itself doesn't parse
+        let slug = match &task {
+            Some(task) => task.slug.clone(),
+            None => {
+                let filename = path
+                    .file_name()
+                    .and_then(|f| f.to_str())
+                    .unwrap_or("unknown");
+
+                filename
+                    .strip_suffix(".md")
+                    .and_then(|s| s.split_once('-'))
+                    .map_or(filename, |(_, slug)| slug)
+                    .to_string()
+            }
+        };
+
+        for (line, text) in lines {
+            matches.push(SearchMatch {
+                id: task.as_ref().map(|t| t.id.clone()),
+                slug: slug.clone(),
+                title: task.as_ref().map(|t| t.title.clone()),
+                status: task.as_ref().map(|t| t.status.clone()),
+                path: path.display().to_string(),
+                line,
+                text,
+            });
+        }
+    }
+
+    Ok(matches)
+}
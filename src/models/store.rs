@@ -0,0 +1,598 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+use super::{Config, DependencyGraph, Task};
+use crate::utils::errors;
+
+/// File-backed collection of tasks stored under `.repo-tasks/tasks/<status>/`
+#[derive(Debug, Clone)]
+pub struct TaskStore {
+    root: PathBuf,
+}
+
+impl Default for TaskStore {
+    fn default() -> Self {
+        Self::new(PathBuf::from(".repo-tasks").join("tasks"))
+    }
+}
+
+impl TaskStore {
+    /// Create a store rooted at the given tasks directory
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        TaskStore { root: root.into() }
+    }
+
+    /// Get the root tasks directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Get the directory holding tasks in the given status
+    pub fn status_dir(&self, status: &str) -> PathBuf {
+        self.root.join(status)
+    }
+
+    /// Get the path a task should be stored at, based on its status, ID and slug
+    pub fn task_path(&self, task: &Task) -> PathBuf {
+        self.status_dir(&task.status).join(task.file_name())
+    }
+
+    /// Collect the paths of all task files across every status directory
+    pub fn task_files(&self) -> Vec<PathBuf> {
+        if !self.root.exists() {
+            return Vec::new();
+        }
+
+        WalkDir::new(&self.root)
+            .min_depth(2)
+            .max_depth(2)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| is_task_file(e.path()))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    }
+
+    /// List the status directories present on disk, whether configured or not
+    pub fn status_dirs(&self) -> Vec<String> {
+        if !self.root.exists() {
+            return Vec::new();
+        }
+
+        WalkDir::new(&self.root)
+            .min_depth(1)
+            .max_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_dir())
+            .filter_map(|e| e.file_name().to_str().map(String::from))
+            .filter(|name| !name.starts_with('.'))
+            .collect()
+    }
+
+    /// Collect the paths of the task files in a single status directory,
+    /// including ones that don't parse
+    pub fn status_files(&self, status: &str) -> Vec<PathBuf> {
+        let dir = self.status_dir(status);
+        if !dir.exists() {
+            return Vec::new();
+        }
+
+        WalkDir::new(&dir)
+            .min_depth(1)
+            .max_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| is_task_file(e.path()))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    }
+
+    /// Load every task across all statuses, skipping files that fail to parse
+    pub fn all(&self) -> Result<Vec<Task>> {
+        Ok(self
+            .task_files()
+            .iter()
+            .filter_map(|path| Task::from_file(path).ok())
+            .collect())
+    }
+
+    /// Load all tasks in a single status, skipping files that fail to parse
+    pub fn list(&self, status: &str) -> Result<Vec<Task>> {
+        Ok(self
+            .status_files(status)
+            .iter()
+            .filter_map(|path| Task::from_file(path).ok())
+            .collect())
+    }
+
+    /// Build the dependency graph across tasks in every status
+    pub fn dependency_graph(&self) -> Result<DependencyGraph> {
+        Ok(DependencyGraph::build(self.all()?))
+    }
+
+    /// Find every task matching a reference at the best available rank
+    ///
+    /// References are ranked as exact ID, then exact slug, then a prefix of
+    /// the ID or slug. Only the candidates from the highest rank that matched
+    /// anything are returned, so an exact match is never reported as
+    /// ambiguous with a longer task that merely shares its prefix.
+    pub fn find_matches(&self, reference: &str) -> Result<Vec<Task>> {
+        let reference = reference.trim();
+        if reference.is_empty() {
+            bail!("Task reference cannot be empty");
+        }
+
+        let tasks = self.all()?;
+
+        let rank = |t: &Task| {
+            if t.id == reference {
+                Some(0)
+            } else if t.slug == reference {
+                Some(1)
+            } else if t.id.starts_with(reference) || t.slug.starts_with(reference) {
+                Some(2)
+            } else {
+                None
+            }
+        };
+
+        let best = match tasks.iter().filter_map(rank).min() {
+            Some(best) => best,
+            None => return Ok(Vec::new()),
+        };
+
+        Ok(tasks
+            .into_iter()
+            .filter(|t| rank(t) == Some(best))
+            .collect())
+    }
+
+    /// Find a single task by exact ID, exact slug or unique prefix
+    ///
+    /// Fails if nothing matches, or if several tasks match at the same rank.
+    pub fn find(&self, reference: &str) -> Result<Task> {
+        let mut matches = self.find_matches(reference)?;
+
+        match matches.len() {
+            0 => Err(errors::task_not_found(reference.trim())),
+            1 => Ok(matches.remove(0)),
+            _ => Err(errors::ambiguous_task(reference.trim(), &matches)),
+        }
+    }
+
+    /// Generate an ID for a new task that no existing task uses
+    pub fn next_id(&self, config: &Config) -> Result<String> {
+        let existing: HashSet<String> = self.all()?.into_iter().map(|t| t.id).collect();
+        Ok(config
+            .id_scheme
+            .generate(&config.task_id_prefix(), &existing))
+    }
+
+    /// Assign a new task a unique ID and write it to disk
+    ///
+    /// ID generation and the write happen under a lock file, so concurrent
+    /// `tasks new` runs (scripts, the MCP server) can't hand out the same ID.
+    pub fn create(&self, task: &mut Task, config: &Config) -> Result<PathBuf> {
+        let _lock = CreateLock::acquire(&self.root)?;
+        task.id = self.next_id(config)?;
+        self.save(task)
+    }
+
+    /// Write a task to its canonical location
+    ///
+    /// If the task was loaded from a different path (e.g. its slug or status
+    /// changed), the old file is removed after the new one is written.
+    pub fn save(&self, task: &mut Task) -> Result<PathBuf> {
+        let new_path = self.task_path(task);
+        task.to_file(&new_path)?;
+
+        if let Some(old_path) = task.path.take() {
+            if old_path != new_path && old_path.exists() {
+                fs::remove_file(&old_path).context(format!(
+                    "Failed to remove task file: {}",
+                    old_path.display()
+                ))?;
+            }
+        }
+
+        task.path = Some(new_path.clone());
+        Ok(new_path)
+    }
+
+    /// Move a task into a different status directory
+    pub fn move_task(&self, task: &mut Task, new_status: &str) -> Result<PathBuf> {
+        task.status = new_status.to_string();
+        self.save(task)
+    }
+
+    /// Create the directory for a status if it doesn't exist yet
+    pub fn create_status_dir(&self, status: &str) -> Result<()> {
+        let dir = self.status_dir(status);
+        fs::create_dir_all(&dir).context(format!("Failed to create directory: {}", dir.display()))
+    }
+
+    /// Remove an empty status directory
+    ///
+    /// Returns false (and leaves it in place) if anything is still inside.
+    pub fn remove_status_dir(&self, status: &str) -> Result<bool> {
+        let dir = self.status_dir(status);
+        if !dir.exists() {
+            return Ok(true);
+        }
+
+        Ok(fs::remove_dir(&dir).is_ok())
+    }
+
+    /// Rename a status directory, carrying its task files along
+    ///
+    /// Refuses if the new directory already holds task files.
+    pub fn rename_status_dir(&self, old: &str, new: &str) -> Result<()> {
+        let old_dir = self.status_dir(old);
+        let new_dir = self.status_dir(new);
+
+        if !old_dir.exists() {
+            return self.create_status_dir(new);
+        }
+
+        if new_dir.exists() {
+            if !self.status_files(new).is_empty() {
+                bail!(
+                    "Cannot rename '{}' to '{}': {} already contains tasks",
+                    old,
+                    new,
+                    new_dir.display()
+                );
+            }
+            fs::remove_dir(&new_dir)
+                .context(format!("Failed to remove directory: {}", new_dir.display()))?;
+        }
+
+        fs::rename(&old_dir, &new_dir).context(format!(
+            "Failed to rename {} to {}",
+            old_dir.display(),
+            new_dir.display()
+        ))
+    }
+
+    /// Move every task file from one status directory to another
+    ///
+    /// Files are moved as-is (status comes from the directory), so even files
+    /// that don't parse are carried over. Returns the number of files moved.
+    pub fn migrate_status(&self, from: &str, to: &str) -> Result<usize> {
+        let files = self.status_files(from);
+        self.create_status_dir(to)?;
+
+        let target_dir = self.status_dir(to);
+        for path in &files {
+            let Some(file_name) = path.file_name() else {
+                continue;
+            };
+            let target = target_dir.join(file_name);
+            if target.exists() {
+                bail!(
+                    "Cannot move {}: {} already exists",
+                    path.display(),
+                    target.display()
+                );
+            }
+            fs::rename(path, &target).context(format!(
+                "Failed to move {} to {}",
+                path.display(),
+                target.display()
+            ))?;
+        }
+
+        Ok(files.len())
+    }
+}
+
+/// Lock file held while a new task ID is being allocated
+struct CreateLock {
+    path: PathBuf,
+}
+
+impl CreateLock {
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    const STALE_AFTER: Duration = Duration::from_secs(30);
+
+    fn acquire(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root)
+            .context(format!("Failed to create directory: {}", root.display()))?;
+
+        let path = root.join(".create.lock");
+        let deadline = Instant::now() + Self::TIMEOUT;
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(CreateLock { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    // A crashed process may have left the lock behind
+                    if Self::is_stale(&path) {
+                        fs::remove_file(&path).ok();
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        bail!(
+                            "Timed out waiting for lock: {}\n\
+                            \n\
+                            If no other tasks command is running, delete the file and retry.",
+                            path.display()
+                        );
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => {
+                    return Err(e).context(format!("Failed to create lock: {}", path.display()))
+                }
+            }
+        }
+    }
+
+    fn is_stale(path: &Path) -> bool {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > Self::STALE_AFTER)
+    }
+}
+
+impl Drop for CreateLock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Check whether a path looks like a task file
+fn is_task_file(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()) == Some("md")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store_with_tasks(tasks: &[(&str, &str, &str)]) -> (TempDir, TaskStore) {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TaskStore::new(temp_dir.path().join("tasks"));
+
+        for (id, title, status) in tasks {
+            let mut task = Task::new(title.to_string(), "Medium".to_string());
+            task.id = id.to_string();
+            task.status = status.to_string();
+            store.save(&mut task).unwrap();
+        }
+
+        (temp_dir, store)
+    }
+
+    #[test]
+    fn test_list_by_status() {
+        let (_dir, store) = store_with_tasks(&[
+            ("20260108000001", "First Task", "todo"),
+            ("20260108000002", "Second Task", "done"),
+        ]);
+
+        let todo = store.list("todo").unwrap();
+        assert_eq!(todo.len(), 1);
+        assert_eq!(todo[0].id, "20260108000001");
+        assert_eq!(todo[0].status, "todo");
+
+        assert!(store.list("testing").unwrap().is_empty());
+        assert_eq!(store.all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_find_by_id_prefix_and_slug() {
+        let (_dir, store) = store_with_tasks(&[
+            ("20260108000001", "First Task", "todo"),
+            ("20260109000002", "Second Task", "in-progress"),
+        ]);
+
+        assert_eq!(store.find("20260108000001").unwrap().slug, "first-task");
+        assert_eq!(store.find("20260109").unwrap().slug, "second-task");
+        assert_eq!(store.find("second-task").unwrap().id, "20260109000002");
+        assert!(store.find("missing").is_err());
+    }
+
+    #[test]
+    fn test_find_ambiguous_prefix() {
+        let (_dir, store) = store_with_tasks(&[
+            ("20260108000001", "Test Parser", "todo"),
+            ("20260108000002", "Test Hooks", "todo"),
+        ]);
+
+        assert_eq!(store.find_matches("2026").unwrap().len(), 2);
+        assert_eq!(store.find_matches("test").unwrap().len(), 2);
+
+        let err = store.find("test").unwrap_err().to_string();
+        assert!(err.contains("Ambiguous"));
+        assert!(err.contains("20260108000001"));
+        assert!(err.contains("20260108000002"));
+
+        // A longer prefix narrows it down to a single task
+        assert_eq!(store.find("test-h").unwrap().id, "20260108000002");
+    }
+
+    #[test]
+    fn test_find_prefers_exact_matches() {
+        let (_dir, store) = store_with_tasks(&[
+            ("20260108000001", "Test", "todo"),
+            ("20260108000002", "Test Hooks", "todo"),
+        ]);
+
+        // Exact slug wins over a slug prefix match
+        assert_eq!(store.find("test").unwrap().id, "20260108000001");
+    }
+
+    #[test]
+    fn test_find_duplicate_ids_are_ambiguous() {
+        let (_dir, store) = store_with_tasks(&[
+            ("20260110142106", "Create Parser", "done"),
+            ("20260110142106", "Implement Hook", "done"),
+        ]);
+
+        assert!(store.find("20260110142106").is_err());
+        assert_eq!(store.find("create-parser").unwrap().slug, "create-parser");
+    }
+
+    #[test]
+    fn test_create_assigns_unique_ids() {
+        let (_dir, store) = store_with_tasks(&[]);
+        let mut config = Config::default(Some("repo-tasks".to_string()));
+        config.id_scheme = crate::models::IdScheme::Sequential;
+
+        let mut ids = HashSet::new();
+        for i in 0..5 {
+            let mut task = Task::new(format!("Task {}", i), "Medium".to_string());
+            store.create(&mut task, &config).unwrap();
+            assert!(ids.insert(task.id.clone()));
+        }
+
+        assert!(ids.contains("RT-5"));
+        assert_eq!(store.find("RT-3").unwrap().slug, "task-2");
+        assert!(!store.root().join(".create.lock").exists());
+    }
+
+    #[test]
+    fn test_create_avoids_timestamp_collisions() {
+        let (_dir, store) = store_with_tasks(&[]);
+        let config = Config::default(Some("test".to_string()));
+
+        // Bulk creation within the same second must not collide
+        for i in 0..5 {
+            let mut task = Task::new(format!("Task {}", i), "Medium".to_string());
+            store.create(&mut task, &config).unwrap();
+        }
+
+        let ids: HashSet<String> = store.all().unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[test]
+    fn test_dependency_graph() {
+        let (_dir, store) = store_with_tasks(&[
+            ("20260108000001", "First Task", "todo"),
+            ("20260108000002", "Second Task", "todo"),
+        ]);
+
+        let mut task = store.find("second-task").unwrap();
+        task.depends_on = Some(vec!["20260108000001".to_string()]);
+        store.save(&mut task).unwrap();
+
+        // The edge is known from either end
+        let graph = store.dependency_graph().unwrap();
+        assert_eq!(
+            graph.dependents_of("20260108000001"),
+            vec!["20260108000002"]
+        );
+    }
+
+    #[test]
+    fn test_move_task_removes_old_file() {
+        let (_dir, store) = store_with_tasks(&[("20260108000001", "First Task", "todo")]);
+
+        let mut task = store.find("first-task").unwrap();
+        let old_path = task.path.clone().unwrap();
+        let new_path = store.move_task(&mut task, "done").unwrap();
+
+        assert!(!old_path.exists());
+        assert!(new_path.exists());
+        assert_eq!(store.find("first-task").unwrap().status, "done");
+    }
+
+    #[test]
+    fn test_move_and_update_keep_unrelated_bytes() {
+        let (_dir, store) = store_with_tasks(&[]);
+        let content = "---\nID: '20260108000001'\nTitle: First Task   # working title\nAssignee: ada\nPriority: Medium\nTags: [cli]\n\n# Set by the release script\nDue: 2026-02-01\n---\n\nBody\n";
+        store.create_status_dir("todo").unwrap();
+        fs::write(
+            store
+                .status_dir("todo")
+                .join("20260108000001-first-task.md"),
+            content,
+        )
+        .unwrap();
+
+        let mut task = store.find("first-task").unwrap();
+        assert_eq!(
+            task.extra.get("Assignee").and_then(|v| v.as_str()),
+            Some("ada")
+        );
+
+        let path = store.move_task(&mut task, "done").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+
+        task.priority = Some("High".to_string());
+        task.body = "Body, revised".to_string();
+        store.save(&mut task).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            content
+                .replace("Priority: Medium", "Priority: High")
+                .replace("\nBody\n", "\nBody, revised\n")
+        );
+    }
+
+    #[test]
+    fn test_save_renames_when_slug_changes() {
+        let (_dir, store) = store_with_tasks(&[("20260108000001", "First Task", "todo")]);
+
+        let mut task = store.find("first-task").unwrap();
+        task.title = "Renamed Task".to_string();
+        task.slug = Task::generate_slug(&task.title);
+        store.save(&mut task).unwrap();
+
+        assert_eq!(store.all().unwrap().len(), 1);
+        assert_eq!(store.find("20260108000001").unwrap().slug, "renamed-task");
+    }
+
+    #[test]
+    fn test_rename_status_dir_moves_tasks() {
+        let (_dir, store) = store_with_tasks(&[("20260108000001", "First Task", "testing")]);
+
+        store.rename_status_dir("testing", "review").unwrap();
+
+        assert!(!store.status_dir("testing").exists());
+        let tasks = store.list("review").unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].status, "review");
+    }
+
+    #[test]
+    fn test_rename_status_dir_refuses_to_merge() {
+        let (_dir, store) = store_with_tasks(&[
+            ("20260108000001", "First Task", "testing"),
+            ("20260108000002", "Second Task", "done"),
+        ]);
+
+        assert!(store.rename_status_dir("testing", "done").is_err());
+        assert_eq!(store.list("testing").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_migrate_status_moves_unparseable_files_too() {
+        let (_dir, store) = store_with_tasks(&[("20260108000001", "First Task", "testing")]);
+        fs::write(
+            store.status_dir("testing").join("broken.md"),
+            "no frontmatter",
+        )
+        .unwrap();
+
+        assert_eq!(store.migrate_status("testing", "done").unwrap(), 2);
+        assert!(store.status_files("testing").is_empty());
+        assert_eq!(store.status_files("done").len(), 2);
+        assert!(store.remove_status_dir("testing").unwrap());
+    }
+}
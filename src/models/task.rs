@@ -1,30 +1,52 @@
 use anyhow::{Context, Result};
 use chrono::Local;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_yaml::Mapping;
 use slug::slugify;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::frontmatter::Source;
+use super::schema;
+
+/// A task: YAML frontmatter followed by a markdown body
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(title = "repo-tasks task frontmatter")]
 pub struct Task {
+    /// Task ID, also the start of the file name
     #[serde(rename = "ID")]
+    #[schemars(length(min = 1))]
     pub id: String,
 
     #[serde(rename = "Title")]
+    #[schemars(length(min = 1))]
     pub title: String,
 
     #[serde(rename = "Priority", skip_serializing_if = "Option::is_none")]
     pub priority: Option<String>,
 
+    /// IDs of tasks that can't start until this one is done
     #[serde(rename = "Blocks", skip_serializing_if = "Option::is_none")]
     pub blocks: Option<Vec<String>>,
 
+    /// IDs of tasks that must be done before this one
     #[serde(rename = "DependsOn", skip_serializing_if = "Option::is_none")]
     pub depends_on: Option<Vec<String>>,
 
     #[serde(rename = "Tags", skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
 
+    /// Commits that reference this task, recorded by `tasks history scan`
+    #[serde(rename = "Commits", skip_serializing_if = "Option::is_none")]
+    pub commits: Option<Vec<String>>,
+
+    /// Frontmatter fields repo-tasks doesn't use, like `Assignee`, in file
+    /// order; they're written back as they were
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: Mapping,
+
     #[serde(skip)]
     pub body: String,
 
@@ -33,6 +55,14 @@ pub struct Task {
 
     #[serde(skip)]
     pub status: String,
+
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+
+    /// The file the task was read from, so saving it changes only what changed
+    #[serde(skip)]
+    #[schemars(skip)]
+    source: Option<Source>,
 }
 
 impl Task {
@@ -48,13 +78,20 @@ impl Task {
             blocks: None,
             depends_on: None,
             tags: None,
+            commits: None,
+            extra: Mapping::new(),
             body: String::new(),
             slug,
             status: "todo".to_string(),
+            path: None,
+            source: None,
         }
     }
 
-    /// Generate a unique ID based on current timestamp
+    /// Generate an ID based on the current local timestamp
+    ///
+    /// This is not checked against existing tasks; use `TaskStore::create` to
+    /// get an ID that is unique within a repository.
     pub fn generate_id() -> String {
         Local::now().format("%Y%m%d%H%M%S").to_string()
     }
@@ -64,31 +101,31 @@ impl Task {
         slugify(title)
     }
 
-    /// Get the file path for this task in the given status directory
-    pub fn file_path(&self, status: &str) -> PathBuf {
-        PathBuf::from(".repo-tasks")
-            .join("tasks")
-            .join(status)
-            .join(format!("{}-{}.md", self.id, self.slug))
+    /// Get the file name for this task (`{id}-{slug}.md`)
+    pub fn file_name(&self) -> String {
+        format!("{}-{}.md", self.id, self.slug)
     }
 
     /// Parse frontmatter and body from markdown content
+    ///
+    /// The frontmatter is YAML between `---` lines or TOML between `+++`
+    /// lines, at the very top of the file.
     pub fn parse_frontmatter(content: &str) -> Result<(Self, String)> {
-        // Split content by frontmatter delimiters
-        let parts: Vec<&str> = content.splitn(3, "---").collect();
+        let source = Source::parse(content)?;
 
-        if parts.len() < 3 {
-            anyhow::bail!("Invalid task format: missing frontmatter delimiters");
-        }
+        // Check against the schema first for field-level errors
+        let json =
+            serde_json::to_value(source.values()).context("Failed to parse task frontmatter")?;
+        schema::check(&schema::TASK_VALIDATOR, &json, "frontmatter")?;
 
-        // Parse YAML frontmatter (skip first empty part)
-        let frontmatter = parts[1].trim();
         let mut task: Task =
-            serde_yaml::from_str(frontmatter).context("Failed to parse task frontmatter")?;
+            serde_yaml::from_value(serde_yaml::Value::Mapping(source.values().clone()))
+                .context("Failed to parse task frontmatter")?;
 
         // Extract body
-        let body = parts[2].trim().to_string();
+        let body = source.body().to_string();
         task.body = body.clone();
+        task.source = Some(source);
 
         Ok((task, body))
     }
@@ -107,16 +144,22 @@ impl Task {
             }
         }
 
-        // Extract slug from filename
+        // Extract slug from filename (IDs like `RT-42` contain dashes themselves,
+        // so strip the known ID before falling back to the first dash)
         if let Some(filename) = path.file_name() {
             let filename_str = filename.to_string_lossy();
             if let Some(slug_part) = filename_str.strip_suffix(".md") {
-                if let Some((_, slug)) = slug_part.split_once('-') {
+                let id_prefix = format!("{}-", task.id);
+                if let Some(slug) = slug_part.strip_prefix(&id_prefix) {
+                    task.slug = slug.to_string();
+                } else if let Some((_, slug)) = slug_part.split_once('-') {
                     task.slug = slug.to_string();
                 }
             }
         }
 
+        task.path = Some(path.to_path_buf());
+
         Ok(task)
     }
 
@@ -128,12 +171,23 @@ impl Task {
                 .context(format!("Failed to create directory: {}", parent.display()))?;
         }
 
-        // Serialize frontmatter
-        let frontmatter =
-            serde_yaml::to_string(&self).context("Failed to serialize task frontmatter")?;
-
-        // Construct full content
-        let content = format!("---\n{}---\n\n{}", frontmatter, self.body);
+        // Construct full content, leaving what didn't change as it was read
+        let content = match &self.source {
+            Some(source) => {
+                let values = match serde_yaml::to_value(self)
+                    .context("Failed to serialize task frontmatter")?
+                {
+                    serde_yaml::Value::Mapping(values) => values,
+                    _ => Mapping::new(),
+                };
+                source.render(&values, &self.body)?
+            }
+            None => {
+                let frontmatter =
+                    serde_yaml::to_string(&self).context("Failed to serialize task frontmatter")?;
+                format!("---\n{}---\n\n{}", frontmatter, self.body)
+            }
+        };
 
         // Write to file
         fs::write(path, content)
@@ -196,4 +250,38 @@ This is the task body."#;
         assert_eq!(task.priority, Some("High".to_string()));
         assert_eq!(body, "This is the task body.");
     }
+
+    #[test]
+    fn test_parse_frontmatter_reports_invalid_fields() {
+        let content = "---\nID: 20260108120000\nTitle: Test Task\nTags: cli\n---\n";
+
+        let message = Task::parse_frontmatter(content).unwrap_err().to_string();
+
+        assert!(message.starts_with("Invalid frontmatter:"));
+        assert!(message.contains("  - ID: 20260108120000 is not of type \"string\""));
+        assert!(message.contains("  - Tags: \"cli\" is not of type"));
+    }
+
+    #[test]
+    fn test_toml_frontmatter_stays_toml() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("todo").join("20260108120000-test-task.md");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "+++\nID = \"20260108120000\"\nTitle = \"Test --- Task\"\n+++\n\nBody\n",
+        )
+        .unwrap();
+
+        let mut task = Task::from_file(&path).unwrap();
+        assert_eq!(task.title, "Test --- Task");
+        assert_eq!(task.body, "Body");
+
+        task.priority = Some("High".to_string());
+        task.to_file(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "+++\nID = \"20260108120000\"\nTitle = \"Test --- Task\"\nPriority = \"High\"\n+++\n\nBody\n"
+        );
+    }
 }
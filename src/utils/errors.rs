@@ -1,34 +1,218 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
 use std::path::Path;
 
+/// Broad category of a failure, used to pick exit codes and label JSON errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Anything not covered below
+    General,
+    /// Bad command-line arguments
+    Usage,
+    /// No `.repo-tasks/` directory
+    NotInitialized,
+    /// No task matches the reference
+    NotFound,
+    /// Several tasks match the reference
+    Ambiguous,
+    /// A value was rejected (unknown status, priority, empty title, ...)
+    InvalidInput,
+    /// The task has unfinished dependencies
+    Blocked,
+    /// A git operation failed
+    Git,
+    /// `tasks doctor` found problems in the repository
+    Unhealthy,
+}
+
+impl ErrorKind {
+    /// Process exit code for this kind of failure
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::General => 1,
+            ErrorKind::Usage => 2,
+            ErrorKind::NotInitialized => 3,
+            ErrorKind::NotFound => 4,
+            ErrorKind::Ambiguous => 5,
+            ErrorKind::InvalidInput => 6,
+            ErrorKind::Blocked => 7,
+            ErrorKind::Git => 8,
+            ErrorKind::Unhealthy => 9,
+        }
+    }
+}
+
+/// An error with a known [`ErrorKind`]
+///
+/// Build these through the helpers below; `anyhow` context added on top is
+/// kept, and [`classify`] still finds the kind underneath.
+#[derive(Debug)]
+pub struct CliError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Create an error of the given kind
+pub fn error(kind: ErrorKind, message: impl Into<String>) -> anyhow::Error {
+    anyhow::Error::new(CliError {
+        kind,
+        message: message.into(),
+    })
+}
+
+/// Work out which kind of failure an error represents
+pub fn classify(err: &anyhow::Error) -> ErrorKind {
+    for cause in err.chain() {
+        if let Some(cli_error) = cause.downcast_ref::<CliError>() {
+            return cli_error.kind;
+        }
+        if cause.downcast_ref::<git2::Error>().is_some() {
+            return ErrorKind::Git;
+        }
+    }
+    ErrorKind::General
+}
+
+/// Render an error as the JSON object printed on stderr in `--json` mode
+///
+/// ```json
+/// {"error": {"kind": "not_found", "exit_code": 4, "message": "Task not found: foo ..."}}
+/// ```
+pub fn to_json(err: &anyhow::Error) -> serde_json::Value {
+    let kind = classify(err);
+    let message = console::strip_ansi_codes(&format!("{:#}", err)).into_owned();
+
+    serde_json::json!({
+        "error": {
+            "kind": kind,
+            "exit_code": kind.exit_code(),
+            "message": message,
+        }
+    })
+}
+
+/// The first line of an error and its causes, without the hints below it
+pub fn summary(err: &anyhow::Error) -> String {
+    format!("{:#}", err)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
 /// Check if repo-tasks is initialized, return helpful error if not
-#[allow(dead_code)]
 pub fn ensure_initialized() -> Result<()> {
     if !crate::Config::is_initialized() {
-        bail!(
-            "Not in a repo-tasks repository.\n\
+        return Err(error(
+            ErrorKind::NotInitialized,
+            "Not in a repo-tasks repository (or any of its parent directories).\n\
             \n\
             To get started, run:\n\
             \n\
             \x1b[1m  tasks init\x1b[0m\n\
             \n\
-            This will create a .repo-tasks/ directory in your current location."
-        );
+            This will create a .repo-tasks/ directory at the top of your git repository\n\
+            (or in the current directory outside git). To use a repository elsewhere,\n\
+            pass --repo <PATH> or set REPO_TASKS_DIR.",
+        ));
     }
     Ok(())
 }
 
 /// Provide a helpful error when a task is not found
-#[allow(dead_code)]
 pub fn task_not_found(slug_or_id: &str) -> anyhow::Error {
-    anyhow::anyhow!(
-        "Task not found: {}\n\
-        \n\
-        Try:\n\
-        \x1b[1m  tasks list\x1b[0m           # List all tasks\n\
-        \x1b[1m  tasks search {}\x1b[0m  # Search for tasks",
-        slug_or_id,
-        slug_or_id
+    error(
+        ErrorKind::NotFound,
+        format!(
+            "Task not found: {}\n\
+            \n\
+            Try:\n\
+            \x1b[1m  tasks list\x1b[0m           # List all tasks\n\
+            \x1b[1m  tasks search {}\x1b[0m  # Search for tasks",
+            slug_or_id, slug_or_id
+        ),
+    )
+}
+
+/// Provide a helpful error when a task reference matches several tasks
+pub fn ambiguous_task(reference: &str, candidates: &[crate::Task]) -> anyhow::Error {
+    let list: Vec<String> = candidates
+        .iter()
+        .map(|t| format!("  {}  {} ({})", t.id, t.slug, t.status))
+        .collect();
+
+    error(
+        ErrorKind::Ambiguous,
+        format!(
+            "Ambiguous task reference: '{}' matches {} tasks\n\
+            \n\
+            {}\n\
+            \n\
+            Use the full ID or slug to pick one.",
+            reference,
+            candidates.len(),
+            list.join("\n")
+        ),
+    )
+}
+
+/// Provide a helpful error when a task's dependencies aren't finished yet
+pub fn unmet_dependencies(
+    task: &crate::Task,
+    new_status: &str,
+    unmet: &[&crate::Task],
+) -> anyhow::Error {
+    let list: Vec<String> = unmet
+        .iter()
+        .map(|t| format!("  {}  {} ({})", t.id, t.title, t.status))
+        .collect();
+
+    error(
+        ErrorKind::Blocked,
+        format!(
+            "Cannot move '{}' to '{}': {} unfinished dependenc{}\n\
+            \n\
+            {}\n\
+            \n\
+            Finish those first, or use --force to move it anyway.",
+            task.slug,
+            new_status,
+            unmet.len(),
+            if unmet.len() == 1 { "y" } else { "ies" },
+            list.join("\n")
+        ),
+    )
+}
+
+/// Provide a helpful error when `tasks save` would sweep up unrelated files
+pub fn staged_outside_tasks(files: &[String]) -> anyhow::Error {
+    let list: Vec<String> = files.iter().map(|f| format!("  - {}", f)).collect();
+
+    error(
+        ErrorKind::InvalidInput,
+        format!(
+            "Cannot commit non-task files with 'tasks save'\n\
+            \n\
+            The following staged files are outside .repo-tasks/:\n\
+            {}\n\
+            \n\
+            To fix this:\n\
+            \x20 1. Commit project files separately: \x1b[1mgit commit -m \"Your message\"\x1b[0m\n\
+            \x20 2. Then use 'tasks save' for task files only\n\
+            \n\
+            Or unstage non-task files: \x1b[1mgit restore --staged <file>\x1b[0m",
+            list.join("\n")
+        ),
     )
 }
 
@@ -39,25 +223,60 @@ pub fn file_context(path: &Path, operation: &str) -> String {
 }
 
 /// Provide helpful error for invalid status
-#[allow(dead_code)]
 pub fn invalid_status(status: &str, valid_statuses: &[String]) -> anyhow::Error {
-    anyhow::anyhow!(
-        "Invalid status: '{}'\n\
-        \n\
-        Valid statuses are: {}",
-        status,
-        valid_statuses.join(", ")
+    error(
+        ErrorKind::InvalidInput,
+        format!(
+            "Invalid status: '{}'\n\
+            \n\
+            Valid statuses are: {}",
+            status,
+            valid_statuses.join(", ")
+        ),
     )
 }
 
 /// Provide helpful error for invalid priority
-#[allow(dead_code)]
 pub fn invalid_priority(priority: &str, valid_priorities: &[String]) -> anyhow::Error {
-    anyhow::anyhow!(
-        "Invalid priority: '{}'\n\
-        \n\
-        Valid priorities are: {}",
-        priority,
-        valid_priorities.join(", ")
+    error(
+        ErrorKind::InvalidInput,
+        format!(
+            "Invalid priority: '{}'\n\
+            \n\
+            Valid priorities are: {}",
+            priority,
+            valid_priorities.join(", ")
+        ),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_classify_sees_through_context() {
+        let err = Err::<(), _>(task_not_found("foo"))
+            .context("Failed to show task")
+            .unwrap_err();
+        assert_eq!(classify(&err), ErrorKind::NotFound);
+
+        let err =
+            anyhow::Error::new(git2::Error::from_str("boom")).context("Not in a git repository");
+        assert_eq!(classify(&err), ErrorKind::Git);
+
+        assert_eq!(classify(&anyhow::anyhow!("plain")), ErrorKind::General);
+    }
+
+    #[test]
+    fn test_json_error_strips_styling() {
+        let value = to_json(&task_not_found("foo"));
+
+        assert_eq!(value["error"]["kind"], "not_found");
+        assert_eq!(value["error"]["exit_code"], 4);
+        let message = value["error"]["message"].as_str().unwrap();
+        assert!(message.starts_with("Task not found: foo"));
+        assert!(!message.contains('\x1b'));
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::Task;
+
+/// Stable JSON view of a task, as printed by `--json`
+///
+/// Field names are snake_case and every field is always present (empty lists
+/// and `null` rather than omitted keys), so scripts don't need to special-case
+/// missing frontmatter.
+#[derive(Debug, Serialize)]
+pub struct TaskJson<'a> {
+    pub id: &'a str,
+    pub slug: &'a str,
+    pub title: &'a str,
+    pub status: &'a str,
+    pub priority: Option<&'a str>,
+    pub tags: &'a [String],
+    pub depends_on: &'a [String],
+    pub blocks: &'a [String],
+    pub commits: &'a [String],
+    pub body: &'a str,
+    pub path: Option<String>,
+}
+
+impl<'a> From<&'a Task> for TaskJson<'a> {
+    fn from(task: &'a Task) -> Self {
+        TaskJson {
+            id: &task.id,
+            slug: &task.slug,
+            title: &task.title,
+            status: &task.status,
+            priority: task.priority.as_deref(),
+            tags: task.tags.as_deref().unwrap_or_default(),
+            depends_on: task.depends_on.as_deref().unwrap_or_default(),
+            blocks: task.blocks.as_deref().unwrap_or_default(),
+            commits: task.commits.as_deref().unwrap_or_default(),
+            body: &task.body,
+            path: task.path.as_ref().map(|p| p.display().to_string()),
+        }
+    }
+}
+
+/// Convert a list of tasks to their JSON views
+pub fn tasks(tasks: &[Task]) -> Vec<TaskJson<'_>> {
+    tasks.iter().map(TaskJson::from).collect()
+}
+
+/// Print a value to stdout as pretty JSON
+pub fn print<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_json_fills_missing_fields() {
+        let mut task = Task::new("Write parser".to_string(), "High".to_string());
+        task.tags = None;
+
+        let value = serde_json::to_value(TaskJson::from(&task)).unwrap();
+
+        assert_eq!(value["title"], "Write parser");
+        assert_eq!(value["status"], "todo");
+        assert_eq!(value["priority"], "High");
+        assert_eq!(value["tags"], serde_json::json!([]));
+        assert_eq!(value["depends_on"], serde_json::json!([]));
+        assert_eq!(value["commits"], serde_json::json!([]));
+        assert!(value["path"].is_null());
+    }
+}
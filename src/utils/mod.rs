@@ -1,4 +1,7 @@
 pub mod errors;
+pub mod json;
 pub mod output;
 
-pub use output::{format_tasks_as_table, priority_badge, status_badge, success, tags, task_id, task_slug};
+pub use output::{
+    format_tasks_as_table, priority_badge, status_badge, success, tags, task_id, task_slug, warning,
+};
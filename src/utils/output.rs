@@ -1,5 +1,5 @@
-use console::style;
 use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table};
+use console::style;
 
 use crate::Task;
 
@@ -21,7 +21,6 @@ pub fn error(msg: &str) {
 }
 
 /// Print a warning message in yellow
-#[allow(dead_code)]
 pub fn warning(msg: &str) {
     println!("{} {}", style("⚠").yellow().bold(), msg);
 }
@@ -1,11 +1,11 @@
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
 
 #[test]
 fn test_cli_help() {
-    let mut cmd = Command::cargo_bin("repo-tasks").unwrap();
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
     cmd.arg("--help")
         .assert()
         .success()
@@ -18,7 +18,7 @@ fn test_cli_help() {
 fn test_init_command() {
     let temp_dir = TempDir::new().unwrap();
 
-    let mut cmd = Command::cargo_bin("repo-tasks").unwrap();
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
     cmd.current_dir(&temp_dir)
         .arg("init")
         .arg("--project-name")
@@ -43,15 +43,14 @@ fn test_list_empty() {
     let temp_dir = TempDir::new().unwrap();
 
     // Initialize
-    Command::cargo_bin("repo-tasks")
-        .unwrap()
+    cargo_bin_cmd!("repo-tasks")
         .current_dir(&temp_dir)
         .arg("init")
         .assert()
         .success();
 
     // List should show no tasks
-    let mut cmd = Command::cargo_bin("repo-tasks").unwrap();
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
     cmd.current_dir(&temp_dir)
         .arg("list")
         .assert()
@@ -64,15 +63,14 @@ fn test_show_nonexistent_task() {
     let temp_dir = TempDir::new().unwrap();
 
     // Initialize
-    Command::cargo_bin("repo-tasks")
-        .unwrap()
+    cargo_bin_cmd!("repo-tasks")
         .current_dir(&temp_dir)
         .arg("init")
         .assert()
         .success();
 
     // Try to show non-existent task
-    let mut cmd = Command::cargo_bin("repo-tasks").unwrap();
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
     cmd.current_dir(&temp_dir)
         .arg("show")
         .arg("nonexistent")
@@ -86,8 +84,7 @@ fn test_move_task() {
     let temp_dir = TempDir::new().unwrap();
 
     // Initialize
-    Command::cargo_bin("repo-tasks")
-        .unwrap()
+    cargo_bin_cmd!("repo-tasks")
         .current_dir(&temp_dir)
         .arg("init")
         .assert()
@@ -97,21 +94,19 @@ fn test_move_task() {
     let task_path = temp_dir
         .path()
         .join(".repo-tasks/tasks/todo/20260108000001-test-task.md");
-    fs::write(
-        &task_path,
-        r#"---
+    let content = r#"---
 ID: "20260108000001"
 Title: Test Task
-Priority: High
+Priority: High  # bumped after review
+Assignee: ada
 ---
 
 Test body
-"#,
-    )
-    .unwrap();
+"#;
+    fs::write(&task_path, content).unwrap();
 
     // Move the task
-    let mut cmd = Command::cargo_bin("repo-tasks").unwrap();
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
     cmd.current_dir(&temp_dir)
         .arg("move")
         .arg("test-task")
@@ -120,12 +115,15 @@ Test body
         .success()
         .stdout(predicate::str::contains("Moved task"));
 
-    // Verify task was moved
+    // Verify task was moved, unchanged
     assert!(!task_path.exists());
-    assert!(temp_dir
-        .path()
-        .join(".repo-tasks/tasks/done/20260108000001-test-task.md")
-        .exists());
+    let moved = fs::read_to_string(
+        temp_dir
+            .path()
+            .join(".repo-tasks/tasks/done/20260108000001-test-task.md"),
+    )
+    .unwrap();
+    assert_eq!(moved, content);
 }
 
 #[test]
@@ -133,8 +131,7 @@ fn test_search() {
     let temp_dir = TempDir::new().unwrap();
 
     // Initialize
-    Command::cargo_bin("repo-tasks")
-        .unwrap()
+    cargo_bin_cmd!("repo-tasks")
         .current_dir(&temp_dir)
         .arg("init")
         .assert()
@@ -157,7 +154,7 @@ This is a unique_search_term that should be found
     .unwrap();
 
     // Search for the unique term
-    let mut cmd = Command::cargo_bin("repo-tasks").unwrap();
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
     cmd.current_dir(&temp_dir)
         .arg("search")
         .arg("unique_search_term")
@@ -171,8 +168,7 @@ fn test_list_with_filter() {
     let temp_dir = TempDir::new().unwrap();
 
     // Initialize
-    Command::cargo_bin("repo-tasks")
-        .unwrap()
+    cargo_bin_cmd!("repo-tasks")
         .current_dir(&temp_dir)
         .arg("init")
         .assert()
@@ -206,7 +202,7 @@ Priority: Low
     .unwrap();
 
     // List with priority filter
-    let mut cmd = Command::cargo_bin("repo-tasks").unwrap();
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
     cmd.current_dir(&temp_dir)
         .arg("list")
         .arg("--priority")
@@ -216,3 +212,1292 @@ Priority: Low
         .stdout(predicate::str::contains("High Priority Task"))
         .stdout(predicate::str::contains("Low Priority Task").not());
 }
+
+#[test]
+fn test_move_ambiguous_reference_fails() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    // Create two tasks sharing a slug prefix
+    for (id, slug) in [
+        ("20260108000001", "test-parser"),
+        ("20260108000002", "test-hooks"),
+    ] {
+        fs::write(
+            temp_dir
+                .path()
+                .join(format!(".repo-tasks/tasks/todo/{}-{}.md", id, slug)),
+            format!("---\nID: \"{}\"\nTitle: {}\n---\n", id, slug),
+        )
+        .unwrap();
+    }
+
+    // Moving by the shared prefix should refuse and list both candidates
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
+    cmd.current_dir(&temp_dir)
+        .arg("move")
+        .arg("test")
+        .arg("done")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Ambiguous task reference"))
+        .stderr(predicate::str::contains("20260108000001"))
+        .stderr(predicate::str::contains("20260108000002"));
+
+    // Nothing should have been moved
+    assert!(temp_dir
+        .path()
+        .join(".repo-tasks/tasks/todo/20260108000001-test-parser.md")
+        .exists());
+    assert!(temp_dir
+        .path()
+        .join(".repo-tasks/tasks/todo/20260108000002-test-hooks.md")
+        .exists());
+}
+
+#[test]
+fn test_graph_mermaid() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    // Create two tasks where one depends on the other
+    fs::write(
+        temp_dir
+            .path()
+            .join(".repo-tasks/tasks/todo/20260108000001-write-parser.md"),
+        "---\nID: \"20260108000001\"\nTitle: Write parser\nPriority: High\n---\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir
+            .path()
+            .join(".repo-tasks/tasks/todo/20260108000002-wire-up-hooks.md"),
+        "---\nID: \"20260108000002\"\nTitle: Wire up hooks\nDependsOn:\n- \"20260108000001\"\n---\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
+    cmd.current_dir(&temp_dir)
+        .arg("graph")
+        .arg("--format")
+        .arg("mermaid")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("flowchart LR"))
+        .stdout(predicate::str::contains(
+            "t20260108000001 --> t20260108000002",
+        ));
+}
+
+#[test]
+fn test_move_blocked_by_unfinished_dependency() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    // Create a task that depends on another unfinished task
+    fs::write(
+        temp_dir
+            .path()
+            .join(".repo-tasks/tasks/todo/20260108000001-write-parser.md"),
+        "---\nID: \"20260108000001\"\nTitle: Write parser\n---\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir
+            .path()
+            .join(".repo-tasks/tasks/todo/20260108000002-wire-up-hooks.md"),
+        "---\nID: \"20260108000002\"\nTitle: Wire up hooks\nDependsOn:\n- \"20260108000001\"\n---\n",
+    )
+    .unwrap();
+
+    // Moving the dependent task is refused and lists the blocker
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
+    cmd.current_dir(&temp_dir)
+        .arg("move")
+        .arg("wire-up-hooks")
+        .arg("done")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unfinished dependency"))
+        .stderr(predicate::str::contains("20260108000001"));
+
+    // Finishing the blocker reports the dependent as unblocked
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
+    cmd.current_dir(&temp_dir)
+        .arg("move")
+        .arg("write-parser")
+        .arg("done")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Now unblocked"))
+        .stdout(predicate::str::contains("Wire up hooks"));
+
+    // Now the dependent task can move
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
+    cmd.current_dir(&temp_dir)
+        .arg("move")
+        .arg("wire-up-hooks")
+        .arg("done")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_move_force_ignores_dependencies() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    fs::write(
+        temp_dir
+            .path()
+            .join(".repo-tasks/tasks/todo/20260108000002-wire-up-hooks.md"),
+        "---\nID: \"20260108000002\"\nTitle: Wire up hooks\nDependsOn:\n- \"20260108000001\"\n---\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir
+            .path()
+            .join(".repo-tasks/tasks/todo/20260108000001-write-parser.md"),
+        "---\nID: \"20260108000001\"\nTitle: Write parser\n---\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("repo-tasks");
+    cmd.current_dir(&temp_dir)
+        .arg("move")
+        .arg("wire-up-hooks")
+        .arg("in-progress")
+        .arg("--force")
+        .assert()
+        .success();
+
+    assert!(temp_dir
+        .path()
+        .join(".repo-tasks/tasks/in-progress/20260108000002-wire-up-hooks.md")
+        .exists());
+}
+
+#[test]
+fn test_next_ranks_ready_tasks() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let todo = temp_dir.path().join(".repo-tasks/tasks/todo");
+    fs::write(
+        todo.join("20260108000001-old-chore.md"),
+        "---\nID: \"20260108000001\"\nTitle: Old chore\nPriority: Low\n---\n",
+    )
+    .unwrap();
+    fs::write(
+        todo.join("20260108000002-urgent-fix.md"),
+        "---\nID: \"20260108000002\"\nTitle: Urgent fix\nPriority: Critical\n---\n",
+    )
+    .unwrap();
+    fs::write(
+        todo.join("20260108000003-blocked-feature.md"),
+        "---\nID: \"20260108000003\"\nTitle: Blocked feature\nPriority: Critical\nDependsOn:\n- \"20260108000001\"\n---\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("ready")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let urgent = stdout.find("Urgent fix").expect("urgent task listed");
+    let chore = stdout.find("Old chore").expect("old task listed");
+    assert!(urgent < chore);
+    assert!(!stdout.contains("Blocked feature"));
+    assert!(stdout.contains("(unblocks 1)"));
+}
+
+#[test]
+fn test_next_start_with_shared_id() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = git2::Repository::init(temp_dir.path()).unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+        .unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    // Two tasks created at the same second, before IDs were checked
+    let todo = temp_dir.path().join(".repo-tasks/tasks/todo");
+    fs::write(
+        todo.join("20260108000001-urgent-fix.md"),
+        "---\nID: \"20260108000001\"\nTitle: Urgent fix\nPriority: Critical\n---\n",
+    )
+    .unwrap();
+    fs::write(
+        todo.join("20260108000001-old-chore.md"),
+        "---\nID: \"20260108000001\"\nTitle: Old chore\nPriority: Low\n---\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("next")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Urgent fix"))
+        .stdout(predicate::str::contains("Old chore"));
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["next", "--start", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let started: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(started["task"]["title"], "Urgent fix");
+    assert_eq!(started["branch"], "20260108000001-urgent-fix");
+    assert!(temp_dir
+        .path()
+        .join(".repo-tasks/tasks/in-progress/20260108000001-urgent-fix.md")
+        .exists());
+    assert!(todo.join("20260108000001-old-chore.md").exists());
+}
+
+#[test]
+fn test_json_output_for_new_and_show() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args([
+            "new",
+            "--title",
+            "Write parser",
+            "--priority",
+            "High",
+            "--json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let created: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(created["task"]["slug"], "write-parser");
+    assert_eq!(created["task"]["priority"], "High");
+    assert_eq!(created["task"]["status"], "todo");
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["--json", "show", "write-parser"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let shown: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(shown["task"]["id"], created["task"]["id"]);
+    assert_eq!(shown["task"]["tags"], serde_json::json!([]));
+}
+
+#[test]
+fn test_update_with_flags() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    for title in ["Write lexer", "Write parser"] {
+        cargo_bin_cmd!("repo-tasks")
+            .current_dir(&temp_dir)
+            .args(["new", "--title", title, "--tags", "cli,draft"])
+            .assert()
+            .success();
+    }
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args([
+            "--json",
+            "update",
+            "write-parser",
+            "--priority",
+            "High",
+            "--add-tag",
+            "parser",
+            "--remove-tag",
+            "draft",
+            "--depends-on",
+            "write-lexer",
+            "--set",
+            "Assignee=ada",
+            "--append-body",
+            "-",
+        ])
+        .write_stdin("Handle CRLF\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let updated: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(updated["updated"], true);
+    assert_eq!(updated["task"]["priority"], "High");
+    assert_eq!(
+        updated["task"]["tags"],
+        serde_json::json!(["cli", "parser"])
+    );
+    assert_eq!(updated["task"]["depends_on"].as_array().unwrap().len(), 1);
+    assert_eq!(updated["task"]["body"], "Handle CRLF");
+
+    let path = temp_dir
+        .path()
+        .join(updated["task"]["path"].as_str().unwrap());
+    assert!(fs::read_to_string(path)
+        .unwrap()
+        .contains("Assignee: ada\n"));
+
+    // The lexer now lists the parser under Blocks
+    let lexer = &updated["linked"][0];
+    assert_eq!(lexer["slug"], "write-lexer");
+    assert_eq!(lexer["blocks"], serde_json::json!([updated["task"]["id"]]));
+    let lexer_path = temp_dir.path().join(lexer["path"].as_str().unwrap());
+    assert!(fs::read_to_string(lexer_path).unwrap().contains(&format!(
+        "Blocks:\n- '{}'",
+        updated["task"]["id"].as_str().unwrap()
+    )));
+
+    // Invalid values are refused
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["update", "write-parser", "--priority", "Urgent"])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("Invalid priority"));
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["update", "write-parser", "--set", "Assignee"])
+        .assert()
+        .code(6);
+
+    // Without flags or a terminal there's nothing to ask
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["update", "write-parser"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("no changes given"));
+}
+
+#[test]
+fn test_json_errors_have_kind_and_exit_code() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Not initialized yet
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["list", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "not_initialized");
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["show", "missing", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    assert!(output.stdout.is_empty());
+
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["kind"], "not_found");
+    assert_eq!(error["error"]["exit_code"], 4);
+}
+
+#[test]
+fn test_mcp_server_over_stdio() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"create_task","arguments":{"title":"Write parser","priority":"High"}}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"list_tasks","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"show_task","arguments":{"task_id":"missing"}}}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"resources/list"}"#,
+        r#"{"jsonrpc":"2.0","id":6,"method":"resources/read","params":{"uri":"task://write-parser"}}"#,
+    ];
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("mcp")
+        .write_stdin(requests.join("\n"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    // One response per request; the notification gets none
+    assert_eq!(responses.len(), 6);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+
+    let created = &responses[1]["result"]["structuredContent"]["task"];
+    assert_eq!(created["slug"], "write-parser");
+
+    let listed = &responses[2]["result"]["structuredContent"]["tasks"];
+    assert_eq!(listed[0]["id"], created["id"]);
+
+    assert_eq!(responses[3]["result"]["isError"], true);
+    assert_eq!(
+        responses[3]["result"]["structuredContent"]["error"]["kind"],
+        "not_found"
+    );
+
+    let resources = responses[4]["result"]["resources"].as_array().unwrap();
+    assert_eq!(resources.len(), 1);
+
+    let text = responses[5]["result"]["contents"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(text.contains("Title: Write parser"));
+}
+
+#[test]
+fn test_config_status_management() {
+    let temp_dir = TempDir::new().unwrap();
+    let tasks_dir = temp_dir.path().join(".repo-tasks/tasks");
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "add-status", "review", "--position", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added status"));
+    assert!(tasks_dir.join("review").exists());
+
+    fs::write(
+        tasks_dir.join("testing/20260108000001-test-task.md"),
+        "---\nID: \"20260108000001\"\nTitle: Test Task\n---\n",
+    )
+    .unwrap();
+
+    // Refuses to drop a status that still has tasks
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "remove-status", "testing"])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("--move-to"));
+    assert!(tasks_dir
+        .join("testing/20260108000001-test-task.md")
+        .exists());
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "remove-status", "testing", "--move-to", "review"])
+        .assert()
+        .success();
+    assert!(!tasks_dir.join("testing").exists());
+    assert!(tasks_dir
+        .join("review/20260108000001-test-task.md")
+        .exists());
+
+    // Renaming carries the directory along
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "rename-status", "review", "qa"])
+        .assert()
+        .success();
+    assert!(tasks_dir.join("qa/20260108000001-test-task.md").exists());
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "get", "statuses", "--json"])
+        .output()
+        .unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        value["value"],
+        serde_json::json!(["todo", "in-progress", "qa", "done"])
+    );
+
+    // Invalid changes never reach the config file
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "set", "closed_statuses", "archived"])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("closed_statuses"));
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "get", "closed_statuses"])
+        .assert()
+        .success()
+        .stdout("done\n");
+}
+
+#[test]
+fn test_new_tasks_follow_configured_status_and_priority() {
+    let temp_dir = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "rename-status", "todo", "backlog"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["config", "remove-priority", "Medium"])
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["new", "--title", "Hello", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["task"]["status"], "backlog");
+    assert_eq!(value["task"]["priority"], "High");
+    assert!(!temp_dir.path().join(".repo-tasks/tasks/todo").exists());
+
+    // The first status is listed by default
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Hello"));
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("doctor")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_schema_and_invalid_config() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["schema", "task"])
+        .output()
+        .unwrap();
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["properties"]["Priority"]["enum"][3], "Critical");
+
+    // Field-level errors instead of a generic parse failure
+    let config_path = temp_dir.path().join(".repo-tasks/config.json");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(
+        &config_path,
+        config.replace("\"auto_commit\": false", "\"auto_commit\": \"no\""),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("list")
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains(
+            "auto_commit: \"no\" is not of type \"boolean\"",
+        ));
+}
+
+#[test]
+fn test_doctor_reports_and_fixes_problems() {
+    let temp_dir = TempDir::new().unwrap();
+    let tasks_dir = temp_dir.path().join(".repo-tasks/tasks");
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+
+    // A file `list` would silently skip, and one with the wrong name
+    fs::write(
+        tasks_dir.join("todo/20260108000001-broken.md"),
+        "---\nTitle: No ID\n---\n",
+    )
+    .unwrap();
+    fs::write(
+        tasks_dir.join("todo/20260108000009-renamed.md"),
+        "---\nID: \"20260108000002\"\nTitle: Renamed\n---\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("doctor")
+        .assert()
+        .code(9)
+        .stdout(predicate::str::contains("\"ID\" is a required property"))
+        .stdout(predicate::str::contains("tasks doctor --fix"));
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["doctor", "--fix", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(9));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["fixed"][0]["kind"], "misnamed_file");
+    assert_eq!(report["fixed"][0]["fix"]["action"], "rename");
+    assert_eq!(report["problems"][0]["kind"], "invalid_frontmatter");
+    assert!(tasks_dir.join("todo/20260108000002-renamed.md").exists());
+}
+
+#[test]
+fn test_hook_runner() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = git2::Repository::init(temp_dir.path()).unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["new", "--title", "Hook task", "--json"])
+        .output()
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = created["task"]["id"].as_str().unwrap().to_string();
+
+    // Staged task files block a regular commit
+    let mut index = repo.index().unwrap();
+    index
+        .add_all([".repo-tasks"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hook", "run", "pre-commit"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            ".repo-tasks/tasks/todo/{}-hook-task.md",
+            id
+        )));
+
+    // A commit closing the task moves it to done
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &format!("Finish it\n\nCloses #{}", id),
+        &tree,
+        &[],
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hook", "run", "post-commit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Moved task {} to done",
+            id
+        )));
+
+    assert!(temp_dir
+        .path()
+        .join(format!(".repo-tasks/tasks/done/{}-hook-task.md", id))
+        .exists());
+
+    // A task that can't be moved is reported and logged, without failing
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Closes #20200101000000",
+        &tree,
+        &[&repo.head().unwrap().peel_to_commit().unwrap()],
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hook", "run", "post-commit"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "post-commit hook failed: Task not found: 20200101000000",
+        ));
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "log", "--json"])
+        .output()
+        .unwrap();
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let events = log["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["task_ids"][0], id.as_str());
+    assert_eq!(events[0]["action"], "moved to done (closes)");
+    assert!(events[0]["error"].is_null());
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "log", "--failures"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Task not found: 20200101000000"))
+        .stdout(predicate::str::contains(&id).not());
+}
+
+#[test]
+fn test_commands_find_repository_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let nested = temp_dir.path().join("src").join("models");
+    fs::create_dir_all(&nested).unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&nested)
+        .args(["new", "--title", "From below"])
+        .assert()
+        .success();
+
+    assert!(!nested.join(".repo-tasks").exists());
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["list", "todo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("From below"));
+
+    // Pointing at the repository from outside it
+    let outside = TempDir::new().unwrap();
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&outside)
+        .arg("--repo")
+        .arg(temp_dir.path())
+        .args(["list", "todo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("From below"));
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&outside)
+        .env("REPO_TASKS_DIR", temp_dir.path().join(".repo-tasks"))
+        .args(["list", "todo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("From below"));
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&outside)
+        .args(["list", "todo"])
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_history_scan() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = git2::Repository::init(temp_dir.path()).unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["new", "--title", "Old task", "--json"])
+        .output()
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = created["task"]["id"].as_str().unwrap().to_string();
+
+    // Commits made before any hook was installed
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let mut parents = Vec::new();
+    let mut commits = Vec::new();
+    for message in [format!("[wip] [{}] Start", id), format!("Closes #{}", id)] {
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let oid = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &message,
+                &tree,
+                &parent_refs,
+            )
+            .unwrap();
+        commits.push(oid.to_string());
+        parents = vec![repo.find_commit(oid).unwrap()];
+    }
+
+    // Without --apply nothing changes
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["history", "scan"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would move:"));
+    assert!(temp_dir
+        .path()
+        .join(format!(".repo-tasks/tasks/todo/{}-old-task.md", id))
+        .exists());
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["history", "scan", "--apply", "--json"])
+        .output()
+        .unwrap();
+    let scan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(scan["commits"], 2);
+    assert_eq!(scan["moves"][0]["to"], "done");
+    assert_eq!(scan["moves"][0]["moved"], true);
+    assert_eq!(scan["last_scanned"], commits[1].as_str());
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["show", &id, "--json"])
+        .output()
+        .unwrap();
+    let shown: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(shown["task"]["status"], "done");
+    assert_eq!(shown["task"]["commits"], serde_json::json!(commits));
+
+    // The next scan starts where the last one stopped
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["history", "scan"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Scanned 0 commit(s)"));
+}
+
+#[test]
+fn test_history_scan_retries_failed_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = git2::Repository::init(temp_dir.path()).unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let mut ids = Vec::new();
+    for title in ["Lexer", "Parser"] {
+        let output = cargo_bin_cmd!("repo-tasks")
+            .current_dir(&temp_dir)
+            .args(["new", "--title", title, "--json"])
+            .output()
+            .unwrap();
+        let created: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        ids.push(created["task"]["id"].as_str().unwrap().to_string());
+    }
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["update", "parser", "--depends-on", "lexer"])
+        .assert()
+        .success();
+
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let message = format!("Closes #{}", ids[1]);
+    repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &[])
+        .unwrap();
+
+    // The parser is blocked by the lexer, so the marker stays put
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["history", "scan", "--apply", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let scan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(scan["moves"][0]["moved"], false);
+    assert!(scan["moves"][0]["error"].is_string());
+    assert_eq!(scan["links"][0]["linked"], true);
+    assert!(scan["last_scanned"].is_null());
+    assert!(!temp_dir.path().join(".repo-tasks/history.json").exists());
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["move", "lexer", "done"])
+        .assert()
+        .success();
+
+    // The next run picks the commit up again
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["history", "scan", "--apply", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let scan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(scan["commits"], 1);
+    assert_eq!(scan["moves"][0]["moved"], true);
+    assert_eq!(scan["links"], serde_json::json!([]));
+    assert!(scan["last_scanned"].is_string());
+}
+
+#[test]
+fn test_hook_policy() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = git2::Repository::init(temp_dir.path()).unwrap();
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["new", "--title", "Policy task", "--json"])
+        .output()
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = created["task"]["id"].as_str().unwrap().to_string();
+
+    let message_file = temp_dir.path().join("COMMIT_EDITMSG");
+    fs::write(
+        &message_file,
+        "Tidy up\n\n# Please enter the commit message\n",
+    )
+    .unwrap();
+
+    // Nothing is enforced until the policy is switched on
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hook", "run", "commit-msg"])
+        .arg(&message_file)
+        .assert()
+        .success();
+
+    for key in [
+        "hook_policy.require_task_reference",
+        "hook_policy.block_unstarted_push",
+    ] {
+        cargo_bin_cmd!("repo-tasks")
+            .current_dir(&temp_dir)
+            .args(["config", "set", key, "true"])
+            .assert()
+            .success();
+    }
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hook", "run", "commit-msg"])
+        .arg(&message_file)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Tidy up\n    - doesn't reference a task",
+        ));
+
+    fs::write(&message_file, format!("[{}] Tidy up\n", id)).unwrap();
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hook", "run", "commit-msg"])
+        .arg(&message_file)
+        .assert()
+        .success();
+
+    // Pushing reports the offending commits and the unstarted task's branch
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let commit = repo
+        .commit(Some("HEAD"), &signature, &signature, "Tidy up", &tree, &[])
+        .unwrap();
+
+    let branch = format!("{}-policy-task", id);
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args([
+            "hook",
+            "run",
+            "pre-push",
+            "origin",
+            "git@example.com:repo.git",
+        ])
+        .write_stdin(format!(
+            "refs/heads/{0} {1} refs/heads/{0} {2}\n",
+            branch,
+            commit,
+            git2::Oid::zero()
+        ))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "{} Tidy up\n    - doesn't reference a task",
+            &commit.to_string()[..7]
+        )))
+        .stderr(predicate::str::contains(format!(
+            "belongs to task {} (Policy task), which is still in todo",
+            id
+        )));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_chain_to_existing_hook() {
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::Command;
+
+    let temp_dir = TempDir::new().unwrap();
+    git2::Repository::init(temp_dir.path()).unwrap();
+    let hooks_dir = temp_dir.path().join(".git/hooks");
+    let hook = hooks_dir.join("post-checkout");
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    // A hook from another tool, which fails
+    let original = "#!/bin/sh\necho \"$@\" > original-ran\nexit 3\n";
+    fs::create_dir_all(&hooks_dir).unwrap();
+    fs::write(&hook, original).unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "install"])
+        .assert()
+        .success();
+
+    assert!(fs::read_to_string(&hook).unwrap().contains("repo-tasks"));
+    assert_eq!(
+        fs::read_to_string(hooks_dir.join("post-checkout.backup")).unwrap(),
+        original
+    );
+
+    // The original runs first, with the same arguments, and its failure is passed on
+    let status = Command::new(&hook)
+        .current_dir(&temp_dir)
+        .args(["a", "b", "1"])
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("original-ran")).unwrap(),
+        "a b 1\n"
+    );
+
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "uninstall"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Restored original post-checkout hook",
+        ));
+
+    assert_eq!(fs::read_to_string(&hook).unwrap(), original);
+    assert!(!hooks_dir.join("post-checkout.backup").exists());
+    assert!(!hooks_dir.join("post-commit").exists());
+}
+
+#[test]
+fn test_hooks_install_respects_hooks_path_and_shared() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = git2::Repository::init(temp_dir.path()).unwrap();
+    let hooks_path = |repo: &git2::Repository| {
+        repo.config()
+            .unwrap()
+            .snapshot()
+            .unwrap()
+            .get_string("core.hooksPath")
+            .ok()
+    };
+
+    // Initialize
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .arg("init")
+        .assert()
+        .success();
+
+    // An existing core.hooksPath is where hooks go
+    repo.config()
+        .unwrap()
+        .set_str("core.hooksPath", "custom-hooks")
+        .unwrap();
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "install", "post-commit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in custom-hooks"))
+        .stdout(predicate::str::contains(
+            "post-commit - Auto-update task status",
+        ))
+        .stdout(predicate::str::contains("pre-commit").not());
+    assert!(temp_dir.path().join("custom-hooks/post-commit").exists());
+    assert!(!temp_dir.path().join(".git/hooks/post-commit").exists());
+
+    // ...and isn't taken over by shared hooks
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "install", "--shared"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("core.hooksPath is already set"));
+
+    repo.config().unwrap().remove("core.hooksPath").unwrap();
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "install", "--shared"])
+        .assert()
+        .success();
+
+    // Shared hooks don't depend on where this clone's binary lives
+    let hook = fs::read_to_string(temp_dir.path().join(".repo-tasks/hooks/post-commit")).unwrap();
+    assert!(hook.contains("TASKS_BIN=$(command -v repo-tasks || command -v tasks)"));
+    assert!(!hook.contains("target"));
+    assert_eq!(hooks_path(&repo).as_deref(), Some(".repo-tasks/hooks"));
+
+    // Uninstalling only opts this clone out...
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "uninstall"])
+        .assert()
+        .success();
+    assert_eq!(hooks_path(&repo), None);
+    assert!(temp_dir
+        .path()
+        .join(".repo-tasks/hooks/post-commit")
+        .exists());
+
+    // ...unless the shared hooks should go too
+    cargo_bin_cmd!("repo-tasks")
+        .current_dir(&temp_dir)
+        .args(["hooks", "uninstall", "--shared"])
+        .assert()
+        .success();
+    assert!(!temp_dir.path().join(".repo-tasks/hooks").exists());
+}